@@ -135,7 +135,6 @@ impl ContextBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
     
     #[test]
     fn test_basic_context_build() {
@@ -144,34 +143,17 @@ mod tests {
             operator_name: "TEST_OPERATOR".to_string(),
             node_type: NodeType::OlapScan,
             plan_node_id: Some(1),
-            parent_plan_node_id: None,
             metrics: OperatorMetrics {
                 rows_returned: Some(1000),
-                input_rows: None,
                 operator_total_time: Some(1000000),
                 operator_total_time_raw: Some("1ms".to_string()),
-                operator_max_time: None,
-                operator_max_time_raw: None,
-                operator_min_time: None,
-                operator_min_time_raw: None,
                 memory_used: Some(0),
-                cpu_time: None,
-                wait_time: None,
+                ..Default::default()
             },
-            children: vec![],
-            depth: 0,
-            is_hotspot: false,
-            hotspot_severity: HotspotSeverity::None,
             fragment_id: Some("Fragment 0".to_string()),
             pipeline_id: Some("Pipeline 0".to_string()),
             time_percentage: Some(50.0),
-            is_most_consuming: false,
-            is_second_most_consuming: false,
-            plan_info: vec![],
-            common_counters: vec![],
-            custom_counters: vec![],
-            unique_metrics: HashMap::new(),
-            table_name: None,
+            ..Default::default()
         };
         
         let profile = Profile {
@@ -183,6 +165,10 @@ mod tests {
             },
             fragments: vec![],
             execution_tree: None,
+            detail_profile: None,
+            physical_plan: None,
+            runtime_filters: Vec::new(),
+            parse_warnings: Vec::new(),
         };
         
         let config = ContextConfig {
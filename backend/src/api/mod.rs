@@ -3,7 +3,8 @@ use serde_json::json;
 use serde::{Serialize, Deserialize};
 use std::sync::Arc;
 use crate::static_files::StaticFiles;
-use crate::{AiDiagnosisService, ProfileComposer, PerformanceBottleneck, OptimizationAdvisor};
+use crate::{AiDiagnosisService, FeClient, HistoryStore, ProfileComposer, PerformanceBottleneck, OptimizationAdvisor};
+use crate::ingest::{ChunkReader, ProfileUpload, UploadReader};
 use crate::config::DefaultSuggestionsConfig;
 
 #[derive(Deserialize)]
@@ -18,6 +19,22 @@ struct AnalyzeResponse {
     data: Option<crate::models::ProfileAnalysisResponse>,
 }
 
+#[derive(Serialize)]
+struct AnalyzeFileResponse {
+    success: bool,
+    error: Option<String>,
+    data: Option<Vec<UploadedProfileAnalysis>>,
+}
+
+/// Analysis of one profile found in an uploaded file. The profile text is not echoed back
+#[derive(Serialize)]
+struct UploadedProfileAnalysis {
+    source: String,
+    success: bool,
+    error: Option<String>,
+    data: Option<crate::models::ProfileAnalysisResponse>,
+}
+
 #[derive(Deserialize)]
 struct DiagnoseNodeRequest {
     profile_text: String,
@@ -36,12 +53,64 @@ struct DiagnoseNodeResponse {
     error: Option<String>,
     suggestion: Option<String>,
     suggestion_source: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    parse_warnings: Vec<crate::models::ParseWarning>,
+}
+
+#[derive(Deserialize)]
+struct CompareRequest {
+    before_profile_text: String,
+    after_profile_text: String,
+}
+
+#[derive(Serialize)]
+struct CompareResponse {
+    success: bool,
+    error: Option<String>,
+    data: Option<crate::models::ProfileComparison>,
+}
+
+#[derive(Deserialize)]
+struct RedactRequest {
+    profile_text: String,
+    #[serde(default)]
+    salt: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RedactResponse {
+    success: bool,
+    error: Option<String>,
+    data: Option<crate::models::RedactedProfile>,
+}
+
+#[derive(Serialize)]
+struct FeQueriesResponse {
+    success: bool,
+    error: Option<String>,
+    data: Option<Vec<crate::models::FeQueryInfo>>,
+}
+
+#[derive(Serialize)]
+struct HistoryListResponse {
+    success: bool,
+    error: Option<String>,
+    data: Option<Vec<crate::models::HistoryEntry>>,
+}
+
+#[derive(Serialize)]
+struct HistoryRecordResponse {
+    success: bool,
+    error: Option<String>,
+    data: Option<crate::models::HistoryRecord>,
 }
 
 #[derive(Clone)]
 struct AppState {
     ai_service: Option<Arc<AiDiagnosisService>>,
     default_config: Arc<DefaultSuggestionsConfig>,
+    fe_client: Option<Arc<FeClient>>,
+    history: Option<Arc<HistoryStore>>,
 }
 
 pub async fn start_server(
@@ -49,10 +118,14 @@ pub async fn start_server(
     port: u16,
     ai_service: Option<Arc<AiDiagnosisService>>,
     default_config: Arc<DefaultSuggestionsConfig>,
+    fe_client: Option<Arc<FeClient>>,
+    history: Option<Arc<HistoryStore>>,
 ) {
     let app_state = Arc::new(AppState {
         ai_service,
         default_config,
+        fe_client,
+        history,
     });
     let cors = warp::cors()
         .allow_any_origin()
@@ -94,11 +167,64 @@ pub async fn start_server(
         .and(state_filter.clone())
         .and_then(handle_diagnose_node);
 
+    // Compare two profiles of the same query
+    let compare = warp::path("api")
+        .and(warp::path("compare"))
+        .and(warp::post())
+        .and(warp::body::content_length_limit(1024 * 1024 * 100))
+        .and(warp::body::json())
+        .and_then(handle_compare_profiles);
+
+    // Redact a profile for sharing
+    let redact = warp::path!("api" / "redact")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(1024 * 1024 * 50))
+        .and(warp::body::json())
+        .and_then(handle_redact_profile);
+
+    // List recent queries of the configured FE
+    let fe_queries = warp::path!("api" / "fe" / "queries")
+        .and(warp::get())
+        .and(state_filter.clone())
+        .and_then(handle_fe_queries);
+
+    // Pull a profile from the configured FE and analyze it
+    let fe_analyze = warp::path!("api" / "fe" / "analyze" / String)
+        .and(warp::post())
+        .and(state_filter.clone())
+        .and_then(handle_fe_analyze);
+
+    // Search stored analyses
+    let history_list = warp::path!("api" / "history")
+        .and(warp::get())
+        .and(warp::query::<crate::models::HistoryFilter>())
+        .and(state_filter.clone())
+        .and_then(handle_history_list);
+
+    // Load a stored analysis
+    let history_get = warp::path!("api" / "history" / String)
+        .and(warp::get())
+        .and(state_filter.clone())
+        .and_then(handle_history_get);
+
+    // Download the raw profile of a stored analysis
+    let history_raw = warp::path!("api" / "history" / String / "raw")
+        .and(warp::get())
+        .and(state_filter.clone())
+        .and_then(handle_history_raw);
+
     // API routes
     let api_routes = health
         .or(analyze_profile_json)
         .or(analyze_profile_file)
-        .or(diagnose_node);
+        .or(diagnose_node)
+        .or(compare)
+        .or(redact)
+        .or(fe_queries)
+        .or(fe_analyze)
+        .or(history_list)
+        .or(history_get)
+        .or(history_raw);
 
     // Static file serving for frontend
     let static_routes = warp::get()
@@ -149,87 +275,256 @@ async fn analyze_profile_with_ai(
     profile_text: &str,
     state: &AppState,
 ) -> Result<crate::models::ProfileAnalysisResponse, String> {
-    // 1. Parse profile
-    let mut composer = ProfileComposer::new();
+    let mut composer = ProfileComposer::lenient();
     let profile = composer.parse(profile_text)
         .map_err(|e| format!("Failed to parse profile: {:?}", e))?;
     
-    // 2. Detect hotspots
-    let mut hotspots = PerformanceBottleneck::analyze(&profile);
-    
-    // 3. Fill suggestions with default config only (skip AI for initial load)
-    OptimizationAdvisor::fill_suggestions(
-        &mut hotspots,
+    // Default suggestions only; AI is used on demand via /api/diagnose-node
+    let result = crate::analyze_parsed_profile_with_defaults(
         &profile,
         state.ai_service.as_deref(),
         &state.default_config,
-        true,  // skip_ai = true for initial analysis
     ).await;
     
-    // 4. Generate conclusion and score
-    let conclusion = OptimizationAdvisor::generate_conclusion(&hotspots, &profile);
-    let suggestions = OptimizationAdvisor::generate_suggestions(&hotspots);
-    let performance_score = OptimizationAdvisor::calculate_performance_score(&hotspots, &profile);
-    let execution_tree = profile.execution_tree.clone();
-    let summary = Some(profile.summary.clone());
+    // Keep the analysis for /api/history; a failed save must not fail the analysis
+    if let Some(ref history) = state.history {
+        if let Err(e) = history.save(profile_text, &profile, &result) {
+            eprintln!("Failed to save analysis of {} to history: {}", profile.summary.query_id, e);
+        }
+    }
     
-    Ok(crate::models::ProfileAnalysisResponse {
-        hotspots,
-        conclusion,
-        suggestions,
-        performance_score,
-        execution_tree,
-        summary,
-    })
+    Ok(result)
 }
 
 async fn handle_analyze_profile_file(
     mut form: warp::multipart::FormData,
     state: Arc<AppState>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let uploads = match read_upload(&mut form).await? {
+        Some(Ok(uploads)) => uploads,
+        result => {
+            let error = match result {
+                Some(Err(err)) => err,
+                _ => "No file provided".to_string(),
+            };
+            return Ok(warp::reply::json(&AnalyzeFileResponse {
+                success: false,
+                error: Some(error),
+                data: None,
+            }));
+        }
+    };
+    
+    let mut results = Vec::with_capacity(uploads.len());
+    for upload in uploads {
+        let (data, error) = match analyze_profile_with_ai(&upload.profile_text, &state).await {
+            Ok(result) => (Some(result), None),
+            Err(err) => (None, Some(err)),
+        };
+        results.push(UploadedProfileAnalysis {
+            source: upload.source,
+            success: data.is_some(),
+            error,
+            data,
+        });
+    }
+    
+    let success = results.iter().any(|r| r.success);
+    let response = AnalyzeFileResponse {
+        success,
+        error: (!success).then(|| "No profile in the file could be analyzed".to_string()),
+        data: Some(results),
+    };
+    Ok(warp::reply::json(&response))
+}
+
+/// Read the profiles of the "file" part. The upload is decompressed and split by a
+/// blocking task while it streams in, instead of being buffered first.
+/// Returns None when the form has no file part
+async fn read_upload(
+    form: &mut warp::multipart::FormData,
+) -> Result<Option<Result<Vec<ProfileUpload>, String>>, warp::Rejection> {
     use futures::TryStreamExt;
     use bytes::Buf;
     
-    let mut profile_text = String::new();
-    
     while let Some(part) = form.try_next().await.map_err(|_| warp::reject::reject())? {
-        if part.name() == "file" {
-            let data = part.stream().try_fold(Vec::new(), |mut acc, chunk| async move {
-                let chunk_bytes = chunk.chunk();
-                acc.extend_from_slice(chunk_bytes);
-                Ok(acc)
-            }).await.map_err(|_| warp::reject::reject())?;
-            
-            profile_text = String::from_utf8(data).map_err(|_| warp::reject::reject())?;
-            break;
+        if part.name() != "file" {
+            continue;
         }
+        let name = part.filename().unwrap_or("upload").to_string();
+        let (sender, receiver) = tokio::sync::mpsc::channel(16);
+        let reader = tokio::task::spawn_blocking(move || {
+            UploadReader::read_profiles(&name, ChunkReader::new(receiver))
+        });
+        
+        let mut stream = part.stream();
+        while let Some(mut chunk) = stream.try_next().await.map_err(|_| warp::reject::reject())? {
+            // The reader stops early on a decoding error; its result explains why
+            if sender.send(chunk.copy_to_bytes(chunk.remaining())).await.is_err() {
+                break;
+            }
+        }
+        drop(sender);
+        
+        let profiles = reader.await
+            .map_err(|e| format!("Failed to read upload: {}", e))
+            .and_then(|result| result);
+        return Ok(Some(profiles));
     }
-    
-    if profile_text.is_empty() {
-        return Ok(warp::reply::json(&json!({
-            "success": false,
-            "error": "No file provided",
-            "data": null
-        })));
-    }
-    
-    match analyze_profile_with_ai(&profile_text, &state).await {
-        Ok(result) => {
-            let response = AnalyzeResponse {
-                success: true,
-                error: None,
-                data: Some(result),
-            };
-            Ok(warp::reply::json(&response))
+    Ok(None)
+}
+
+async fn handle_compare_profiles(
+    req: CompareRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let response = match crate::compare_profiles(&req.before_profile_text, &req.after_profile_text) {
+        Ok(result) => CompareResponse {
+            success: true,
+            error: None,
+            data: Some(result),
+        },
+        Err(err) => CompareResponse {
+            success: false,
+            error: Some(err),
+            data: None,
+        },
+    };
+    Ok(warp::reply::json(&response))
+}
+
+async fn handle_redact_profile(
+    req: RedactRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let response = if req.profile_text.trim().is_empty() {
+        RedactResponse {
+            success: false,
+            error: Some("No profile provided".to_string()),
+            data: None,
         }
-        Err(err) => {
-            let response = AnalyzeResponse {
-                success: false,
-                error: Some(format!("Failed to parse profile: {}", err)),
-                data: None,
-            };
-            Ok(warp::reply::json(&response))
+    } else {
+        RedactResponse {
+            success: true,
+            error: None,
+            data: Some(crate::redact_profile(&req.profile_text, req.salt.as_deref())),
         }
+    };
+    Ok(warp::reply::json(&response))
+}
+
+async fn handle_fe_queries(
+    state: Arc<AppState>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let result = match state.fe_client {
+        Some(ref client) => client.list_queries().await,
+        None => Err("FE connection is not configured".to_string()),
+    };
+    
+    let response = match result {
+        Ok(queries) => FeQueriesResponse {
+            success: true,
+            error: None,
+            data: Some(queries),
+        },
+        Err(err) => FeQueriesResponse {
+            success: false,
+            error: Some(err),
+            data: None,
+        },
+    };
+    Ok(warp::reply::json(&response))
+}
+
+async fn handle_fe_analyze(
+    query_id: String,
+    state: Arc<AppState>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let result = match state.fe_client {
+        Some(ref client) => match client.fetch_profile(&query_id).await {
+            Ok(profile_text) => analyze_profile_with_ai(&profile_text, &state).await,
+            Err(err) => Err(err),
+        },
+        None => Err("FE connection is not configured".to_string()),
+    };
+    
+    let response = match result {
+        Ok(result) => AnalyzeResponse {
+            success: true,
+            error: None,
+            data: Some(result),
+        },
+        Err(err) => AnalyzeResponse {
+            success: false,
+            error: Some(err),
+            data: None,
+        },
+    };
+    Ok(warp::reply::json(&response))
+}
+
+async fn handle_history_list(
+    filter: crate::models::HistoryFilter,
+    state: Arc<AppState>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let result = match state.history {
+        Some(ref history) => history.search(&filter),
+        None => Err("History is disabled".to_string()),
+    };
+    
+    let response = match result {
+        Ok(entries) => HistoryListResponse {
+            success: true,
+            error: None,
+            data: Some(entries),
+        },
+        Err(err) => HistoryListResponse {
+            success: false,
+            error: Some(err),
+            data: None,
+        },
+    };
+    Ok(warp::reply::json(&response))
+}
+
+async fn handle_history_get(
+    query_id: String,
+    state: Arc<AppState>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let result = match state.history {
+        Some(ref history) => history.get(&query_id)
+            .and_then(|record| record.ok_or_else(|| format!("No stored analysis for query {}", query_id))),
+        None => Err("History is disabled".to_string()),
+    };
+    
+    let response = match result {
+        Ok(record) => HistoryRecordResponse {
+            success: true,
+            error: None,
+            data: Some(record),
+        },
+        Err(err) => HistoryRecordResponse {
+            success: false,
+            error: Some(err),
+            data: None,
+        },
+    };
+    Ok(warp::reply::json(&response))
+}
+
+async fn handle_history_raw(
+    query_id: String,
+    state: Arc<AppState>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let Some(ref history) = state.history else {
+        return Err(warp::reject::not_found());
+    };
+    
+    match history.raw_profile(&query_id) {
+        Ok(Some(text)) => Ok(warp::reply::with_header(
+            text,
+            "content-type",
+            "text/plain; charset=utf-8",
+        )),
+        _ => Err(warp::reject::not_found()),
     }
 }
 
@@ -238,12 +533,13 @@ async fn handle_diagnose_node(
     state: Arc<AppState>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     match diagnose_single_node(&req.profile_text, &req.node_id, &req.language, &state).await {
-        Ok((suggestion, source)) => {
+        Ok((suggestion, source, parse_warnings)) => {
             let response = DiagnoseNodeResponse {
                 success: true,
                 error: None,
                 suggestion: Some(suggestion),
                 suggestion_source: Some(source),
+                parse_warnings,
             };
             Ok(warp::reply::json(&response))
         }
@@ -253,20 +549,22 @@ async fn handle_diagnose_node(
                 error: Some(err),
                 suggestion: None,
                 suggestion_source: None,
+                parse_warnings: Vec::new(),
             };
             Ok(warp::reply::json(&response))
         }
     }
 }
 
+/// Suggestion for one node with its source, and the warnings of the lenient parse
 async fn diagnose_single_node(
     profile_text: &str,
     node_id: &str,
     language: &str,
     state: &AppState,
-) -> Result<(String, String), String> {
-    // 1. Parse profile
-    let mut composer = ProfileComposer::new();
+) -> Result<(String, String, Vec<crate::models::ParseWarning>), String> {
+    // 1. Parse profile, leniently like /api/analyze
+    let mut composer = ProfileComposer::lenient();
     let profile = composer.parse(profile_text)
         .map_err(|e| format!("Failed to parse profile: {:?}", e))?;
     
@@ -283,7 +581,7 @@ async fn diagnose_single_node(
         if ai_service.is_enabled() {
             match ai_service.generate_suggestion_with_language(node, &profile, language).await {
                 Ok(suggestion) => {
-                    return Ok((suggestion, "ai".to_string()));
+                    return Ok((suggestion, "ai".to_string(), profile.parse_warnings));
                 }
                 Err(e) => {
                     let error_msg = format!("AI Suggestion failed: {}", e);
@@ -299,11 +597,11 @@ async fn diagnose_single_node(
     let hotspots = PerformanceBottleneck::analyze(&profile);
     if let Some(hotspot) = hotspots.iter().find(|h| h.node_id == node_id) {
         let default_suggestion = OptimizationAdvisor::get_default_suggestion_public(
-            &hotspot.operator_name,
+            hotspot.node_type,
             &hotspot.severity,
             &state.default_config,
         );
-        Ok((default_suggestion, "AI Suggestion is not enabled".to_string()))
+        Ok((default_suggestion, "AI Suggestion is not enabled".to_string(), profile.parse_warnings))
     } else {
         Err(format!("Hotspot for node {} not found", node_id))
     }
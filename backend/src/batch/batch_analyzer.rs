@@ -0,0 +1,252 @@
+use crate::models::*;
+use crate::constants::{batch, file_limits};
+use crate::OptimizationAdvisor;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Score categories in report order
+const SCORE_CATEGORIES: &[&str] = &["Excellent", "Good", "Fair", "Poor", "Critical"];
+
+/// BatchAnalyzer analyzes every profile in a directory and aggregates the results
+pub struct BatchAnalyzer;
+
+impl BatchAnalyzer {
+    /// Analyze all profile files under `dir` (recursively) using up to `jobs` threads
+    pub fn analyze_dir(dir: &Path, jobs: usize) -> Result<BatchReport, String> {
+        let mut files = Vec::new();
+        Self::collect_files(dir, &mut files)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+        files.sort();
+        
+        let results = Self::analyze_files(dir, &files, jobs.max(1));
+        Ok(Self::build_report(results))
+    }
+    
+    /// Collect files with a supported profile extension
+    fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                Self::collect_files(&path, files)?;
+            } else if path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| file_limits::SUPPORTED_EXTENSIONS.contains(&ext))
+            {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Analyze files on worker threads, keeping the input order in the result
+    fn analyze_files(dir: &Path, files: &[PathBuf], jobs: usize) -> Vec<Result<BatchEntry, BatchFailure>> {
+        let next = AtomicUsize::new(0);
+        
+        let mut results: Vec<(usize, Result<BatchEntry, BatchFailure>)> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..jobs.min(files.len()))
+                .map(|_| scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = files.get(index) else {
+                            break;
+                        };
+                        done.push((index, Self::analyze_file(dir, path)));
+                    }
+                    done
+                }))
+                .collect();
+            
+            workers.into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_default())
+                .collect()
+        });
+        
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+    
+    /// Analyze a single file; only the summary of the analysis is kept
+    fn analyze_file(dir: &Path, path: &Path) -> Result<BatchEntry, BatchFailure> {
+        let file = path.strip_prefix(dir).unwrap_or(path).display().to_string();
+        let failure = |error: String| BatchFailure { file: file.clone(), error };
+        
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| failure(format!("Failed to read file: {}", e)))?;
+        
+        // A panic on one malformed profile must not abort the whole batch
+        let result = std::panic::catch_unwind(|| crate::analyze_profile(&text))
+            .map_err(|_| failure("Analyzer panicked on this profile".to_string()))?
+            .map_err(failure)?;
+        
+        let summary = result.summary.unwrap_or_default();
+        Ok(BatchEntry {
+            file,
+            query_id: summary.query_id,
+            total_time_ms: summary.total_time_ms,
+            performance_score: result.performance_score,
+            hotspot_count: result.hotspots.len(),
+            top_hotspot: result.hotspots.first().map(|h| h.operator_name.clone()),
+            hotspot_operators: result.hotspots.iter().map(|h| h.operator_name.clone()).collect(),
+        })
+    }
+    
+    /// Aggregate per-file results into a report
+    pub fn build_report(results: Vec<Result<BatchEntry, BatchFailure>>) -> BatchReport {
+        let total_files = results.len();
+        let (entries, failures): (Vec<_>, Vec<_>) = results.into_iter().partition(|r| r.is_ok());
+        let entries: Vec<BatchEntry> = entries.into_iter().flatten().collect();
+        let failures: Vec<BatchFailure> = failures.into_iter().filter_map(Result::err).collect();
+        
+        let mut slowest_queries: Vec<BatchEntry> = entries.iter()
+            .filter(|e| e.total_time_ms.is_some())
+            .cloned()
+            .collect();
+        slowest_queries.sort_by(|a, b| {
+            b.total_time_ms.partial_cmp(&a.total_time_ms).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        slowest_queries.truncate(batch::MAX_SLOWEST_QUERIES);
+        
+        let score_distribution = SCORE_CATEGORIES.iter()
+            .map(|category| ScoreBucket {
+                category: category.to_string(),
+                count: entries.iter()
+                    .filter(|e| OptimizationAdvisor::get_score_category(e.performance_score) == *category)
+                    .count(),
+            })
+            .collect();
+        
+        BatchReport {
+            total_files,
+            analyzed_count: entries.len(),
+            hotspot_operators: Self::count_hotspot_operators(&entries),
+            slowest_queries,
+            score_distribution,
+            entries,
+            failures,
+        }
+    }
+    
+    /// Count hotspots per operator across all entries, most frequent first
+    fn count_hotspot_operators(entries: &[BatchEntry]) -> Vec<OperatorFrequency> {
+        let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+        for entry in entries {
+            for (i, operator) in entry.hotspot_operators.iter().enumerate() {
+                let count = counts.entry(operator.as_str()).or_default();
+                count.0 += 1;
+                // Count each query once per operator
+                if !entry.hotspot_operators[..i].contains(operator) {
+                    count.1 += 1;
+                }
+            }
+        }
+        
+        let mut frequencies: Vec<OperatorFrequency> = counts.into_iter()
+            .map(|(operator_name, (hotspot_count, query_count))| OperatorFrequency {
+                operator_name: operator_name.to_string(),
+                hotspot_count,
+                query_count,
+            })
+            .collect();
+        frequencies.sort_by(|a, b| {
+            b.hotspot_count.cmp(&a.hotspot_count).then_with(|| a.operator_name.cmp(&b.operator_name))
+        });
+        frequencies
+    }
+    
+    /// Export one CSV row per file, failures included
+    pub fn to_csv(report: &BatchReport) -> String {
+        let mut lines = vec![
+            "file,query_id,status,total_time_ms,performance_score,hotspot_count,top_hotspot,error".to_string(),
+        ];
+        
+        for entry in &report.entries {
+            lines.push([
+                Self::csv_field(&entry.file),
+                Self::csv_field(&entry.query_id),
+                "ok".to_string(),
+                entry.total_time_ms.map(|t| format!("{:.3}", t)).unwrap_or_default(),
+                entry.performance_score.to_string(),
+                entry.hotspot_count.to_string(),
+                Self::csv_field(entry.top_hotspot.as_deref().unwrap_or_default()),
+                String::new(),
+            ].join(","));
+        }
+        
+        for failure in &report.failures {
+            lines.push(format!(
+                "{},,failed,,,,,{}",
+                Self::csv_field(&failure.file), Self::csv_field(&failure.error)
+            ));
+        }
+        
+        lines.join("\n") + "\n"
+    }
+    
+    /// Quote a CSV field when it contains separators, quotes or line breaks
+    fn csv_field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn entry(file: &str, total_time_ms: f64, score: u32, operators: &[&str]) -> Result<BatchEntry, BatchFailure> {
+        Ok(BatchEntry {
+            file: file.to_string(),
+            query_id: format!("q-{}", file),
+            total_time_ms: Some(total_time_ms),
+            performance_score: score,
+            hotspot_count: operators.len(),
+            top_hotspot: operators.first().map(|o| o.to_string()),
+            hotspot_operators: operators.iter().map(|o| o.to_string()).collect(),
+        })
+    }
+    
+    #[test]
+    fn test_build_report() {
+        let report = BatchAnalyzer::build_report(vec![
+            entry("a.txt", 1200.0, 95, &[]),
+            entry("b.txt", 56000.0, 40, &["HASH_JOIN_OPERATOR", "OLAP_SCAN_OPERATOR", "OLAP_SCAN_OPERATOR"]),
+            Err(BatchFailure { file: "c.txt".to_string(), error: "Failed to parse profile".to_string() }),
+            entry("d.txt", 3400.0, 75, &["OLAP_SCAN_OPERATOR"]),
+        ]);
+        
+        assert_eq!(report.total_files, 4);
+        assert_eq!(report.analyzed_count, 3);
+        assert_eq!(report.failures.len(), 1);
+        
+        let slowest: Vec<&str> = report.slowest_queries.iter().map(|e| e.file.as_str()).collect();
+        assert_eq!(slowest, vec!["b.txt", "d.txt", "a.txt"]);
+        
+        assert_eq!(report.hotspot_operators[0], OperatorFrequency {
+            operator_name: "OLAP_SCAN_OPERATOR".to_string(),
+            hotspot_count: 3,
+            query_count: 2,
+        });
+        
+        let counts: Vec<usize> = report.score_distribution.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 1, 0, 1, 0]);
+    }
+    
+    #[test]
+    fn test_to_csv() {
+        let report = BatchAnalyzer::build_report(vec![
+            entry("a,1.txt", 1200.0, 95, &["OLAP_SCAN_OPERATOR"]),
+            Err(BatchFailure { file: "c.txt".to_string(), error: "bad \"profile\"".to_string() }),
+        ]);
+        
+        let csv = BatchAnalyzer::to_csv(&report);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "\"a,1.txt\",\"q-a,1.txt\",ok,1200.000,95,1,OLAP_SCAN_OPERATOR,");
+        assert_eq!(lines[2], "c.txt,,failed,,,,,\"bad \"\"profile\"\"\"");
+    }
+}
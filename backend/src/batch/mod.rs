@@ -0,0 +1,3 @@
+pub mod batch_analyzer;
+
+pub use batch_analyzer::*;
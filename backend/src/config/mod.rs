@@ -57,6 +57,21 @@ pub struct SeveritySuggestions {
     pub low: Vec<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeConfig {
+    pub fe_connection: FeConnectionConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeConnectionConfig {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub user: String,
+    #[serde(default)]
+    pub password: String,
+    pub timeout_seconds: u64,
+}
+
 pub struct ConfigLoader;
 
 impl ConfigLoader {
@@ -123,6 +138,42 @@ impl ConfigLoader {
         Ok(config)
     }
     
+    /// Load FE connection configuration from config/fe_config.yaml
+    /// Environment variable DORIS_FE_PASSWORD will override the password in config file
+    pub fn load_fe_config() -> Result<FeConfig, Box<dyn std::error::Error>> {
+        // Try to find config file in multiple locations
+        let possible_paths = vec![
+            "backend/config/fe_config.yaml",
+            "config/fe_config.yaml",
+            "./fe_config.yaml",
+        ];
+        
+        let mut config_path = None;
+        for path in possible_paths {
+            if Path::new(path).exists() {
+                config_path = Some(path);
+                break;
+            }
+        }
+        
+        let config_content = if let Some(path) = config_path {
+            fs::read_to_string(path)?
+        } else {
+            return Err("FE config file not found".into());
+        };
+        
+        let mut config: FeConfig = serde_yaml::from_str(&config_content)?;
+        
+        // Override password from environment variable if set
+        if let Ok(password) = std::env::var("DORIS_FE_PASSWORD") {
+            if !password.is_empty() {
+                config.fe_connection.password = password;
+            }
+        }
+        
+        Ok(config)
+    }
+    
     /// Get a default AI config for cases where loading fails
     pub fn default_ai_config() -> AiConfig {
         AiConfig {
@@ -158,7 +209,7 @@ mod tests {
     #[test]
     fn test_default_ai_config() {
         let config = ConfigLoader::default_ai_config();
-        assert_eq!(config.ai_diagnosis.enabled, false);
+        assert!(!config.ai_diagnosis.enabled);
         assert_eq!(config.ai_diagnosis.provider, "openai");
     }
 }
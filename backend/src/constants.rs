@@ -3,6 +3,12 @@ pub mod file_limits {
     /// Maximum upload file size (50MB)
     pub const MAX_UPLOAD_SIZE: u64 = 50 * 1024 * 1024;
     
+    /// Maximum decompressed size of an uploaded archive (500MB), against zip bombs
+    pub const MAX_DECOMPRESSED_SIZE: u64 = 500 * 1024 * 1024;
+    
+    /// Maximum number of archives nested in each other, e.g. 2 for a zip inside a zip
+    pub const MAX_ARCHIVE_DEPTH: usize = 2;
+    
     /// Supported file extensions
     pub const SUPPORTED_EXTENSIONS: &[&str] = &["txt", "log", "profile"];
 }
@@ -20,6 +26,202 @@ pub mod thresholds {
     
     /// Time percentage threshold for low severity hotspot
     pub const LOW_TIME_PERCENTAGE: f64 = 5.0;
+    
+    /// Estimate/actual row ratio for critical cardinality misestimation
+    pub const CRITICAL_CARDINALITY_RATIO: f64 = 10_000.0;
+    
+    /// Estimate/actual row ratio for high severity cardinality misestimation
+    pub const HIGH_CARDINALITY_RATIO: f64 = 1_000.0;
+    
+    /// Estimate/actual row ratio for medium severity cardinality misestimation
+    pub const MEDIUM_CARDINALITY_RATIO: f64 = 100.0;
+    
+    /// Minimum rows (estimated or actual) before a misestimation is worth reporting
+    pub const MIN_CARDINALITY_ROWS: f64 = 10_000.0;
+    
+    /// Max/avg ratio across instances for critical data skew
+    pub const CRITICAL_SKEW_RATIO: f64 = 8.0;
+    
+    /// Max/avg ratio across instances for high severity data skew
+    pub const HIGH_SKEW_RATIO: f64 = 4.0;
+    
+    /// Max/avg ratio across instances for medium severity data skew
+    pub const MEDIUM_SKEW_RATIO: f64 = 2.0;
+    
+    /// Minimum max ExecTime (100ms in ns) before skew is worth reporting
+    pub const MIN_SKEW_TIME_NS: i64 = 100_000_000;
+    
+    /// Minimum max row count before skew is worth reporting
+    pub const MIN_SKEW_ROWS: i64 = 100_000;
+    
+    /// Minimum input rows before a runtime filter that filters nothing is reported
+    pub const MIN_RUNTIME_FILTER_INPUT_ROWS: u64 = 100_000;
+    
+    /// Share of total query time for high severity FE planning / scheduling overhead
+    pub const HIGH_PLANNING_PERCENTAGE: f64 = 50.0;
+    
+    /// Share of total query time for medium severity FE planning / scheduling overhead
+    pub const MEDIUM_PLANNING_PERCENTAGE: f64 = 25.0;
+    
+    /// Share of total query time for low severity FE planning / scheduling overhead
+    pub const LOW_PLANNING_PERCENTAGE: f64 = 10.0;
+    
+    /// Minimum FE phase duration (ms) before its overhead is worth reporting
+    pub const MIN_PLANNING_OVERHEAD_MS: f64 = 100.0;
+    
+    /// Largest-BE / average-of-other-BEs ratio for high severity backend imbalance
+    pub const HIGH_BACKEND_IMBALANCE_RATIO: f64 = 3.0;
+    
+    /// Largest-BE / average-of-other-BEs ratio for medium severity backend imbalance
+    pub const MEDIUM_BACKEND_IMBALANCE_RATIO: f64 = 1.5;
+    
+    /// Minimum instances on the busiest BE before instance imbalance is reported
+    pub const MIN_BACKEND_IMBALANCE_INSTANCES: f64 = 8.0;
+    
+    /// Minimum fragment RPC time (ms) on the slowest BE before it is reported
+    pub const MIN_BACKEND_RPC_TIME_MS: f64 = 20.0;
+    
+    /// Share of total load time for high severity tablet writer / close wait time
+    pub const HIGH_LOAD_SINK_PERCENTAGE: f64 = 50.0;
+    
+    /// Share of total load time for medium severity tablet writer / close wait time
+    pub const MEDIUM_LOAD_SINK_PERCENTAGE: f64 = 25.0;
+    
+    /// Minimum tablet writer / close wait time (500ms in ns) before it is reported
+    pub const MIN_LOAD_SINK_TIME_NS: f64 = 500_000_000.0;
+    
+    /// Minimum batches added by a table sink before small batches are reported
+    pub const MIN_LOAD_BATCHES: f64 = 1_000.0;
+    
+    /// Average rows per added batch below which batches are high severity small
+    pub const HIGH_SMALL_BATCH_ROWS: f64 = 10.0;
+    
+    /// Average rows per added batch below which batches are medium severity small
+    pub const MEDIUM_SMALL_BATCH_ROWS: f64 = 100.0;
+    
+    /// Share of query time for a high severity critical path step
+    pub const HIGH_CRITICAL_PATH_PERCENTAGE: f64 = 40.0;
+    
+    /// Share of query time for a medium severity critical path step
+    pub const MEDIUM_CRITICAL_PATH_PERCENTAGE: f64 = 20.0;
+    
+    /// Minimum contribution of a critical path step (100ms in ns) before it is reported
+    pub const MIN_CRITICAL_PATH_STEP_NS: f64 = 100_000_000.0;
+    
+    /// Share of task time spent waiting for a worker thread for a high severity CPU-starved BE
+    pub const HIGH_WAIT_WORKER_PERCENTAGE: f64 = 40.0;
+    
+    /// Share of task time spent waiting for a worker thread for a medium severity CPU-starved BE
+    pub const MEDIUM_WAIT_WORKER_PERCENTAGE: f64 = 20.0;
+    
+    /// Minimum average worker wait per task (50ms in ns) before CPU starvation is reported
+    pub const MIN_WAIT_WORKER_NS: f64 = 50_000_000.0;
+    
+    /// Share of query time a pipeline task is blocked on a dependency for high severity
+    pub const HIGH_DEPENDENCY_WAIT_PERCENTAGE: f64 = 60.0;
+    
+    /// Share of query time a pipeline task is blocked on a dependency for medium severity
+    pub const MEDIUM_DEPENDENCY_WAIT_PERCENTAGE: f64 = 30.0;
+    
+    /// Minimum average dependency wait per task (500ms in ns) before it is reported
+    pub const MIN_DEPENDENCY_WAIT_NS: f64 = 500_000_000.0;
+    
+    /// Share of its own task time a pipeline must be blocked on dependencies before it is reported
+    pub const MIN_DEPENDENCY_WAIT_TASK_PERCENTAGE: f64 = 50.0;
+    
+    /// Operator peak memory summed over instances for high severity (8GB)
+    pub const HIGH_OPERATOR_MEMORY_BYTES: u64 = 8 * 1024 * 1024 * 1024;
+    
+    /// Operator peak memory summed over instances for medium severity (2GB)
+    pub const MEDIUM_OPERATOR_MEMORY_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+    
+    /// Query peak as a share of exec_mem_limit for high severity
+    pub const HIGH_MEM_LIMIT_PERCENTAGE: f64 = 80.0;
+    
+    /// Share of memory reservations failing for high severity
+    pub const HIGH_RESERVE_FAILED_PERCENTAGE: f64 = 10.0;
+    
+    /// Share of query time spent spilling for high severity
+    pub const HIGH_SPILL_TIME_PERCENTAGE: f64 = 30.0;
+    
+    /// Share of query time spent spilling for medium severity
+    pub const MEDIUM_SPILL_TIME_PERCENTAGE: f64 = 10.0;
+    
+    /// Share of query time an exchange sender waits for the RPC buffer queue for high severity
+    pub const HIGH_RPC_BUFFER_WAIT_PERCENTAGE: f64 = 40.0;
+    
+    /// Share of query time an exchange sender waits for the RPC buffer queue for medium severity
+    pub const MEDIUM_RPC_BUFFER_WAIT_PERCENTAGE: f64 = 20.0;
+    
+    /// Minimum RPC buffer wait of the slowest sender (100ms in ns) before it is reported
+    pub const MIN_RPC_BUFFER_WAIT_NS: f64 = 100_000_000.0;
+    
+    /// Slowest exchange RPC as a multiple of the average RPC to count as a long tail
+    pub const RPC_TAIL_RATIO: f64 = 10.0;
+    
+    /// Minimum slowest exchange RPC (200ms in ns) before a long tail is reported
+    pub const MIN_RPC_TAIL_NS: f64 = 200_000_000.0;
+    
+    /// Share of query time of the slowest exchange RPC for high severity
+    pub const HIGH_RPC_TAIL_PERCENTAGE: f64 = 30.0;
+    
+    /// Minimum rows read by an external table scan before its pruning is judged
+    pub const MIN_PRUNING_ROWS: u64 = 10_000_000;
+    
+    /// Share of the rows in the files skipped by row-group statistics or the page
+    /// index below which pruning is considered ineffective
+    pub const LOW_PRUNED_PERCENTAGE: f64 = 10.0;
+    
+    /// Share of the rows read that filters discard afterwards for high severity
+    pub const HIGH_DISCARDED_ROWS_PERCENTAGE: f64 = 99.0;
+    
+    /// Share of the rows read that filters discard afterwards for medium severity
+    pub const MEDIUM_DISCARDED_ROWS_PERCENTAGE: f64 = 90.0;
+    
+    /// Minimum files opened by an external table scan before small files are reported
+    pub const MIN_SMALL_FILE_COUNT: u64 = 100;
+    
+    /// Average rows per file below which files are considered small
+    pub const SMALL_FILE_ROWS: f64 = 100_000.0;
+    
+    /// Average rows per file below which small files are high severity
+    pub const TINY_FILE_ROWS: f64 = 10_000.0;
+    
+    /// Share of empty files among the files opened by a scan to be reported
+    pub const HIGH_EMPTY_FILE_PERCENTAGE: f64 = 10.0;
+    
+    /// Minimum footer lookups (cache hits + reads) before the footer cache is judged
+    pub const MIN_FOOTER_LOOKUPS: u64 = 100;
+    
+    /// Footer cache hit rate below which repeated scans re-read file footers
+    pub const LOW_FOOTER_HIT_PERCENTAGE: f64 = 50.0;
+    
+    /// Share of reader time spent decompressing pages to be reported
+    pub const HIGH_DECOMPRESS_PERCENTAGE: f64 = 30.0;
+    
+    /// Share of reader time spent reading and sorting Iceberg delete files for high severity
+    pub const HIGH_DELETE_TIME_PERCENTAGE: f64 = 30.0;
+    
+    /// Share of reader time spent reading and sorting Iceberg delete files for medium severity
+    pub const MEDIUM_DELETE_TIME_PERCENTAGE: f64 = 10.0;
+    
+    /// Deleted rows as a share of the rows read for medium severity
+    pub const MEDIUM_DELETE_ROWS_PERCENTAGE: f64 = 5.0;
+    
+    /// Share of scanner time slept after object storage throttling (429) for high severity
+    pub const HIGH_THROTTLE_PERCENTAGE: f64 = 20.0;
+    
+    /// Share of scanner time slept after object storage throttling (429) for medium severity
+    pub const MEDIUM_THROTTLE_PERCENTAGE: f64 = 5.0;
+    
+    /// Average object storage GET size below which requests are considered small (256KB)
+    pub const SMALL_REQUEST_BYTES: u64 = 256 * 1024;
+    
+    /// Minimum GET requests of a scan before small requests are reported
+    pub const MIN_SMALL_REQUESTS: u64 = 1000;
+    
+    /// Share of scanner time spent waiting for GET requests to report small requests
+    pub const HIGH_REQUEST_TIME_PERCENTAGE: f64 = 50.0;
 }
 
 /// Performance score thresholds
@@ -37,3 +239,14 @@ pub mod scores {
     pub const POOR: u32 = 30;
 }
 
+/// Batch analysis limits
+pub mod batch {
+    /// Number of slowest queries listed in a batch report
+    pub const MAX_SLOWEST_QUERIES: usize = 10;
+}
+
+/// Profile history store limits
+pub mod history {
+    /// Number of entries returned by a history search without an explicit limit
+    pub const DEFAULT_SEARCH_LIMIT: usize = 100;
+}
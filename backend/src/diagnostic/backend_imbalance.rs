@@ -0,0 +1,213 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::counters;
+
+/// BackendImbalance flags one BE receiving far more instances or splits than
+/// the others, or carrying much slower fragment RPCs, from the per-BE
+/// scheduling data of the Execution Summary
+pub struct BackendImbalance;
+
+/// Per-BE value compared across backends
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BackendMetric {
+    Instances,
+    SplitWeight,
+    RpcLatency,
+    RpcWorkTime,
+}
+
+impl BackendImbalance {
+    /// Analyze a profile and return one hotspot per imbalanced metric
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let backends = &profile.summary.backend_schedules;
+        
+        [BackendMetric::Instances, BackendMetric::SplitWeight, BackendMetric::RpcLatency, BackendMetric::RpcWorkTime]
+            .into_iter()
+            .filter_map(|metric| Self::check_metric(backends, metric))
+            .collect()
+    }
+    
+    /// Compare the largest value to the average of the other BEs
+    fn check_metric(backends: &[BackendSchedule], metric: BackendMetric) -> Option<HotSpot> {
+        let values: Vec<(&str, f64)> = backends.iter()
+            .filter_map(|b| Self::value(b, metric).map(|v| (b.host.as_str(), v)))
+            .collect();
+        if values.len() < 2 {
+            return None;
+        }
+        
+        let (max_idx, &(host, max)) = values.iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+        if max < Self::min_value(metric) {
+            return None;
+        }
+        
+        let others: Vec<f64> = values.iter()
+            .enumerate()
+            .filter(|(i, _)| *i != max_idx)
+            .map(|(_, (_, v))| *v)
+            .collect();
+        let others_avg = others.iter().sum::<f64>() / others.len() as f64;
+        let others_min = others.iter().copied().fold(f64::INFINITY, f64::min);
+        let others_max = others.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let ratio = max / others_avg.max(1.0);
+        
+        let severity = if ratio >= thresholds::HIGH_BACKEND_IMBALANCE_RATIO {
+            HotspotSeverity::High
+        } else if ratio >= thresholds::MEDIUM_BACKEND_IMBALANCE_RATIO {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        let others_range = if others_min == others_max {
+            Self::format_value(metric, others_min)
+        } else {
+            format!("{}-{}", Self::format_value(metric, others_min), Self::format_value(metric, others_max))
+        };
+        
+        Some(HotSpot {
+            node_id: format!("be/{}", host),
+            node_path: format!("BE {}", host),
+            operator_name: Self::label(metric).to_string(),
+            node_type: NodeType::Unknown,
+            severity,
+            description: format!(
+                "BE {} has {} {}, other BEs {} ({:.1}x their average)",
+                host, Self::format_value(metric, max), Self::unit(metric), others_range, ratio
+            ),
+            kind: HotspotKind::BackendImbalance,
+            time_percentage: None,
+            suggestion: Some(Self::generate_suggestion(metric).to_string()),
+            suggestion_source: Some("default".to_string()),
+        })
+    }
+    
+    /// Value of a metric for one BE; RPC times add up both schedule phases
+    fn value(backend: &BackendSchedule, metric: BackendMetric) -> Option<f64> {
+        let phases = || [&backend.rpc_phase1, &backend.rpc_phase2].into_iter().flatten();
+        if matches!(metric, BackendMetric::RpcLatency | BackendMetric::RpcWorkTime) && phases().next().is_none() {
+            return None;
+        }
+        match metric {
+            BackendMetric::Instances => backend.instances_num.map(|n| n as f64),
+            BackendMetric::SplitWeight => backend.split_weight.map(|w| w as f64),
+            BackendMetric::RpcLatency => phases().map(|rpc| Some(rpc.latency_ms())).sum(),
+            BackendMetric::RpcWorkTime => phases().map(|rpc| rpc.work_time_ms).sum(),
+        }
+    }
+    
+    /// Smallest maximum worth reporting, so that small absolute differences are ignored
+    fn min_value(metric: BackendMetric) -> f64 {
+        match metric {
+            BackendMetric::Instances => thresholds::MIN_BACKEND_IMBALANCE_INSTANCES,
+            BackendMetric::SplitWeight => 0.0,
+            BackendMetric::RpcLatency | BackendMetric::RpcWorkTime => thresholds::MIN_BACKEND_RPC_TIME_MS,
+        }
+    }
+    
+    fn label(metric: BackendMetric) -> &'static str {
+        match metric {
+            BackendMetric::Instances => "instance assignment",
+            BackendMetric::SplitWeight => "split assignment",
+            BackendMetric::RpcLatency => "fragment RPC latency",
+            BackendMetric::RpcWorkTime => "fragment RPC work time",
+        }
+    }
+    
+    fn unit(metric: BackendMetric) -> &'static str {
+        match metric {
+            BackendMetric::Instances => "instances",
+            BackendMetric::SplitWeight => "split weight",
+            BackendMetric::RpcLatency => "fragment RPC latency",
+            BackendMetric::RpcWorkTime => "fragment RPC work time",
+        }
+    }
+    
+    fn format_value(metric: BackendMetric, value: f64) -> String {
+        match metric {
+            BackendMetric::Instances | BackendMetric::SplitWeight => format!("{:.0}", value),
+            BackendMetric::RpcLatency | BackendMetric::RpcWorkTime => counters::format_time(value * 1_000_000.0),
+        }
+    }
+    
+    /// Suggest fixes depending on the imbalanced metric
+    fn generate_suggestion(metric: BackendMetric) -> &'static str {
+        match metric {
+            BackendMetric::Instances => {
+                "Fragment instances are concentrated on one BE, which becomes the bottleneck of the query.\n\
+                 Check the tablet / replica distribution of the scanned tables (ADMIN SHOW REPLICA DISTRIBUTION), \
+                 and whether other BEs are not alive, decommissioned or excluded by resource tags"
+            }
+            BackendMetric::SplitWeight => {
+                "External table splits are assigned unevenly across BEs.\n\
+                 Check that all BEs are alive and share the same resource tag, and prefer more, evenly sized files"
+            }
+            BackendMetric::RpcLatency => {
+                "Sending fragments to one BE is much slower than to the others.\n\
+                 Check the network between the FE and this BE, and the load of its brpc threads"
+            }
+            BackendMetric::RpcWorkTime => {
+                "One BE takes much longer to prepare its fragments.\n\
+                 Check its CPU / memory load, and whether heavy loads or compaction run on it"
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn backend(host: &str, instances: u64, latency_ms: f64) -> BackendSchedule {
+        BackendSchedule {
+            host: host.to_string(),
+            instances_num: Some(instances),
+            split_weight: Some(20_000),
+            rpc_phase1: Some(ScheduleRpc {
+                work_time_ms: Some(15.0),
+                queue_time_ms: Some(0.0),
+                latency_to_be_ms: Some(latency_ms),
+                latency_to_fe_ms: Some(-1.0),
+            }),
+            rpc_phase2: None,
+        }
+    }
+    
+    #[test]
+    fn test_instance_imbalance() {
+        let backends = vec![
+            backend("172.20.56.83", 129, 3.0),
+            backend("172.20.56.84", 33, 1.0),
+            backend("172.20.56.85", 32, 2.0),
+        ];
+        
+        let hotspot = BackendImbalance::check_metric(&backends, BackendMetric::Instances).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert_eq!(hotspot.node_path, "BE 172.20.56.83");
+        assert_eq!(
+            hotspot.description,
+            "BE 172.20.56.83 has 129 instances, other BEs 32-33 (4.0x their average)"
+        );
+        
+        assert!(BackendImbalance::check_metric(&backends, BackendMetric::SplitWeight).is_none());
+        assert!(BackendImbalance::check_metric(&backends, BackendMetric::RpcLatency).is_none(), "latencies below the minimum");
+    }
+    
+    #[test]
+    fn test_rpc_latency_imbalance() {
+        let backends = vec![
+            backend("10.0.0.1", 32, 2.4),
+            backend("10.0.0.2", 32, 120.0),
+        ];
+        
+        let hotspot = BackendImbalance::check_metric(&backends, BackendMetric::RpcLatency).unwrap();
+        assert_eq!(hotspot.operator_name, "fragment RPC latency");
+        assert_eq!(
+            hotspot.description,
+            "BE 10.0.0.2 has 120ms fragment RPC latency, other BEs 2ms (50.0x their average)"
+        );
+        assert!(BackendImbalance::check_metric(&backends, BackendMetric::Instances).is_none());
+    }
+}
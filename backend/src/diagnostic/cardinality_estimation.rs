@@ -0,0 +1,235 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::PerformanceBottleneck;
+
+/// CardinalityEstimation compares planner row estimates (`stats=`) with `actualRows=`
+/// in the PhysicalPlan to find nodes whose statistics are badly off
+pub struct CardinalityEstimation;
+
+impl CardinalityEstimation {
+    /// Analyze a profile and return hotspots for misestimated plan nodes
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let Some(ref plan) = profile.physical_plan else {
+            return Vec::new();
+        };
+        
+        plan.nodes.iter()
+            .filter_map(|node| Self::analyze_node(node, plan, profile.execution_tree.as_ref()))
+            .collect()
+    }
+    
+    /// Check a single plan node for cardinality misestimation
+    fn analyze_node(
+        node: &PhysicalPlanNode,
+        plan: &PhysicalPlan,
+        tree: Option<&ExecutionTree>,
+    ) -> Option<HotSpot> {
+        let estimated = node.estimated_rows?;
+        let actual = node.actual_rows? as f64;
+        
+        if estimated.max(actual) < thresholds::MIN_CARDINALITY_ROWS {
+            return None;
+        }
+        
+        let ratio = Self::misestimation_ratio(estimated, actual);
+        
+        // Only report where the error originates, not every ancestor that inherits it
+        let inherited = Self::inherited_ratio(node, plan);
+        if ratio / inherited < thresholds::MEDIUM_CARDINALITY_RATIO {
+            return None;
+        }
+        
+        let severity = Self::determine_severity(ratio);
+        if severity == HotspotSeverity::None {
+            return None;
+        }
+        
+        let exec_node = tree.and_then(|t| {
+            node.execution_node_ids.first()
+                .and_then(|id| t.nodes.iter().find(|n| &n.id == id))
+        });
+        
+        let plan_label = match (node.nereids_id, node.table_name.as_ref()) {
+            (Some(id), _) => format!("{}[{}]", node.name, id),
+            (None, Some(table)) => format!("{}[{}]", node.name, table),
+            (None, None) => node.name.clone(),
+        };
+        
+        let direction = if estimated > actual { "overestimated" } else { "underestimated" };
+        let description = format!(
+            "{} row count {} by {:.0}x: estimated {:.0} rows, actual {} rows",
+            plan_label, direction, ratio, estimated, actual as u64
+        );
+        
+        let tables = Self::collect_tables(node, plan);
+        
+        Some(HotSpot {
+            node_id: exec_node.map(|n| n.id.clone()).unwrap_or_else(|| node.id.clone()),
+            node_path: exec_node
+                .map(PerformanceBottleneck::build_node_path)
+                .unwrap_or_else(|| format!("PhysicalPlan > {}", plan_label)),
+            operator_name: exec_node
+                .map(|n| n.operator_name.clone())
+                .unwrap_or_else(|| node.name.clone()),
+            node_type: exec_node.map(|n| n.node_type).unwrap_or_default(),
+            severity,
+            description,
+            kind: HotspotKind::CardinalityMisestimation,
+            time_percentage: exec_node.and_then(|n| n.time_percentage),
+            suggestion: Some(Self::generate_suggestion(&tables)),
+            suggestion_source: Some("default".to_string()),
+        })
+    }
+    
+    /// Ratio between the larger and the smaller of estimate and actual (>= 1)
+    fn misestimation_ratio(estimated: f64, actual: f64) -> f64 {
+        let low = estimated.min(actual).max(1.0);
+        let high = estimated.max(actual).max(1.0);
+        high / low
+    }
+    
+    /// Largest misestimation ratio among the nearest descendants with actual rows (1.0 if none)
+    fn inherited_ratio(node: &PhysicalPlanNode, plan: &PhysicalPlan) -> f64 {
+        let mut ratio: f64 = 1.0;
+        let mut stack: Vec<&String> = node.children.iter().collect();
+        
+        while let Some(child_id) = stack.pop() {
+            let Some(child) = plan.nodes.iter().find(|n| &n.id == child_id) else {
+                continue;
+            };
+            
+            match (child.estimated_rows, child.actual_rows) {
+                (Some(estimated), Some(actual)) => {
+                    ratio = ratio.max(Self::misestimation_ratio(estimated, actual as f64));
+                }
+                _ => stack.extend(child.children.iter()),
+            }
+        }
+        
+        ratio
+    }
+    
+    /// Determine severity by how many orders of magnitude the estimate is off
+    fn determine_severity(ratio: f64) -> HotspotSeverity {
+        if ratio >= thresholds::CRITICAL_CARDINALITY_RATIO {
+            HotspotSeverity::Critical
+        } else if ratio >= thresholds::HIGH_CARDINALITY_RATIO {
+            HotspotSeverity::High
+        } else if ratio >= thresholds::MEDIUM_CARDINALITY_RATIO {
+            HotspotSeverity::Medium
+        } else {
+            HotspotSeverity::None
+        }
+    }
+    
+    /// Collect the tables scanned under a plan node (the node itself for scans)
+    fn collect_tables(node: &PhysicalPlanNode, plan: &PhysicalPlan) -> Vec<String> {
+        let mut tables = Vec::new();
+        let mut stack = vec![node];
+        
+        while let Some(current) = stack.pop() {
+            if current.table_name.is_some() {
+                let table = current.attributes.iter()
+                    .find(|a| a.key == "qualified")
+                    .map(|a| a.value.clone())
+                    .or_else(|| current.table_name.clone());
+                if let Some(table) = table {
+                    if !tables.contains(&table) {
+                        tables.push(table);
+                    }
+                }
+            }
+            
+            for child_id in current.children.iter().rev() {
+                if let Some(child) = plan.nodes.iter().find(|n| &n.id == child_id) {
+                    stack.push(child);
+                }
+            }
+        }
+        
+        tables
+    }
+    
+    /// Recommend refreshing statistics of the involved tables
+    fn generate_suggestion(tables: &[String]) -> String {
+        let mut lines = vec![
+            "Planner row estimate is off by orders of magnitude. Table statistics are likely missing or stale, which leads to bad join orders and distribution choices".to_string(),
+        ];
+        
+        for table in tables {
+            lines.push(format!("ANALYZE TABLE {} WITH SYNC;", table));
+        }
+        
+        lines.push("Verify the refreshed statistics with SHOW COLUMN STATS <table>".to_string());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn plan_node(id: &str, estimated: Option<f64>, actual: Option<u64>) -> PhysicalPlanNode {
+        PhysicalPlanNode {
+            id: id.to_string(),
+            name: "PhysicalFileScan".to_string(),
+            nereids_id: None,
+            group_id: None,
+            table_name: Some("web_sales".to_string()),
+            estimated_rows: estimated,
+            actual_rows: actual,
+            attributes: vec![MetricItem {
+                key: "qualified".to_string(),
+                value: "iceberg.tpcds.web_sales".to_string(),
+                counter: CounterValue::text("iceberg.tpcds.web_sales"),
+                children: vec![],
+            }],
+            children: vec![],
+            depth: 0,
+            execution_node_ids: vec![],
+        }
+    }
+    
+    #[test]
+    fn test_detect_overestimated_scan() {
+        let plan = PhysicalPlan {
+            nodes: vec![plan_node("plan-0", Some(720_000_376.0), Some(183750))],
+        };
+        
+        let hotspot = CardinalityEstimation::analyze_node(&plan.nodes[0], &plan, None).unwrap();
+        assert_eq!(hotspot.kind, HotspotKind::CardinalityMisestimation);
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert!(hotspot.description.contains("overestimated"));
+        assert!(hotspot.suggestion.unwrap().contains("ANALYZE TABLE iceberg.tpcds.web_sales"));
+    }
+    
+    #[test]
+    fn test_skip_inherited_misestimation() {
+        let mut join = plan_node("plan-0", Some(720_000_376.0), Some(183750));
+        join.name = "PhysicalHashJoin".to_string();
+        join.nereids_id = Some(1854);
+        join.table_name = None;
+        join.children = vec!["plan-1".to_string()];
+        let plan = PhysicalPlan {
+            nodes: vec![join, plan_node("plan-1", Some(720_000_376.0), Some(183750))],
+        };
+        
+        assert!(CardinalityEstimation::analyze_node(&plan.nodes[0], &plan, None).is_none());
+        assert!(CardinalityEstimation::analyze_node(&plan.nodes[1], &plan, None).is_some());
+    }
+    
+    #[test]
+    fn test_ignore_accurate_or_small_estimates() {
+        let plan = PhysicalPlan {
+            nodes: vec![
+                plan_node("plan-0", Some(200_000.0), Some(183750)),
+                plan_node("plan-1", Some(1.0), Some(500)),
+                plan_node("plan-2", Some(750.0), None),
+            ],
+        };
+        
+        for node in &plan.nodes {
+            assert!(CardinalityEstimation::analyze_node(node, &plan, None).is_none());
+        }
+    }
+}
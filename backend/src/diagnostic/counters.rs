@@ -0,0 +1,280 @@
+use crate::models::*;
+use crate::diagnostic::PerformanceBottleneck;
+use crate::parser::ValueParser;
+
+/// A counter over all instances of an operator
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stat {
+    pub sum: f64,
+    pub avg: f64,
+    pub max: f64,
+}
+
+/// Counters of one operator: the MergedProfile leaves out most exchange and
+/// scanner counters at the default profile level, so they are also looked up
+/// in the DetailProfile
+pub(crate) struct OperatorCounters<'a> {
+    pub node: &'a ExecutionTreeNode,
+    pub tasks: Vec<&'a TaskOperator>,
+}
+
+impl<'a> OperatorCounters<'a> {
+    /// Counters of a tree node and the PipelineTask operators of the same fragment
+    /// and name that `same_operator` accepts
+    pub fn new(
+        task_operators: &[(&str, &'a TaskOperator)],
+        node: &'a ExecutionTreeNode,
+        same_operator: impl Fn(&TaskOperator) -> bool,
+    ) -> Self {
+        let tasks = task_operators.iter()
+            .filter(|(fragment_id, op)| {
+                node.fragment_id.as_deref() == Some(*fragment_id)
+                    && op.operator_name == node.operator_name
+                    && same_operator(op)
+            })
+            .map(|(_, op)| *op)
+            .collect();
+        OperatorCounters { node, tasks }
+    }
+    
+    pub fn stat(&self, key: &str) -> Option<Stat> {
+        if let Some(counter) = find_counter(&self.node.common_counters, key)
+            .or_else(|| find_counter(&self.node.custom_counters, key))
+        {
+            let avg = counter.average()?;
+            return Some(Stat {
+                sum: counter.total().unwrap_or(avg),
+                avg,
+                max: counter.maximum().unwrap_or(avg),
+            });
+        }
+        
+        let values: Vec<f64> = self.tasks.iter()
+            .filter_map(|op| {
+                find_counter(&op.common_counters, key)
+                    .or_else(|| find_counter(&op.custom_counters, key))
+            })
+            .filter_map(CounterValue::total)
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        let sum: f64 = values.iter().sum();
+        Some(Stat {
+            sum,
+            avg: sum / values.len() as f64,
+            max: values.iter().cloned().fold(0.0, f64::max),
+        })
+    }
+    
+    pub fn sum(&self, key: &str) -> Option<u64> {
+        self.stat(key).map(|s| s.sum as u64)
+    }
+    
+    pub fn max(&self, key: &str) -> Option<u64> {
+        self.stat(key).map(|s| s.max as u64)
+    }
+    
+    /// Whether the operator has a counter or counter group, e.g. ParquetReader
+    pub fn has(&self, key: &str) -> bool {
+        let has = |items: &[MetricItem]| find_item(items, key).is_some();
+        has(&self.node.custom_counters) || self.tasks.iter().any(|op| has(&op.custom_counters))
+    }
+}
+
+/// All PipelineTask operators of the DetailProfile with their fragment id
+pub(crate) fn task_operators(profile: &Profile) -> Vec<(&str, &TaskOperator)> {
+    profile.detail_profile.iter()
+        .flat_map(|d| &d.fragments)
+        .flat_map(|f| {
+            f.backends.iter()
+                .flat_map(|b| &b.pipelines)
+                .flat_map(|p| &p.tasks)
+                .flat_map(|t| &t.operators)
+                .map(move |op| (f.id.as_str(), op))
+        })
+        .collect()
+}
+
+/// Find a counter, also among nested counters such as WaitForRpcBufferQueue
+/// under WaitForDependencyTime
+pub(crate) fn find_counter<'a>(items: &'a [MetricItem], key: &str) -> Option<&'a CounterValue> {
+    find_item(items, key).map(|item| &item.counter)
+}
+
+fn find_item<'a>(items: &'a [MetricItem], key: &str) -> Option<&'a MetricItem> {
+    items.iter().find_map(|item| {
+        if item.key == key {
+            Some(item)
+        } else {
+            find_item(&item.children, key)
+        }
+    })
+}
+
+/// Find a counter of a tree node, common counters first
+pub(crate) fn node_counter<'a>(node: &'a ExecutionTreeNode, key: &str) -> Option<&'a CounterValue> {
+    find_counter(&node.common_counters, key).or_else(|| find_counter(&node.custom_counters, key))
+}
+
+/// Largest value of the counters whose key `matches`, also among nested counters
+pub(crate) fn max_matching(
+    items: &[MetricItem],
+    matches: &impl Fn(&str) -> bool,
+    value: fn(&CounterValue) -> Option<f64>,
+) -> f64 {
+    items.iter()
+        .map(|item| {
+            let own = if matches(&item.key) { value(&item.counter).unwrap_or(0.0) } else { 0.0 };
+            own.max(max_matching(&item.children, matches, value))
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Hotspot of a tree node with a default suggestion
+pub(crate) fn hotspot(
+    node: &ExecutionTreeNode,
+    kind: HotspotKind,
+    severity: HotspotSeverity,
+    description: String,
+    suggestion: impl Into<String>,
+) -> HotSpot {
+    HotSpot {
+        node_id: node.id.clone(),
+        node_path: PerformanceBottleneck::build_node_path(node),
+        operator_name: node.operator_name.clone(),
+        node_type: node.node_type,
+        severity,
+        description,
+        kind,
+        time_percentage: node.time_percentage,
+        suggestion: Some(suggestion.into()),
+        suggestion_source: Some("default".to_string()),
+    }
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    ValueParser::format_bytes(bytes as f64)
+}
+
+pub(crate) fn format_time(ns: f64) -> String {
+    if ns >= 1_000_000_000.0 {
+        format!("{:.2}s", ns / 1_000_000_000.0)
+    } else if ns >= 1_000_000.0 {
+        format!("{:.0}ms", ns / 1_000_000.0)
+    } else {
+        format!("{:.0}us", ns / 1_000.0)
+    }
+}
+
+/// Builders for the profiles, tree nodes and counters of the diagnostic tests
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::models::*;
+    use crate::parser::ValueParser;
+    
+    pub(crate) fn item(key: &str, value: &str, children: Vec<MetricItem>) -> MetricItem {
+        MetricItem {
+            key: key.to_string(),
+            value: value.to_string(),
+            counter: ValueParser::parse_counter(value),
+            children,
+        }
+    }
+    
+    pub(crate) fn items(counters: &[(&str, &str)]) -> Vec<MetricItem> {
+        counters.iter().map(|(key, value)| item(key, value, vec![])).collect()
+    }
+    
+    /// Operator of Fragment `fragment` / Pipeline `pipeline` whose id ends in `suffix`
+    pub(crate) fn node(fragment: u32, pipeline: u32, suffix: &str, operator_name: &str, node_type: NodeType) -> ExecutionTreeNode {
+        ExecutionTreeNode {
+            id: format!("Fragment {}-Pipeline {}-{}", fragment, pipeline, suffix),
+            operator_name: operator_name.to_string(),
+            node_type,
+            fragment_id: Some(format!("Fragment {}", fragment)),
+            pipeline_id: Some(format!("Pipeline {}", pipeline)),
+            ..Default::default()
+        }
+    }
+    
+    /// Profile with the given summary whose execution tree is rooted at the first node
+    pub(crate) fn profile(nodes: Vec<ExecutionTreeNode>, summary: ProfileSummary) -> Profile {
+        Profile {
+            summary,
+            fragments: vec![],
+            execution_tree: nodes.first().cloned().map(|root| ExecutionTree { root, nodes }),
+            detail_profile: None,
+            physical_plan: None,
+            runtime_filters: Vec::new(),
+            parse_warnings: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::fixtures::{item, node};
+    
+    fn sink(custom_counters: Vec<MetricItem>) -> ExecutionTreeNode {
+        ExecutionTreeNode {
+            custom_counters,
+            ..node(3, 0, "dest17", "DATA_STREAM_SINK_OPERATOR", NodeType::DataStreamSink)
+        }
+    }
+    
+    fn task(dest_id: i32, bytes: &str, rpc_max: &str) -> TaskOperator {
+        TaskOperator {
+            operator_name: "DATA_STREAM_SINK_OPERATOR".to_string(),
+            plan_node_id: -1,
+            nereids_id: None,
+            dest_id: Some(dest_id),
+            exec_time: None,
+            rows_produced: None,
+            input_rows: None,
+            memory_peak: None,
+            common_counters: vec![],
+            custom_counters: vec![
+                item("BytesSent", bytes, vec![]),
+                item("RpcMaxTime", rpc_max, vec![]),
+            ],
+        }
+    }
+    
+    #[test]
+    fn test_counters_from_detail_tasks() {
+        let tasks = [task(17, "1.00 KB", "2.000ms"), task(17, "3.00 KB", "6.000ms"), task(19, "8.00 KB", "9.000ms")];
+        let task_operators = vec![
+            ("Fragment 3", &tasks[0]),
+            ("Fragment 3", &tasks[1]),
+            ("Fragment 3", &tasks[2]),
+            ("Fragment 4", &tasks[0]),
+        ];
+        let node = sink(vec![]);
+        let counters = OperatorCounters::new(&task_operators, &node, |op| op.dest_id == Some(17));
+        
+        assert_eq!(counters.tasks.len(), 2);
+        assert_eq!(counters.sum("BytesSent"), Some(4096));
+        assert_eq!(counters.max("RpcMaxTime"), Some(6_000_000));
+        assert_eq!(counters.stat("RpcMaxTime").unwrap().avg, 4_000_000.0);
+        assert!(counters.stat("CompressTime").is_none());
+    }
+    
+    #[test]
+    fn test_merged_counters_first() {
+        let tasks = [task(17, "1.00 KB", "2.000ms")];
+        let node = sink(vec![
+            item("BytesSent", "sum 10.00 KB, avg 5.00 KB, max 6.00 KB, min 4.00 KB", vec![]),
+            item("ParquetReader", "0ns", vec![item("FilteredRowsByGroup", "1.024K (1024)", vec![])]),
+        ]);
+        let counters = OperatorCounters::new(&[("Fragment 3", &tasks[0])], &node, |_| true);
+        
+        assert_eq!(counters.sum("BytesSent"), Some(10 * 1024));
+        assert_eq!(counters.max("BytesSent"), Some(6 * 1024));
+        // Nested counters are found as well
+        assert_eq!(counters.sum("FilteredRowsByGroup"), Some(1024));
+        assert!(counters.has("ParquetReader"));
+        assert!(!counters.has("OrcReader"));
+    }
+}
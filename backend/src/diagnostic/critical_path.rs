@@ -0,0 +1,293 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::counters;
+use std::collections::HashMap;
+
+/// CriticalPathAnalysis finds the chain of operators that determined the
+/// wall-clock latency of a query.
+///
+/// A node finishes `ExecTime` after the later of its slowest input and the end of
+/// its own waits (WaitForDependency / WaitForData / DataArrivalWaitTime), all taken
+/// from the slowest instance. Parallel inputs are not added up: only the slowest
+/// one is on the path. Waits longer than the slowest input are work the tree does
+/// not show, e.g. scanner threads or the network, and are charged to the waiting node.
+pub struct CriticalPathAnalysis;
+
+impl CriticalPathAnalysis {
+    /// Compute the critical path from the root of the execution tree
+    pub fn compute(profile: &Profile) -> Option<CriticalPath> {
+        let tree = profile.execution_tree.as_ref()?;
+        let index: HashMap<&str, usize> = tree.nodes.iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.as_str(), i))
+            .collect();
+        let root = *index.get(tree.root.id.as_str())?;
+        
+        let mut latencies = vec![None; tree.nodes.len()];
+        let mut visiting = vec![false; tree.nodes.len()];
+        let latency_ns = Self::latency(&tree.nodes, &index, root, &mut latencies, &mut visiting);
+        
+        let mut steps = Vec::new();
+        let mut on_path = vec![false; tree.nodes.len()];
+        let mut current = Some(root);
+        while let Some(idx) = current {
+            on_path[idx] = true;
+            let node = &tree.nodes[idx];
+            
+            // Continue with the slowest input not already on the path
+            let next = node.children.iter()
+                .filter_map(|id| index.get(id.as_str()).copied())
+                .filter(|&child| !on_path[child])
+                .max_by(|&a, &b| {
+                    let a = latencies[a].unwrap_or(0.0);
+                    let b = latencies[b].unwrap_or(0.0);
+                    a.total_cmp(&b)
+                });
+            let input_ns = next.and_then(|child| latencies[child]).unwrap_or(0.0);
+            
+            let exec_ns = Self::exec_time(node);
+            let wait_ns = (Self::wait_time(node) - input_ns).max(0.0);
+            steps.push(CriticalPathStep {
+                node_id: node.id.clone(),
+                operator_name: node.operator_name.clone(),
+                node_type: node.node_type,
+                fragment_id: node.fragment_id.clone(),
+                pipeline_id: node.pipeline_id.clone(),
+                exec_time_ns: exec_ns as u64,
+                wait_time_ns: wait_ns as u64,
+                contribution_ns: (exec_ns + wait_ns) as u64,
+                percentage: 0.0,
+            });
+            current = next;
+        }
+        
+        let total_ns = profile.summary.total_time_ms
+            .map(|ms| ms * 1_000_000.0)
+            .filter(|t| *t > 0.0)
+            .unwrap_or(latency_ns);
+        if total_ns > 0.0 {
+            for step in steps.iter_mut() {
+                step.percentage = (step.contribution_ns as f64 / total_ns * 100.0).min(100.0);
+            }
+        }
+        
+        Some(CriticalPath {
+            latency_ns: latency_ns as u64,
+            steps,
+        })
+    }
+    
+    /// Analyze a profile and return hotspots for critical path steps taking a large share of the query
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let (Some(tree), Some(path)) = (profile.execution_tree.as_ref(), Self::compute(profile)) else {
+            return Vec::new();
+        };
+        
+        path.steps.iter()
+            .filter_map(|step| {
+                let node = tree.nodes.iter().find(|n| n.id == step.node_id)?;
+                Self::analyze_step(node, step)
+            })
+            .collect()
+    }
+    
+    fn analyze_step(node: &ExecutionTreeNode, step: &CriticalPathStep) -> Option<HotSpot> {
+        if (step.contribution_ns as f64) < thresholds::MIN_CRITICAL_PATH_STEP_NS {
+            return None;
+        }
+        let severity = if step.percentage >= thresholds::HIGH_CRITICAL_PATH_PERCENTAGE {
+            HotspotSeverity::High
+        } else if step.percentage >= thresholds::MEDIUM_CRITICAL_PATH_PERCENTAGE {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        let waiting = step.wait_time_ns > step.exec_time_ns;
+        let description = format!(
+            "{} adds {} ({:.1}% of the query) to the critical path: {} executing, {} waiting on {}",
+            node.operator_name,
+            counters::format_time(step.contribution_ns as f64),
+            step.percentage,
+            counters::format_time(step.exec_time_ns as f64),
+            counters::format_time(step.wait_time_ns as f64),
+            Self::wait_source(node.node_type),
+        );
+        
+        Some(HotSpot {
+            time_percentage: Some(step.percentage),
+            ..counters::hotspot(
+                node,
+                HotspotKind::CriticalPath,
+                severity,
+                description,
+                Self::generate_suggestion(node.node_type, waiting),
+            )
+        })
+    }
+    
+    /// Latency of a node: its ExecTime after the later of its slowest input and its own waits
+    fn latency(
+        nodes: &[ExecutionTreeNode],
+        index: &HashMap<&str, usize>,
+        idx: usize,
+        latencies: &mut [Option<f64>],
+        visiting: &mut [bool],
+    ) -> f64 {
+        if let Some(latency) = latencies[idx] {
+            return latency;
+        }
+        // Connections are heuristic; never follow a cycle back into the path
+        if visiting[idx] {
+            return 0.0;
+        }
+        visiting[idx] = true;
+        
+        let node = &nodes[idx];
+        let input_ns = node.children.iter()
+            .filter_map(|id| index.get(id.as_str()).copied())
+            .map(|child| Self::latency(nodes, index, child, latencies, visiting))
+            .fold(0.0, f64::max);
+        let latency = Self::exec_time(node) + input_ns.max(Self::wait_time(node));
+        
+        visiting[idx] = false;
+        latencies[idx] = Some(latency);
+        latency
+    }
+    
+    /// ExecTime of the slowest instance
+    fn exec_time(node: &ExecutionTreeNode) -> f64 {
+        node.metrics.operator_max_time
+            .or(node.metrics.operator_total_time)
+            .unwrap_or(0) as f64
+    }
+    
+    /// Longest dependency or data wait of the slowest instance, e.g.
+    /// WaitForDependency[HASH_JOIN_BUILD_DEPENDENCY]Time, WaitForData0 of an exchange
+    /// or DataArrivalWaitTime of a legacy exchange node
+    fn wait_time(node: &ExecutionTreeNode) -> f64 {
+        let wait = |items| counters::max_matching(items, &Self::is_wait_counter, CounterValue::maximum);
+        wait(&node.common_counters).max(wait(&node.custom_counters))
+    }
+    
+    /// Counters of the time an operator is blocked on upstream data or a dependency
+    pub fn is_wait_counter(key: &str) -> bool {
+        key.starts_with("WaitForDependency") || key.starts_with("WaitForData") || key == "DataArrivalWaitTime"
+    }
+    
+    fn wait_source(node_type: NodeType) -> &'static str {
+        if node_type.is_exchange() {
+            "data from other fragments"
+        } else if node_type.is_scan() {
+            "scanners"
+        } else {
+            "dependencies"
+        }
+    }
+    
+    fn generate_suggestion(node_type: NodeType, waiting: bool) -> String {
+        let lines: &[&str] = if !waiting {
+            &[
+                "This operator's own execution is on the critical path",
+                "Reduce the rows reaching it with earlier filters, or raise parallel_pipeline_task_num when it runs with few instances",
+            ]
+        } else if node_type.is_exchange() {
+            &[
+                "The critical path waits on data sent by another fragment",
+                "Check the sending DATA_STREAM_SINK and its fragment for slow operators, RPC latency or a skewed shuffle",
+            ]
+        } else if node_type.is_scan() {
+            &[
+                "The critical path waits on scanners reading the table",
+                "Read less data (partition pruning, filters pushed to storage), or check storage and remote IO latency of the scan",
+            ]
+        } else {
+            &[
+                "The critical path waits on a dependency of this operator, e.g. a hash table build or a runtime filter",
+                "Speed up the build side (smaller build table, broadcast vs shuffle) or check runtime filter wait time",
+            ]
+        };
+        
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::counters::fixtures::{self, item};
+    
+    fn node(id: &str, node_type: NodeType, exec_ms: u64, wait: Option<&str>, children: &[&str]) -> ExecutionTreeNode {
+        let custom_counters = wait.map(|value| item(
+            "WaitForDependencyTime",
+            "avg 0ns, max 0ns, min 0ns",
+            vec![item("WaitForData0", value, vec![])],
+        ));
+        
+        ExecutionTreeNode {
+            id: id.to_string(),
+            metrics: OperatorMetrics {
+                operator_max_time: Some(exec_ms * 1_000_000),
+                ..Default::default()
+            },
+            children: children.iter().map(|c| c.to_string()).collect(),
+            custom_counters: custom_counters.into_iter().collect(),
+            ..fixtures::node(0, 0, id, &format!("{}_OPERATOR", id.to_uppercase()), node_type)
+        }
+    }
+    
+    fn profile(nodes: Vec<ExecutionTreeNode>, total_time_ms: f64) -> Profile {
+        fixtures::profile(nodes, ProfileSummary {
+            total_time_ms: Some(total_time_ms),
+            ..Default::default()
+        })
+    }
+    
+    #[test]
+    fn test_slowest_branch_is_on_the_path() {
+        // The join waits for both inputs; only the slower build side counts
+        let profile = profile(vec![
+            node("result_sink", NodeType::ResultSink, 1, None, &["hash_join"]),
+            node("hash_join", NodeType::HashJoin, 200, None, &["probe_scan", "exchange"]),
+            node("probe_scan", NodeType::OlapScan, 300, None, &[]),
+            node("exchange", NodeType::Exchange, 5, Some("avg 700.000ms, max 900.000ms, min 500.000ms"), &["data_stream_sink"]),
+            node("data_stream_sink", NodeType::DataStreamSink, 100, None, &["build_scan"]),
+            node("build_scan", NodeType::OlapScan, 400, None, &[]),
+        ], 1_200.0);
+        
+        let path = CriticalPathAnalysis::compute(&profile).unwrap();
+        let ids: Vec<&str> = path.steps.iter().map(|s| s.node_id.as_str()).collect();
+        assert_eq!(ids, ["result_sink", "hash_join", "exchange", "data_stream_sink", "build_scan"]);
+        
+        // The exchange waited 900ms on a sender that needed 500ms: 400ms are not in the tree
+        let exchange = &path.steps[2];
+        assert_eq!(exchange.wait_time_ns, 400_000_000);
+        assert_eq!(exchange.contribution_ns, 405_000_000);
+        assert_eq!(path.latency_ns, 1_106_000_000);
+        assert_eq!(path.steps.iter().map(|s| s.contribution_ns).sum::<u64>(), path.latency_ns);
+        
+        let hotspots = CriticalPathAnalysis::analyze(&profile);
+        let exchange_hotspot = hotspots.iter().find(|h| h.node_id == "exchange").unwrap();
+        assert_eq!(exchange_hotspot.kind, HotspotKind::CriticalPath);
+        assert_eq!(exchange_hotspot.severity, HotspotSeverity::Medium);
+        assert!(exchange_hotspot.description.contains("adds 405ms (33.8% of the query)"));
+        assert!(exchange_hotspot.description.contains("waiting on data from other fragments"));
+        assert!(exchange_hotspot.suggestion.as_ref().unwrap().contains("DATA_STREAM_SINK"));
+        
+        let build_scan = hotspots.iter().find(|h| h.node_id == "build_scan").unwrap();
+        assert!(build_scan.suggestion.as_ref().unwrap().contains("own execution"));
+        assert!(!hotspots.iter().any(|h| h.node_id == "probe_scan"), "parallel branch is off the path");
+    }
+    
+    #[test]
+    fn test_cycle_does_not_loop() {
+        let profile = profile(vec![
+            node("a", NodeType::ResultSink, 10, None, &["b"]),
+            node("b", NodeType::Sort, 20, None, &["a"]),
+        ], 100.0);
+        
+        let path = CriticalPathAnalysis::compute(&profile).unwrap();
+        assert_eq!(path.steps.len(), 2);
+        assert_eq!(path.latency_ns, 30_000_000);
+    }
+}
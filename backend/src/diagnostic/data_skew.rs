@@ -0,0 +1,201 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::counters;
+
+/// Counters checked for skew across instances
+const SKEW_METRICS: &[&str] = &["ExecTime", "RowsProduced", "InputRows", "ScanRows"];
+
+/// Max/avg spread of one aggregated counter
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkewMetric {
+    pub metric: String,
+    pub max: i64,
+    pub avg: i64,
+    pub min: Option<i64>,
+    pub ratio: f64,
+}
+
+/// DataSkew uses the avg/max/min aggregated counters of the MergedProfile
+/// to find operators whose instances process very uneven amounts of work
+pub struct DataSkew;
+
+impl DataSkew {
+    /// Analyze a profile and return hotspots for skewed operators
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let Some(ref tree) = profile.execution_tree else {
+            return Vec::new();
+        };
+        
+        tree.nodes.iter()
+            .filter_map(Self::analyze_node)
+            .collect()
+    }
+    
+    /// Compute max/avg ratios of the skew-relevant counters of a node
+    pub fn compute_node_skew(node: &ExecutionTreeNode) -> Vec<SkewMetric> {
+        SKEW_METRICS.iter()
+            .filter_map(|metric| {
+                let counter = counters::node_counter(node, metric)?;
+                let (max, avg) = (counter.max?, counter.avg?);
+                if avg <= 0.0 {
+                    return None;
+                }
+                
+                Some(SkewMetric {
+                    metric: metric.to_string(),
+                    max: max as i64,
+                    avg: avg as i64,
+                    min: counter.min.map(|m| m as i64),
+                    ratio: max / avg,
+                })
+            })
+            .collect()
+    }
+    
+    /// Check a single node for skew
+    fn analyze_node(node: &ExecutionTreeNode) -> Option<HotSpot> {
+        let skewed: Vec<SkewMetric> = Self::compute_node_skew(node)
+            .into_iter()
+            .filter(|m| Self::is_significant(m) && m.ratio >= thresholds::MEDIUM_SKEW_RATIO)
+            .collect();
+        
+        let max_ratio = skewed.iter().map(|m| m.ratio).fold(0.0, f64::max);
+        let severity = Self::determine_severity(max_ratio);
+        if severity == HotspotSeverity::None {
+            return None;
+        }
+        
+        let details: Vec<String> = skewed.iter()
+            .map(|m| format!(
+                "{} max/avg {:.1}x (max {}, avg {})",
+                m.metric, m.ratio, Self::format_value(&m.metric, m.max), Self::format_value(&m.metric, m.avg)
+            ))
+            .collect();
+        
+        Some(counters::hotspot(
+            node,
+            HotspotKind::DataSkew,
+            severity,
+            format!("{} instances are skewed: {}", node.operator_name, details.join("; ")),
+            Self::generate_suggestion(node),
+        ))
+    }
+    
+    /// Ignore spreads on counters too small to matter
+    fn is_significant(metric: &SkewMetric) -> bool {
+        if metric.metric == "ExecTime" {
+            metric.max >= thresholds::MIN_SKEW_TIME_NS
+        } else {
+            metric.max >= thresholds::MIN_SKEW_ROWS
+        }
+    }
+    
+    /// Determine severity by the largest max/avg ratio
+    fn determine_severity(ratio: f64) -> HotspotSeverity {
+        if ratio >= thresholds::CRITICAL_SKEW_RATIO {
+            HotspotSeverity::Critical
+        } else if ratio >= thresholds::HIGH_SKEW_RATIO {
+            HotspotSeverity::High
+        } else if ratio >= thresholds::MEDIUM_SKEW_RATIO {
+            HotspotSeverity::Medium
+        } else {
+            HotspotSeverity::None
+        }
+    }
+    
+    /// Format a counter value for the description
+    fn format_value(metric: &str, value: i64) -> String {
+        if metric == "ExecTime" {
+            counters::format_time(value as f64)
+        } else {
+            value.to_string()
+        }
+    }
+    
+    /// Suggest how to even out the distribution depending on the operator
+    fn generate_suggestion(node: &ExecutionTreeNode) -> String {
+        let lines: &[&str] = if node.node_type.is_scan() {
+            &[
+                "Scan instances read very different amounts of data; tablets or file splits are unevenly sized",
+                "Choose a higher-cardinality bucket key (or more buckets) so data spreads evenly across tablets",
+                "For external tables, check for very large files or partitions that cannot be split",
+            ]
+        } else if node.node_type.is_join() {
+            &[
+                "Join instances receive very different row counts; the join keys are likely skewed (hot keys or NULLs)",
+                "Filter or handle hot/NULL keys separately, or switch distribution with a [broadcast] / [shuffle] hint",
+                "If tables are colocated/bucket-shuffled on a skewed key, consider a more uniform bucket key",
+            ]
+        } else if node.node_type.is_aggregate() {
+            &[
+                "Aggregation instances receive very different row counts; the GROUP BY keys are skewed",
+                "Make sure two-phase aggregation is used so partial aggregation reduces hot keys before the shuffle",
+                "Consider a more uniform bucket key when the table is bucketed by the grouping column",
+            ]
+        } else {
+            &[
+                "Operator instances process very uneven amounts of data",
+                "Check the bucket keys of the source tables and the shuffle keys of upstream exchanges for hot values",
+            ]
+        };
+        
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::counters::fixtures::{items, node};
+    
+    fn create_node(common_counters: Vec<(&str, &str)>) -> ExecutionTreeNode {
+        ExecutionTreeNode {
+            plan_node_id: Some(7),
+            common_counters: items(&common_counters),
+            ..node(1, 0, "id7", "HASH_JOIN_OPERATOR", NodeType::HashJoin)
+        }
+    }
+    
+    #[test]
+    fn test_compute_node_skew() {
+        let node = create_node(vec![
+            ("ExecTime", "avg 683.359ms, max 807.567ms, min 544.247ms"),
+            ("RowsProduced", "sum 183.75K (183750), avg 3.828K (3828), max 4.278K (4278), min 3.452K (3452)"),
+        ]);
+        
+        let skew = DataSkew::compute_node_skew(&node);
+        assert_eq!(skew.len(), 2);
+        assert_eq!(skew[1].metric, "RowsProduced");
+        assert_eq!(skew[1].max, 4278);
+        assert_eq!(skew[1].avg, 3828);
+        assert!((skew[0].ratio - 807.567 / 683.359).abs() < 0.001);
+    }
+    
+    #[test]
+    fn test_detect_skewed_join() {
+        let node = create_node(vec![
+            ("ExecTime", "avg 100.000ms, max 1sec200ms, min 10.000ms"),
+            ("InputRows", "sum 4.8M (4800000), avg 100.0K (100000), max 1.0M (1000000), min 1.0K (1000)"),
+        ]);
+        
+        let hotspot = DataSkew::analyze_node(&node).unwrap();
+        assert_eq!(hotspot.kind, HotspotKind::DataSkew);
+        assert_eq!(hotspot.severity, HotspotSeverity::Critical);
+        assert!(hotspot.description.contains("InputRows max/avg 10.0x"));
+        assert!(hotspot.suggestion.unwrap().contains("join keys"));
+    }
+    
+    #[test]
+    fn test_ignore_even_or_small_counters() {
+        let even = create_node(vec![
+            ("ExecTime", "avg 683.359ms, max 807.567ms, min 544.247ms"),
+        ]);
+        assert!(DataSkew::analyze_node(&even).is_none());
+        
+        let small = create_node(vec![
+            ("ExecTime", "avg 10.000us, max 95.241us, min 1.000us"),
+            ("RowsProduced", "sum 48, avg 1, max 48, min 0"),
+        ]);
+        assert!(DataSkew::analyze_node(&small).is_none());
+    }
+}
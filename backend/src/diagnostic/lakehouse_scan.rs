@@ -0,0 +1,430 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::counters::{self, OperatorCounters};
+
+/// LakehouseScanAnalysis reads the Parquet / ORC / Iceberg reader counters of
+/// FILE_SCAN_OPERATOR and reports how well row groups and pages were pruned,
+/// small or empty files, footer cache misses, decompression and delete files
+pub struct LakehouseScanAnalysis;
+
+impl LakehouseScanAnalysis {
+    /// Collect the reader counters of every external table scan
+    pub fn compute(profile: &Profile) -> Option<LakehouseScanModel> {
+        let tree = profile.execution_tree.as_ref()?;
+        let task_operators = counters::task_operators(profile);
+        
+        let scans: Vec<LakehouseScan> = tree.nodes.iter()
+            .filter(|n| n.node_type == NodeType::FileScan)
+            .filter_map(|node| {
+                let plan_node_id = node.plan_node_id?;
+                let counters = OperatorCounters::new(&task_operators, node, |op| op.plan_node_id == plan_node_id);
+                Self::scan(&counters)
+            })
+            .collect();
+        
+        if scans.is_empty() {
+            return None;
+        }
+        Some(LakehouseScanModel { scans })
+    }
+    
+    /// Analyze a profile for external table scans that read more than they need
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let (Some(model), Some(tree)) = (Self::compute(profile), profile.execution_tree.as_ref()) else {
+            return Vec::new();
+        };
+        
+        let mut hotspots = Vec::new();
+        for scan in &model.scans {
+            let Some(node) = tree.nodes.iter().find(|n| n.id == scan.node_id) else {
+                continue;
+            };
+            hotspots.extend(Self::check_pruning(node, scan));
+            hotspots.extend(Self::check_small_files(node, scan));
+            hotspots.extend(Self::check_footer_cache(node, scan));
+            hotspots.extend(Self::check_decompression(node, scan));
+            hotspots.extend(Self::check_delete_files(node, scan));
+        }
+        hotspots
+    }
+    
+    /// Most rows are discarded by filters after being read, although row-group
+    /// statistics and the page index skipped almost nothing
+    fn check_pruning(node: &ExecutionTreeNode, scan: &LakehouseScan) -> Option<HotSpot> {
+        let raw_rows = scan.raw_rows_read?;
+        let rows_produced = scan.rows_produced?;
+        let pruned = scan.pruned_percentage.unwrap_or(0.0);
+        if raw_rows < thresholds::MIN_PRUNING_ROWS || pruned >= thresholds::LOW_PRUNED_PERCENTAGE {
+            return None;
+        }
+        let kept = rows_produced.min(raw_rows) as f64 / raw_rows as f64 * 100.0;
+        let severity = if 100.0 - kept >= thresholds::HIGH_DISCARDED_ROWS_PERCENTAGE {
+            HotspotSeverity::High
+        } else if 100.0 - kept >= thresholds::MEDIUM_DISCARDED_ROWS_PERCENTAGE {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        let description = format!(
+            "{} read {} rows but kept {} ({:.2}%); row-group statistics and the page index skipped only {:.1}% of the rows in the files",
+            Self::scan_label(scan),
+            raw_rows,
+            rows_produced,
+            kept,
+            pruned,
+        );
+        
+        let mut suggestion = vec![
+            "Predicates and runtime filters discard most rows only after they are read and decoded.",
+            "Sort or cluster the table by the filtered columns (e.g. an Iceberg sort order applied by rewrite_data_files) so row-group min/max statistics can skip data.",
+            "Partition the table by a commonly filtered column so whole files are pruned during planning.",
+        ];
+        if scan.format.as_deref() == Some("parquet") && scan.page_index_read_calls.unwrap_or(0) == 0 {
+            suggestion.push("No page index was read: write Parquet files with column and offset indexes to also skip pages inside row groups.");
+        }
+        
+        Some(counters::hotspot(node, HotspotKind::LakehouseScan, severity, description, suggestion.join("\n")))
+    }
+    
+    /// Many files with few rows each, or many empty files: per-file overhead
+    /// (open, footer, remote requests) dominates
+    fn check_small_files(node: &ExecutionTreeNode, scan: &LakehouseScan) -> Option<HotSpot> {
+        let files = scan.files.filter(|f| *f >= thresholds::MIN_SMALL_FILE_COUNT)?;
+        let rows = scan.raw_rows_read.unwrap_or(0)
+            + scan.filtered_rows_by_group.unwrap_or(0)
+            + scan.filtered_rows_by_page.unwrap_or(0);
+        let rows_per_file = rows as f64 / files as f64;
+        let empty_files = scan.empty_files.unwrap_or(0);
+        let empty_pct = empty_files as f64 / files as f64 * 100.0;
+        
+        let severity = if rows_per_file < thresholds::TINY_FILE_ROWS {
+            HotspotSeverity::High
+        } else if rows_per_file < thresholds::SMALL_FILE_ROWS || empty_pct >= thresholds::HIGH_EMPTY_FILE_PERCENTAGE {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        let mut description = format!(
+            "{} opened {} files with {:.0} rows on average",
+            Self::scan_label(scan),
+            files,
+            rows_per_file,
+        );
+        if let Some(bytes) = scan.file_read_bytes {
+            description.push_str(&format!(" and {} read per file", counters::format_bytes(bytes / files)));
+        }
+        if empty_files > 0 {
+            description.push_str(&format!("; {} files ({:.1}%) were empty", empty_files, empty_pct));
+        }
+        
+        Some(counters::hotspot(
+            node,
+            HotspotKind::LakehouseScan,
+            severity,
+            description,
+            "Every file costs an open, a footer read and at least one storage request regardless of its size.\n\
+             Compact the table into fewer, larger files (e.g. Iceberg rewrite_data_files) and have the writers produce larger files.\n\
+             Expire old snapshots and drop the empty files left behind by writers".to_string(),
+        ))
+    }
+    
+    /// Footers read from storage instead of the file meta cache
+    fn check_footer_cache(node: &ExecutionTreeNode, scan: &LakehouseScan) -> Option<HotSpot> {
+        let reads = scan.footer_read_calls?;
+        let lookups = reads + scan.footer_hit_cache.unwrap_or(0);
+        let hit_pct = scan.footer_hit_percentage?;
+        if lookups < thresholds::MIN_FOOTER_LOOKUPS || hit_pct >= thresholds::LOW_FOOTER_HIT_PERCENTAGE {
+            return None;
+        }
+        
+        let mut description = format!(
+            "{} read {} of {} file footers from storage ({:.1}% hit the file meta cache)",
+            Self::scan_label(scan),
+            reads,
+            lookups,
+            hit_pct,
+        );
+        if let Some(parse_ns) = scan.parse_footer_time_ns {
+            description.push_str(&format!("; parsing footers took {}", counters::format_time(parse_ns as f64)));
+        }
+        
+        Some(counters::hotspot(
+            node,
+            HotspotKind::LakehouseScan,
+            HotspotSeverity::Medium,
+            description,
+            "Footers are cached after their first read, so the first run of a query always misses.\n\
+             If repeated queries keep missing, increase the BE file meta cache (max_external_file_meta_cache_num) or scan fewer files per query".to_string(),
+        ))
+    }
+    
+    /// Page decompression taking a large share of the reader time
+    fn check_decompression(node: &ExecutionTreeNode, scan: &LakehouseScan) -> Option<HotSpot> {
+        let decompress_ns = scan.decompress_time_ns? as f64;
+        let reader_ns = scan.reader_time_ns.filter(|t| *t > 0)? as f64;
+        let pct = (decompress_ns / reader_ns * 100.0).min(100.0);
+        if pct < thresholds::HIGH_DECOMPRESS_PERCENTAGE {
+            return None;
+        }
+        
+        let description = format!(
+            "{} spent {} ({:.1}% of its reader time) decompressing pages",
+            Self::scan_label(scan),
+            counters::format_time(decompress_ns),
+            pct,
+        );
+        
+        Some(counters::hotspot(
+            node,
+            HotspotKind::LakehouseScan,
+            HotspotSeverity::Medium,
+            description,
+            "The files use an expensive codec such as gzip or a high zstd level.\n\
+             Rewrite the files with snappy, lz4 or a lower compression level, or give the scan more scanner threads if the BEs have idle CPU".to_string(),
+        ))
+    }
+    
+    /// Iceberg merge-on-read delete files applied on every scan
+    fn check_delete_files(node: &ExecutionTreeNode, scan: &LakehouseScan) -> Option<HotSpot> {
+        let delete_files = scan.delete_files.filter(|f| *f > 0)?;
+        let delete_rows = scan.delete_rows.unwrap_or(0);
+        let rows_pct = scan.raw_rows_read.filter(|r| *r > 0)
+            .map(|raw| delete_rows as f64 / raw as f64 * 100.0);
+        let time_pct = match (scan.delete_time_ns, scan.reader_time_ns) {
+            (Some(delete_ns), Some(reader_ns)) if reader_ns > 0 => Some((delete_ns as f64 / reader_ns as f64 * 100.0).min(100.0)),
+            _ => None,
+        };
+        
+        let severity = if time_pct.is_some_and(|pct| pct >= thresholds::HIGH_DELETE_TIME_PERCENTAGE) {
+            HotspotSeverity::High
+        } else if time_pct.is_some_and(|pct| pct >= thresholds::MEDIUM_DELETE_TIME_PERCENTAGE)
+            || rows_pct.is_some_and(|pct| pct >= thresholds::MEDIUM_DELETE_ROWS_PERCENTAGE)
+        {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        let mut description = format!(
+            "{} applied {} Iceberg delete files with {} deleted rows",
+            Self::scan_label(scan),
+            delete_files,
+            delete_rows,
+        );
+        if let Some(pct) = rows_pct {
+            description.push_str(&format!(" ({:.1}% of the rows read)", pct));
+        }
+        if let (Some(delete_ns), Some(pct)) = (scan.delete_time_ns, time_pct) {
+            description.push_str(&format!("; reading and sorting them took {} ({:.1}% of the reader time)", counters::format_time(delete_ns as f64), pct));
+        }
+        
+        Some(counters::hotspot(
+            node,
+            HotspotKind::LakehouseScan,
+            severity,
+            description,
+            "Merge-on-read deletes are applied on every scan until the data files are rewritten.\n\
+             Run Iceberg compaction (rewrite_data_files, rewrite_position_delete_files) regularly to fold delete files into the data files.\n\
+             For tables with frequent updates, consider copy-on-write mode".to_string(),
+        ))
+    }
+    
+    fn scan(counters: &OperatorCounters) -> Option<LakehouseScan> {
+        let node = counters.node;
+        let files = counters.sum("FileNumber").or_else(|| counters.sum("FileNum"));
+        let raw_rows_read = counters.sum("RawRowsRead");
+        // Merged-only profiles of older versions carry no reader counters
+        if files.is_none() && raw_rows_read.is_none() {
+            return None;
+        }
+        
+        let filtered_rows_by_group = counters.sum("FilteredRowsByGroup");
+        let filtered_rows_by_page = counters.sum("FilteredRowsByPage");
+        let pruned_percentage = match (filtered_rows_by_group, filtered_rows_by_page) {
+            (None, None) => None,
+            (group, page) => {
+                let pruned = group.unwrap_or(0) + page.unwrap_or(0);
+                let total = pruned + raw_rows_read.unwrap_or(0);
+                (total > 0).then(|| pruned as f64 / total as f64 * 100.0)
+            }
+        };
+        
+        let footer_hit_cache = counters.sum("FileFooterHitCache");
+        let footer_read_calls = counters.sum("FileFooterReadCalls");
+        let footer_hit_percentage = match (footer_hit_cache, footer_read_calls) {
+            (Some(hits), Some(reads)) if hits + reads > 0 => Some(hits as f64 / (hits + reads) as f64 * 100.0),
+            _ => None,
+        };
+        
+        let delete_time_ns = match (counters.sum("DeleteFileReadTime"), counters.sum("DeleteRowsSortTime")) {
+            (None, None) => None,
+            (read, sort) => Some(read.unwrap_or(0) + sort.unwrap_or(0)),
+        };
+        
+        let format = if counters.has("ParquetReader") {
+            Some("parquet".to_string())
+        } else if counters.has("OrcReader") {
+            Some("orc".to_string())
+        } else {
+            None
+        };
+        
+        Some(LakehouseScan {
+            node_id: node.id.clone(),
+            table_name: node.table_name.clone(),
+            fragment_id: node.fragment_id.clone(),
+            format,
+            files,
+            empty_files: counters.sum("EmptyFileNum"),
+            file_read_bytes: counters.sum("FileReadBytes"),
+            raw_rows_read,
+            rows_produced: counters.sum("RowsProduced"),
+            filtered_rows_by_group,
+            filtered_rows_by_page,
+            read_row_groups: counters.sum("ReadGroups"),
+            filtered_row_groups: counters.sum("FilteredGroups"),
+            pruned_percentage,
+            page_index_read_calls: counters.sum("PageIndexReadCalls"),
+            footer_hit_cache,
+            footer_read_calls,
+            footer_hit_percentage,
+            reader_time_ns: counters.sum("FileScannerGetBlockTime"),
+            row_group_filter_time_ns: counters.sum("RowGroupFilterTime"),
+            parse_footer_time_ns: counters.sum("ParseFooterTime"),
+            decompress_time_ns: counters.sum("DecompressTime"),
+            delete_files: counters.sum("NumDeleteFiles"),
+            delete_rows: counters.sum("NumDeleteRows"),
+            delete_time_ns,
+        })
+    }
+    
+    fn scan_label(scan: &LakehouseScan) -> String {
+        match scan.table_name {
+            Some(ref table) => format!("The scan of {}", table),
+            None => "The file scan".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::counters::fixtures::node;
+    
+    fn file_scan() -> ExecutionTreeNode {
+        ExecutionTreeNode {
+            plan_node_id: Some(20),
+            time_percentage: Some(40.0),
+            table_name: Some("orders".to_string()),
+            ..node(1, 4, "id20", "FILE_SCAN_OPERATOR", NodeType::FileScan)
+        }
+    }
+    
+    fn scan() -> LakehouseScan {
+        LakehouseScan {
+            node_id: "Fragment 1-Pipeline 4-id20".to_string(),
+            table_name: Some("orders".to_string()),
+            format: Some("parquet".to_string()),
+            files: Some(200),
+            empty_files: Some(0),
+            file_read_bytes: Some(200 * 64 * 1024 * 1024),
+            raw_rows_read: Some(100_000_000),
+            rows_produced: Some(5_000_000),
+            filtered_rows_by_group: Some(0),
+            filtered_rows_by_page: Some(0),
+            pruned_percentage: Some(0.0),
+            page_index_read_calls: Some(0),
+            footer_hit_cache: Some(200),
+            footer_read_calls: Some(0),
+            footer_hit_percentage: Some(100.0),
+            reader_time_ns: Some(100_000_000_000),
+            decompress_time_ns: Some(5_000_000_000),
+            delete_files: Some(0),
+            delete_rows: Some(0),
+            delete_time_ns: Some(0),
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn test_ineffective_pruning() {
+        let hotspot = LakehouseScanAnalysis::check_pruning(&file_scan(), &scan()).unwrap();
+        assert_eq!(hotspot.kind, HotspotKind::LakehouseScan);
+        assert_eq!(hotspot.severity, HotspotSeverity::Medium);
+        assert_eq!(
+            hotspot.description,
+            "The scan of orders read 100000000 rows but kept 5000000 (5.00%); row-group statistics and the page index skipped only 0.0% of the rows in the files"
+        );
+        assert!(hotspot.suggestion.unwrap().contains("No page index was read"));
+        
+        let selective = LakehouseScan { rows_produced: Some(50_000), page_index_read_calls: Some(400), ..scan() };
+        let hotspot = LakehouseScanAnalysis::check_pruning(&file_scan(), &selective).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert!(!hotspot.suggestion.unwrap().contains("No page index was read"));
+        
+        let pruned = LakehouseScan { filtered_rows_by_group: Some(40_000_000), pruned_percentage: Some(28.6), ..scan() };
+        assert!(LakehouseScanAnalysis::check_pruning(&file_scan(), &pruned).is_none());
+    }
+    
+    #[test]
+    fn test_small_and_empty_files() {
+        assert!(LakehouseScanAnalysis::check_small_files(&file_scan(), &scan()).is_none());
+        
+        let tiny = LakehouseScan { files: Some(20_000), file_read_bytes: Some(20_000 * 1024), ..scan() };
+        let hotspot = LakehouseScanAnalysis::check_small_files(&file_scan(), &tiny).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert_eq!(hotspot.description, "The scan of orders opened 20000 files with 5000 rows on average and 1.00 KB read per file");
+        
+        let empty = LakehouseScan { empty_files: Some(50), ..scan() };
+        let hotspot = LakehouseScanAnalysis::check_small_files(&file_scan(), &empty).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::Medium);
+        assert!(hotspot.description.ends_with("; 50 files (25.0%) were empty"));
+    }
+    
+    #[test]
+    fn test_footer_cache_misses() {
+        assert!(LakehouseScanAnalysis::check_footer_cache(&file_scan(), &scan()).is_none());
+        
+        let cold = LakehouseScan {
+            footer_hit_cache: Some(20),
+            footer_read_calls: Some(180),
+            footer_hit_percentage: Some(10.0),
+            parse_footer_time_ns: Some(3_000_000_000),
+            ..scan()
+        };
+        let hotspot = LakehouseScanAnalysis::check_footer_cache(&file_scan(), &cold).unwrap();
+        assert_eq!(
+            hotspot.description,
+            "The scan of orders read 180 of 200 file footers from storage (10.0% hit the file meta cache); parsing footers took 3.00s"
+        );
+    }
+    
+    #[test]
+    fn test_slow_decompression() {
+        assert!(LakehouseScanAnalysis::check_decompression(&file_scan(), &scan()).is_none());
+        
+        let gzip = LakehouseScan { decompress_time_ns: Some(45_000_000_000), ..scan() };
+        let hotspot = LakehouseScanAnalysis::check_decompression(&file_scan(), &gzip).unwrap();
+        assert_eq!(hotspot.description, "The scan of orders spent 45.00s (45.0% of its reader time) decompressing pages");
+    }
+    
+    #[test]
+    fn test_iceberg_delete_files() {
+        assert!(LakehouseScanAnalysis::check_delete_files(&file_scan(), &scan()).is_none());
+        
+        let slow = LakehouseScan { delete_files: Some(120), delete_rows: Some(1_000_000), delete_time_ns: Some(40_000_000_000), ..scan() };
+        let hotspot = LakehouseScanAnalysis::check_delete_files(&file_scan(), &slow).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert_eq!(
+            hotspot.description,
+            "The scan of orders applied 120 Iceberg delete files with 1000000 deleted rows (1.0% of the rows read); \
+             reading and sorting them took 40.00s (40.0% of the reader time)"
+        );
+        
+        let many_rows = LakehouseScan { delete_files: Some(4), delete_rows: Some(8_000_000), delete_time_ns: Some(1_000_000_000), ..scan() };
+        let hotspot = LakehouseScanAnalysis::check_delete_files(&file_scan(), &many_rows).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::Medium);
+    }
+}
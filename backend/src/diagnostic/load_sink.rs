@@ -0,0 +1,203 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::counters;
+
+/// Counters holding the time tablet writers spend writing, in order of preference:
+/// the slowest node channel (OLAP_TABLE_SINK), memtable writes on the sink node
+/// (OLAP_TABLE_SINK_V2), then the whole send time
+const WRITE_TIME_COUNTERS: &[&str] = &["MaxAddBatchExecTime", "WriteMemtableTime", "SendDataTime"];
+
+/// LoadSink checks the OLAP table sink of INSERT / Stream Load / Broker Load
+/// profiles for slow tablet writers, too many small batches and long waits
+/// for replicas to close
+pub struct LoadSink;
+
+impl LoadSink {
+    /// Analyze a profile and return hotspots for its table sinks
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let Some(ref tree) = profile.execution_tree else {
+            return Vec::new();
+        };
+        let total_ns = profile.summary.total_time_ms.map(|ms| ms * 1_000_000.0);
+        
+        tree.nodes.iter()
+            .filter(|node| node.node_type == NodeType::OlapTableSink)
+            .flat_map(|node| {
+                [
+                    Self::check_write_time(node, total_ns),
+                    Self::check_small_batches(node),
+                    Self::check_close_wait(node, total_ns),
+                ]
+            })
+            .flatten()
+            .collect()
+    }
+    
+    /// Tablet writers taking a large share of the load
+    fn check_write_time(node: &ExecutionTreeNode, total_ns: Option<f64>) -> Option<HotSpot> {
+        let (counter, write_ns) = WRITE_TIME_COUNTERS.iter()
+            .find_map(|key| Some((*key, counters::node_counter(node, key)?.maximum()?)))?;
+        let pct = Self::share(write_ns, total_ns)?;
+        let severity = Self::severity_by_share(pct)?;
+        
+        let mut description = format!(
+            "Tablet writers of {} spent {} ({:.1}% of the load) in {}",
+            node.operator_name, counters::format_time(write_ns), pct, counter
+        );
+        // MaxAddBatchExecTime is the slowest node channel, TotalAddBatchExecTime adds up all of them
+        let channels = counters::node_counter(node, "NumberNodeChannels").and_then(CounterValue::maximum);
+        let total = counters::node_counter(node, "TotalAddBatchExecTime").and_then(CounterValue::maximum);
+        if let (Some(channels), Some(total), "MaxAddBatchExecTime") = (channels, total, counter) {
+            if channels > 1.0 {
+                description.push_str(&format!(
+                    "; the slowest of {} node channels took {}, {} on average",
+                    channels, counters::format_time(write_ns), counters::format_time(total / channels)
+                ));
+            }
+        }
+        
+        Some(counters::hotspot(
+            node,
+            HotspotKind::LoadSink,
+            severity,
+            description,
+            "Writing tablets dominates the load.\n\
+             Check the load / compaction pressure and disk IO of the BEs; a single slow node channel points at one BE.\n\
+             Fewer tablets per load (fewer partitions or buckets touched) and enable_memtable_on_sink_node reduce the write cost",
+        ))
+    }
+    
+    /// Many batches with only a few rows each, which pay RPC and memtable overhead per batch
+    fn check_small_batches(node: &ExecutionTreeNode) -> Option<HotSpot> {
+        let batches = counters::node_counter(node, "NumberBatchAdded").and_then(CounterValue::total)?;
+        let rows = ["RowsProduced", "InputRows"].iter()
+            .find_map(|key| counters::node_counter(node, key).and_then(CounterValue::total))?;
+        if batches < thresholds::MIN_LOAD_BATCHES {
+            return None;
+        }
+        
+        let rows_per_batch = rows / batches;
+        let severity = if rows_per_batch < thresholds::HIGH_SMALL_BATCH_ROWS {
+            HotspotSeverity::High
+        } else if rows_per_batch < thresholds::MEDIUM_SMALL_BATCH_ROWS {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        Some(counters::hotspot(
+            node,
+            HotspotKind::LoadSink,
+            severity,
+            format!(
+                "{} added {} batches for {} rows ({:.1} rows per batch)",
+                node.operator_name, batches, rows, rows_per_batch
+            ),
+            "The load sends many tiny batches, paying RPC and memtable overhead for each of them.\n\
+             Batch rows on the client side instead of sending small loads at a high frequency, or enable group commit (group_commit = async_mode).\n\
+             Check that batch_size is not lowered for the load session",
+        ))
+    }
+    
+    /// Waiting for all replicas to flush memtables and commit when the sink closes
+    fn check_close_wait(node: &ExecutionTreeNode, total_ns: Option<f64>) -> Option<HotSpot> {
+        let close_ns = counters::node_counter(node, "CloseWaitTime").and_then(CounterValue::maximum)?;
+        let pct = Self::share(close_ns, total_ns)?;
+        let severity = Self::severity_by_share(pct)?;
+        
+        Some(counters::hotspot(
+            node,
+            HotspotKind::LoadSink,
+            severity,
+            format!(
+                "{} waited {} ({:.1}% of the load) for replicas to close",
+                node.operator_name, counters::format_time(close_ns), pct
+            ),
+            "Closing the load waits for every replica to flush its memtables and commit its rowsets.\n\
+             Check disk IO and compaction on the BEs holding the slowest replicas, \
+             and reduce the number of tablets written by one load",
+        ))
+    }
+    
+    /// Share of the load time in percent, when the time is large enough to matter
+    fn share(time_ns: f64, total_ns: Option<f64>) -> Option<f64> {
+        let total_ns = total_ns.filter(|t| *t > 0.0)?;
+        if time_ns < thresholds::MIN_LOAD_SINK_TIME_NS {
+            return None;
+        }
+        Some((time_ns / total_ns * 100.0).min(100.0))
+    }
+    
+    fn severity_by_share(pct: f64) -> Option<HotspotSeverity> {
+        if pct >= thresholds::HIGH_LOAD_SINK_PERCENTAGE {
+            Some(HotspotSeverity::High)
+        } else if pct >= thresholds::MEDIUM_LOAD_SINK_PERCENTAGE {
+            Some(HotspotSeverity::Medium)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::counters::fixtures::{item, items, node, profile};
+    
+    fn sink_node(common: &[(&str, &str)], custom: &[(&str, &str)]) -> ExecutionTreeNode {
+        ExecutionTreeNode {
+            plan_node_id: Some(-1),
+            common_counters: items(common),
+            custom_counters: items(custom),
+            ..node(0, 0, "id-1", "OLAP_TABLE_SINK_OPERATOR", NodeType::OlapTableSink)
+        }
+    }
+    
+    #[test]
+    fn test_slow_tablet_writer() {
+        let node = sink_node(&[], &[
+            ("NumberNodeChannels", "3"),
+            ("MaxAddBatchExecTime", "6sec500ms"),
+            ("TotalAddBatchExecTime", "9sec300ms"),
+        ]);
+        
+        let hotspot = LoadSink::check_write_time(&node, Some(10_000_000_000.0)).unwrap();
+        assert_eq!(hotspot.kind, HotspotKind::LoadSink);
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert_eq!(
+            hotspot.description,
+            "Tablet writers of OLAP_TABLE_SINK_OPERATOR spent 6.50s (65.0% of the load) in MaxAddBatchExecTime; \
+             the slowest of 3 node channels took 6.50s, 3.10s on average"
+        );
+        
+        assert!(LoadSink::check_write_time(&node, Some(60_000_000_000.0)).is_none(), "small share of the load");
+        assert!(LoadSink::check_write_time(&node, None).is_none());
+    }
+    
+    #[test]
+    fn test_small_batches_and_close_wait() {
+        let mut node = sink_node(
+            &[("InputRows", "sum 50.0K (50000), avg 12.5K (12500), max 13.0K (13000), min 12.0K (12000)")],
+            &[("NumberBatchAdded", "sum 10.0K (10000), avg 2.5K (2500), max 2.6K (2600), min 2.4K (2400)")],
+        );
+        node.custom_counters.push(item(
+            "CloseTime",
+            "avg 3sec100ms, max 4sec200ms, min 2sec100ms",
+            items(&[("CloseWaitTime", "avg 2sec800ms, max 3sec900ms, min 1sec900ms")]),
+        ));
+        
+        let small = LoadSink::check_small_batches(&node).unwrap();
+        assert_eq!(small.severity, HotspotSeverity::High);
+        assert!(small.description.contains("added 10000 batches for 50000 rows (5.0 rows per batch)"));
+        
+        let close = LoadSink::check_close_wait(&node, Some(10_000_000_000.0)).unwrap();
+        assert_eq!(close.severity, HotspotSeverity::Medium);
+        assert!(close.description.contains("waited 3.90s (39.0% of the load)"));
+        
+        let profile = profile(vec![node], ProfileSummary {
+            total_time_ms: Some(10_000.0),
+            ..Default::default()
+        });
+        assert_eq!(LoadSink::analyze(&profile).len(), 2);
+    }
+}
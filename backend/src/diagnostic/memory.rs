@@ -0,0 +1,460 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::counters;
+use crate::parser::ValueParser;
+
+/// Number of operators kept in the breakdown besides the ones that spilled
+const MAX_MEMORY_OPERATORS: usize = 10;
+
+/// Texts of a query cancelled for exceeding its memory limit
+const MEM_LIMIT_EXCEEDED_MARKERS: &[&str] = &["MEM_LIMIT_EXCEEDED", "memory limit exceeded", "exceed memory limit"];
+
+/// MemoryAnalysis breaks the peak memory down by operator and component
+/// (hash tables, build / sort blocks, arenas) and reports memory limit failures,
+/// failed reservations, spilling and operators holding a lot of memory
+pub struct MemoryAnalysis;
+
+impl MemoryAnalysis {
+    /// Compute the memory breakdown of the execution tree
+    pub fn compute(profile: &Profile) -> Option<MemoryBreakdown> {
+        let tree = profile.execution_tree.as_ref()?;
+        
+        let mut operators: Vec<OperatorMemory> = tree.nodes.iter()
+            .filter_map(Self::operator_memory)
+            .collect();
+        let operator_peak_bytes = operators.iter().map(|op| op.peak_bytes).sum();
+        operators.sort_by_key(|op| std::cmp::Reverse(op.peak_bytes));
+        let mut rank = 0;
+        operators.retain(|op| {
+            rank += 1;
+            rank <= MAX_MEMORY_OPERATORS || op.spill.is_some()
+        });
+        
+        let (reserve_times, reserve_failed_times) = Self::reservations(profile);
+        
+        Some(MemoryBreakdown {
+            query_peak_bytes: profile.summary.query_peak_memory,
+            mem_limit_bytes: Self::mem_limit(&profile.summary),
+            operator_peak_bytes,
+            reserve_times,
+            reserve_failed_times,
+            operators,
+        })
+    }
+    
+    /// Analyze a profile for memory limit failures, failed reservations, spills and large operators
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let (Some(memory), Some(tree)) = (Self::compute(profile), profile.execution_tree.as_ref()) else {
+            return Vec::new();
+        };
+        let total_ns = profile.summary.total_time_ms.map(|ms| ms * 1_000_000.0).filter(|t| *t > 0.0);
+        
+        let mut hotspots = Vec::new();
+        hotspots.extend(Self::check_limit_exceeded(&profile.summary, &memory));
+        hotspots.extend(Self::check_reservations(&memory));
+        for op in &memory.operators {
+            let Some(node) = tree.nodes.iter().find(|n| n.id == op.node_id) else {
+                continue;
+            };
+            match op.spill {
+                Some(ref spill) => hotspots.push(Self::spill_hotspot(node, op, spill, total_ns)),
+                None => hotspots.extend(Self::check_large_operator(node, op)),
+            }
+        }
+        hotspots
+    }
+    
+    /// Query cancelled for exceeding its memory limit, or peaking close to exec_mem_limit
+    fn check_limit_exceeded(summary: &ProfileSummary, memory: &MemoryBreakdown) -> Option<HotSpot> {
+        let failed = [Some(&summary.query_state), summary.execution_summary.get("System Message")]
+            .into_iter()
+            .flatten()
+            .any(|text| {
+                let text = text.to_lowercase();
+                MEM_LIMIT_EXCEEDED_MARKERS.iter().any(|m| text.contains(&m.to_lowercase()))
+            });
+        let limit_pct = match (memory.query_peak_bytes, memory.mem_limit_bytes) {
+            (Some(peak), Some(limit)) if limit > 0 => Some(peak as f64 / limit as f64 * 100.0),
+            _ => None,
+        };
+        
+        let mut description = if failed {
+            "The query exceeded its memory limit".to_string()
+        } else {
+            match limit_pct {
+                Some(pct) if pct >= thresholds::HIGH_MEM_LIMIT_PERCENTAGE => format!(
+                    "The query peaked at {} ({:.1}% of exec_mem_limit {})",
+                    counters::format_bytes(memory.query_peak_bytes.unwrap_or(0)),
+                    pct,
+                    counters::format_bytes(memory.mem_limit_bytes.unwrap_or(0)),
+                ),
+                _ => return None,
+            }
+        };
+        if let Some(top) = memory.operators.first() {
+            description.push_str(&format!(
+                "; the largest consumer is {} with {} at peak",
+                top.operator_name, counters::format_bytes(top.peak_bytes)
+            ));
+        }
+        
+        Some(HotSpot {
+            node_id: "query/memory".to_string(),
+            node_path: "Query memory".to_string(),
+            operator_name: "MemoryLimit".to_string(),
+            node_type: NodeType::Unknown,
+            severity: if failed { HotspotSeverity::Critical } else { HotspotSeverity::High },
+            description,
+            kind: HotspotKind::Memory,
+            time_percentage: None,
+            suggestion: Some(
+                "Reduce the memory held by the largest operators listed in the memory breakdown.\n\
+                 Enable spilling (enable_spill = true, plus enable_reserve_memory on Doris 3.0+) so joins, aggregations and sorts spill to disk instead of failing.\n\
+                 Otherwise raise exec_mem_limit or the memory_limit of the workload group, and run fewer memory-heavy queries at the same time".to_string()
+            ),
+            suggestion_source: Some("default".to_string()),
+        })
+    }
+    
+    /// Pipeline tasks failing to reserve memory, which pauses them until memory is freed or they spill
+    fn check_reservations(memory: &MemoryBreakdown) -> Option<HotSpot> {
+        let failed = memory.reserve_failed_times.filter(|n| *n > 0)?;
+        let total = memory.reserve_times.unwrap_or(0).max(failed);
+        let pct = failed as f64 / total as f64 * 100.0;
+        let severity = if pct >= thresholds::HIGH_RESERVE_FAILED_PERCENTAGE {
+            HotspotSeverity::High
+        } else {
+            HotspotSeverity::Medium
+        };
+        
+        Some(HotSpot {
+            node_id: "query/memory-reserve".to_string(),
+            node_path: "Query memory".to_string(),
+            operator_name: "MemoryReserveFailedTimes".to_string(),
+            node_type: NodeType::Unknown,
+            severity,
+            description: format!(
+                "{} of {} memory reservations of pipeline tasks failed ({:.1}%); tasks were paused until memory was released or spilled",
+                failed, total, pct
+            ),
+            kind: HotspotKind::Memory,
+            time_percentage: None,
+            suggestion: Some(
+                "The query runs close to the memory limit of the query or its workload group.\n\
+                 Reduce the memory held by the largest operators, make sure spilling is enabled (enable_spill = true), \
+                 or give the workload group more memory".to_string()
+            ),
+            suggestion_source: Some("default".to_string()),
+        })
+    }
+    
+    fn spill_hotspot(node: &ExecutionTreeNode, op: &OperatorMemory, spill: &SpillStats, total_ns: Option<f64>) -> HotSpot {
+        let pct = total_ns.map(|total| (spill.spill_time_ns as f64 / total * 100.0).min(100.0));
+        let severity = match pct {
+            Some(pct) if pct >= thresholds::HIGH_SPILL_TIME_PERCENTAGE => HotspotSeverity::High,
+            Some(pct) if pct >= thresholds::MEDIUM_SPILL_TIME_PERCENTAGE => HotspotSeverity::Medium,
+            _ => HotspotSeverity::Low,
+        };
+        
+        let mut description = format!(
+            "{} spilled {} ({} rows) to disk and read back {}",
+            op.operator_name,
+            counters::format_bytes(spill.write_bytes),
+            spill.write_rows,
+            counters::format_bytes(spill.read_bytes),
+        );
+        if let Some(pct) = pct {
+            description.push_str(&format!(
+                ", spending {} ({:.1}% of the query) spilling",
+                counters::format_time(spill.spill_time_ns as f64), pct
+            ));
+        }
+        
+        let suggestion = format!(
+            "{}\nSpilling keeps the query alive under memory pressure at the cost of disk IO; \
+             place spill directories on fast disks, or give the query more memory to avoid it",
+            Self::reduce_suggestion(op)
+        );
+        counters::hotspot(node, HotspotKind::Memory, severity, description, suggestion)
+    }
+    
+    /// Operators holding a lot of memory without spilling
+    fn check_large_operator(node: &ExecutionTreeNode, op: &OperatorMemory) -> Option<HotSpot> {
+        let severity = if op.peak_bytes >= thresholds::HIGH_OPERATOR_MEMORY_BYTES {
+            HotspotSeverity::High
+        } else if op.peak_bytes >= thresholds::MEDIUM_OPERATOR_MEMORY_BYTES {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        let mut description = format!(
+            "{} peaked at {} over all instances ({} for the largest instance)",
+            op.operator_name,
+            counters::format_bytes(op.peak_bytes),
+            counters::format_bytes(op.max_instance_peak_bytes),
+        );
+        if !op.components.is_empty() {
+            let components: Vec<String> = op.components.iter()
+                .take(3)
+                .map(|c| format!("{} {}", c.name, counters::format_bytes(c.bytes)))
+                .collect();
+            description.push_str(&format!(": {}", components.join(", ")));
+        }
+        
+        let suggestion = format!(
+            "{}\nEnable spilling (enable_spill = true) so the operator spills to disk instead of failing the query when memory runs out",
+            Self::reduce_suggestion(op)
+        );
+        Some(counters::hotspot(node, HotspotKind::Memory, severity, description, suggestion))
+    }
+    
+    /// How to shrink the state of an operator, by operator type
+    fn reduce_suggestion(op: &OperatorMemory) -> &'static str {
+        if op.node_type.is_join() {
+            "The hash table of the join build side holds the memory.\n\
+             Put the smaller input on the build side (check join order and statistics), filter it earlier, \
+             and use a shuffle join instead of broadcast for a large build side so each instance builds only part of it"
+        } else if op.node_type.is_aggregate() {
+            "The aggregation hash table grows with the number of distinct GROUP BY keys.\n\
+             Group by fewer or narrower keys, and keep the first phase streaming so it does not hold all keys"
+        } else if op.node_type.is_sort() {
+            "The sort buffers its whole input.\n\
+             Add a LIMIT for a top-N sort, or sort fewer and narrower columns"
+        } else {
+            "Reduce the data this operator buffers, e.g. by filtering or projecting columns earlier"
+        }
+    }
+    
+    fn operator_memory(node: &ExecutionTreeNode) -> Option<OperatorMemory> {
+        let peak = counters::node_counter(node, "MemoryUsagePeak");
+        let peak_bytes = node.metrics.memory_used.unwrap_or(0);
+        let spill = Self::spill(node);
+        if peak_bytes == 0 && spill.is_none() {
+            return None;
+        }
+        
+        Some(OperatorMemory {
+            node_id: node.id.clone(),
+            operator_name: node.operator_name.clone(),
+            node_type: node.node_type,
+            fragment_id: node.fragment_id.clone(),
+            pipeline_id: node.pipeline_id.clone(),
+            peak_bytes,
+            max_instance_peak_bytes: peak.and_then(CounterValue::maximum).unwrap_or(0.0) as u64,
+            components: Self::components(node),
+            spill,
+        })
+    }
+    
+    /// MemoryUsage* counters other than the operator totals; the peak of a
+    /// component wins over its current value
+    fn components(node: &ExecutionTreeNode) -> Vec<MemoryComponent> {
+        fn collect(items: &[MetricItem], out: &mut Vec<MemoryComponent>) {
+            for item in items {
+                let name = item.key.strip_prefix("MemoryUsage")
+                    .or_else(|| item.key.strip_suffix("MemoryUsage"))
+                    .map(|name| name.strip_suffix("Peak").unwrap_or(name))
+                    .filter(|name| !name.is_empty());
+                let bytes = item.counter.total().unwrap_or(0.0) as u64;
+                if let Some(name) = name.filter(|_| bytes > 0) {
+                    match out.iter_mut().find(|c| c.name == name) {
+                        Some(component) => component.bytes = component.bytes.max(bytes),
+                        None => out.push(MemoryComponent { name: name.to_string(), bytes }),
+                    }
+                }
+                collect(&item.children, out);
+            }
+        }
+        
+        let mut components = Vec::new();
+        collect(&node.common_counters, &mut components);
+        collect(&node.custom_counters, &mut components);
+        components.sort_by_key(|c| std::cmp::Reverse(c.bytes));
+        components
+    }
+    
+    fn spill(node: &ExecutionTreeNode) -> Option<SpillStats> {
+        let total = |key: &str| {
+            counters::node_counter(node, key).and_then(CounterValue::total).unwrap_or(0.0) as u64
+        };
+        let write_bytes = match total("SpillWriteFileBytes") {
+            0 => total("SpillWriteBlockBytes"),
+            bytes => bytes,
+        };
+        let spill = SpillStats {
+            write_bytes,
+            write_rows: total("SpillWriteRows"),
+            read_bytes: total("SpillReadFileBytes"),
+            spill_time_ns: counters::node_counter(node, "SpillTotalTime")
+                .and_then(CounterValue::maximum)
+                .unwrap_or(0.0) as u64,
+        };
+        
+        (spill.write_bytes > 0 || spill.write_rows > 0 || spill.read_bytes > 0).then_some(spill)
+    }
+    
+    /// Memory reservations of the PipelineTasks in the DetailProfile
+    fn reservations(profile: &Profile) -> (Option<u64>, Option<u64>) {
+        let Some(ref detail) = profile.detail_profile else {
+            return (None, None);
+        };
+        let tasks = detail.fragments.iter()
+            .flat_map(|f| &f.backends)
+            .flat_map(|b| &b.pipelines)
+            .flat_map(|p| &p.tasks);
+        
+        let mut reserve_times = None;
+        let mut reserve_failed_times = None;
+        for task in tasks {
+            for item in &task.task_counters {
+                let target = match item.key.as_str() {
+                    "MemoryReserveTimes" => &mut reserve_times,
+                    "MemoryReserveFailedTimes" => &mut reserve_failed_times,
+                    _ => continue,
+                };
+                let value = item.counter.total().unwrap_or(0.0) as u64;
+                *target = Some(target.unwrap_or(0) + value);
+            }
+        }
+        (reserve_times, reserve_failed_times)
+    }
+    
+    /// exec_mem_limit in bytes, either a plain number or a size like "8.00 GB"
+    fn mem_limit(summary: &ProfileSummary) -> Option<u64> {
+        let value = summary.session_variables.iter()
+            .find(|v| v.var_name == "exec_mem_limit")?
+            .current_value
+            .trim();
+        value.parse::<u64>().ok().or_else(|| ValueParser::parse_memory_to_bytes(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::counters::fixtures::{self, items, profile};
+    
+    fn node(name: &str, node_type: NodeType, peak: &str, custom: &[(&str, &str)]) -> ExecutionTreeNode {
+        let common = items(&[("MemoryUsagePeak", peak)]);
+        ExecutionTreeNode {
+            plan_node_id: Some(3),
+            metrics: OperatorMetrics {
+                memory_used: common[0].counter.total().map(|v| v as u64),
+                ..Default::default()
+            },
+            common_counters: common,
+            custom_counters: items(custom),
+            ..fixtures::node(1, 0, name, name, node_type)
+        }
+    }
+    
+    fn build_sink() -> ExecutionTreeNode {
+        node("HASH_JOIN_SINK_OPERATOR", NodeType::HashJoinBuild, "sum 3.00 GB, avg 1.00 GB, max 1.50 GB, min 512.00 MB", &[
+            ("MemoryUsageHashTable", "sum 2.00 GB, avg 682.67 MB, max 1.00 GB, min 256.00 MB"),
+            ("MemoryUsageBuildBlocks", "sum 768.00 MB, avg 256.00 MB, max 512.00 MB, min 128.00 MB"),
+            ("MemoryUsageBuildKeyArena", "sum 0.00 , avg 0.00 , max 0.00 , min 0.00 "),
+            ("MemoryUsageBuildKeyArenaPeak", "sum 64.00 MB, avg 21.33 MB, max 32.00 MB, min 16.00 MB"),
+        ])
+    }
+    
+    #[test]
+    fn test_operator_breakdown() {
+        let op = MemoryAnalysis::operator_memory(&build_sink()).unwrap();
+        assert_eq!(op.peak_bytes, 3 * 1024 * 1024 * 1024);
+        assert_eq!(op.max_instance_peak_bytes, 1536 * 1024 * 1024);
+        let names: Vec<&str> = op.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["HashTable", "BuildBlocks", "BuildKeyArena"]);
+        assert_eq!(op.components[2].bytes, 64 * 1024 * 1024);
+        assert!(op.spill.is_none());
+        
+        let hotspot = MemoryAnalysis::check_large_operator(&build_sink(), &op).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::Medium);
+        assert_eq!(
+            hotspot.description,
+            "HASH_JOIN_SINK_OPERATOR peaked at 3.00 GB over all instances (1.50 GB for the largest instance): \
+             HashTable 2.00 GB, BuildBlocks 768.00 MB, BuildKeyArena 64.00 MB"
+        );
+        assert!(hotspot.suggestion.unwrap().contains("build side"));
+        
+        let small = node("SORT_OPERATOR", NodeType::Sort, "sum 4.00 KB, avg 4.00 KB, max 4.00 KB, min 4.00 KB", &[]);
+        let op = MemoryAnalysis::operator_memory(&small).unwrap();
+        assert!(MemoryAnalysis::check_large_operator(&small, &op).is_none());
+    }
+    
+    #[test]
+    fn test_spilling_aggregation() {
+        let agg = node("AGGREGATION_SINK_OPERATOR", NodeType::AggregateSink, "sum 1.00 GB, avg 512.00 MB, max 512.00 MB, min 512.00 MB", &[
+            ("MemoryUsageHashTable", "sum 900.00 MB, avg 450.00 MB, max 450.00 MB, min 450.00 MB"),
+            ("SpillTotalTime", "avg 2sec, max 4sec, min 1sec"),
+            ("SpillWriteFileBytes", "sum 6.00 GB, avg 3.00 GB, max 3.00 GB, min 3.00 GB"),
+            ("SpillWriteRows", "sum 1000000, avg 500000, max 500000, min 500000"),
+            ("SpillReadFileBytes", "sum 6.00 GB, avg 3.00 GB, max 3.00 GB, min 3.00 GB"),
+        ]);
+        let summary = ProfileSummary {
+            total_time_ms: Some(10_000.0),
+            ..Default::default()
+        };
+        
+        let hotspots = MemoryAnalysis::analyze(&profile(vec![agg], summary));
+        assert_eq!(hotspots.len(), 1);
+        assert_eq!(hotspots[0].kind, HotspotKind::Memory);
+        assert_eq!(hotspots[0].severity, HotspotSeverity::High);
+        assert_eq!(
+            hotspots[0].description,
+            "AGGREGATION_SINK_OPERATOR spilled 6.00 GB (1000000 rows) to disk and read back 6.00 GB, \
+             spending 4.00s (40.0% of the query) spilling"
+        );
+        assert!(hotspots[0].suggestion.as_ref().unwrap().contains("GROUP BY"));
+    }
+    
+    #[test]
+    fn test_mem_limit_exceeded() {
+        let summary = ProfileSummary {
+            query_state: "MEM_LIMIT_EXCEEDED".to_string(),
+            ..Default::default()
+        };
+        let hotspots = MemoryAnalysis::analyze(&profile(vec![build_sink()], summary));
+        let failure = hotspots.iter().find(|h| h.severity == HotspotSeverity::Critical).unwrap();
+        assert_eq!(failure.node_type, NodeType::Unknown);
+        assert_eq!(
+            failure.description,
+            "The query exceeded its memory limit; the largest consumer is HASH_JOIN_SINK_OPERATOR with 3.00 GB at peak"
+        );
+        
+        // Close to the session limit without failing
+        let summary = ProfileSummary {
+            query_peak_memory: Some(7 * 1024 * 1024 * 1024),
+            session_variables: vec![SessionVariable {
+                var_name: "exec_mem_limit".to_string(),
+                current_value: "8589934592".to_string(),
+                default_value: "2147483648".to_string(),
+            }],
+            ..Default::default()
+        };
+        let memory = MemoryAnalysis::compute(&profile(vec![build_sink()], summary.clone())).unwrap();
+        assert_eq!(memory.mem_limit_bytes, Some(8 * 1024 * 1024 * 1024));
+        let hotspot = MemoryAnalysis::check_limit_exceeded(&summary, &memory).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert!(hotspot.description.starts_with("The query peaked at 7.00 GB (87.5% of exec_mem_limit 8.00 GB)"));
+    }
+    
+    #[test]
+    fn test_failed_reservations() {
+        let memory = MemoryBreakdown {
+            reserve_times: Some(200),
+            reserve_failed_times: Some(30),
+            ..Default::default()
+        };
+        let hotspot = MemoryAnalysis::check_reservations(&memory).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert!(hotspot.description.starts_with("30 of 200 memory reservations of pipeline tasks failed (15.0%)"));
+        
+        let memory = MemoryBreakdown {
+            reserve_times: Some(200),
+            reserve_failed_times: Some(0),
+            ..Default::default()
+        };
+        assert!(MemoryAnalysis::check_reservations(&memory).is_none());
+    }
+}
@@ -1,6 +1,32 @@
 pub mod performance_bottleneck;
 pub mod optimization_advisor;
+pub mod cardinality_estimation;
+pub mod data_skew;
+pub mod runtime_filter;
+pub mod planning_overhead;
+pub mod backend_imbalance;
+pub mod load_sink;
+pub mod critical_path;
+pub mod pipeline_time;
+pub mod memory;
+pub mod shuffle;
+pub mod lakehouse_scan;
+pub mod remote_io;
+
+mod counters;
 
 pub use performance_bottleneck::*;
 pub use optimization_advisor::*;
+pub use cardinality_estimation::*;
+pub use data_skew::*;
+pub use runtime_filter::*;
+pub use planning_overhead::*;
+pub use backend_imbalance::*;
+pub use load_sink::*;
+pub use critical_path::*;
+pub use pipeline_time::*;
+pub use memory::*;
+pub use shuffle::*;
+pub use lakehouse_scan::*;
+pub use remote_io::*;
 
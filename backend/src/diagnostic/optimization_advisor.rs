@@ -9,13 +9,18 @@ pub struct OptimizationAdvisor;
 impl OptimizationAdvisor {
     /// Fill suggestions for hotspots using AI or default suggestions
     pub async fn fill_suggestions(
-        hotspots: &mut Vec<HotSpot>,
+        hotspots: &mut [HotSpot],
         profile: &Profile,
         ai_service: Option<&AiDiagnosisService>,
         default_config: &DefaultSuggestionsConfig,
         skip_ai: bool,  // If true, only use default suggestions
     ) {
         for hotspot in hotspots.iter_mut() {
+            // Diagnostics such as cardinality misestimation come with their own suggestion
+            if hotspot.suggestion.is_some() {
+                continue;
+            }
+            
             // Find corresponding node
             if let Some(ref tree) = profile.execution_tree {
                 if let Some(node) = tree.nodes.iter().find(|n| n.id == hotspot.node_id) {
@@ -23,7 +28,7 @@ impl OptimizationAdvisor {
                     let (suggestion, source) = if skip_ai {
                         // Skip AI, use default suggestions
                         let default_suggestion = Self::get_default_suggestion(
-                            &hotspot.operator_name, 
+                            hotspot.node_type, 
                             &hotspot.severity, 
                             default_config
                         );
@@ -38,7 +43,7 @@ impl OptimizationAdvisor {
                                     let error_msg = format!("AI Suggestion failed: {}", e);
                                     eprintln!("{} for node {}, using default", error_msg, node.id);
                                     let default_suggestion = Self::get_default_suggestion(
-                                        &hotspot.operator_name, 
+                                        hotspot.node_type, 
                                         &hotspot.severity, 
                                         default_config
                                     );
@@ -47,7 +52,7 @@ impl OptimizationAdvisor {
                             }
                         } else {
                             let default_suggestion = Self::get_default_suggestion(
-                                &hotspot.operator_name, 
+                                hotspot.node_type, 
                                 &hotspot.severity, 
                                 default_config
                             );
@@ -55,7 +60,7 @@ impl OptimizationAdvisor {
                         }
                     } else {
                         let default_suggestion = Self::get_default_suggestion(
-                            &hotspot.operator_name, 
+                            hotspot.node_type, 
                             &hotspot.severity, 
                             default_config
                         );
@@ -71,21 +76,25 @@ impl OptimizationAdvisor {
     
     /// Get default suggestion from configuration file (public version)
     pub fn get_default_suggestion_public(
-        operator_name: &str,
+        node_type: NodeType,
         severity: &HotspotSeverity,
         config: &DefaultSuggestionsConfig,
     ) -> String {
-        Self::get_default_suggestion(operator_name, severity, config)
+        Self::get_default_suggestion(node_type, severity, config)
     }
     
     /// Get default suggestion from configuration file
     fn get_default_suggestion(
-        operator_name: &str,
+        node_type: NodeType,
         severity: &HotspotSeverity,
         config: &DefaultSuggestionsConfig,
     ) -> String {
-        // Try to match operator name
-        let suggestions = config.suggestions.get(operator_name)
+        // Try the node type, then the operator it belongs to, then its key in
+        // configs written before node types were named, then DEFAULT
+        let family = Self::suggestion_family(node_type);
+        let suggestions = config.suggestions.get(&format!("{:?}", node_type))
+            .or_else(|| config.suggestions.get(&format!("{:?}", family)))
+            .or_else(|| Self::legacy_suggestion_key(family).and_then(|key| config.suggestions.get(key)))
             .or_else(|| config.suggestions.get("DEFAULT"));
         
         if let Some(sev_suggestions) = suggestions {
@@ -108,16 +117,46 @@ impl OptimizationAdvisor {
         }
     }
     
-
+    /// Operator whose default suggestions cover a node type without its own entry
+    fn suggestion_family(node_type: NodeType) -> NodeType {
+        match node_type {
+            NodeType::HashJoinBuild => NodeType::HashJoin,
+            NodeType::NestedLoopJoinBuild => NodeType::NestedLoopJoin,
+            NodeType::AggregateSink | NodeType::StreamingAggregate | NodeType::DistinctStreamingAggregate => NodeType::Aggregate,
+            NodeType::AnalyticEvalSink => NodeType::AnalyticEval,
+            node_type if node_type.is_sort() => NodeType::Sort,
+            NodeType::LocalExchangeSink => NodeType::LocalExchange,
+            NodeType::DataStreamSink | NodeType::MultiCastSink | NodeType::MultiCastSource => NodeType::Exchange,
+            node_type => node_type,
+        }
+    }
+    
+    /// Key of an operator in default_suggestions.yaml files that still use the
+    /// operator names, e.g. OLAP_SCAN_OPERATOR
+    fn legacy_suggestion_key(node_type: NodeType) -> Option<&'static str> {
+        match node_type {
+            NodeType::OlapScan => Some("OLAP_SCAN_OPERATOR"),
+            NodeType::FileScan => Some("FILE_SCAN_OPERATOR"),
+            NodeType::HashJoin => Some("HASH_JOIN_OPERATOR"),
+            NodeType::NestedLoopJoin => Some("CROSS_JOIN_OPERATOR"),
+            NodeType::Aggregate => Some("AGGREGATION_OPERATOR"),
+            NodeType::Sort => Some("SORT_OPERATOR"),
+            NodeType::Exchange => Some("EXCHANGE_OPERATOR"),
+            NodeType::LocalExchange => Some("LOCAL_EXCHANGE_OPERATOR"),
+            _ => None,
+        }
+    }
+    
     /// Generate a conclusion summary based on hotspots and profile
     pub fn generate_conclusion(hotspots: &[HotSpot], profile: &Profile) -> String {
         let total_time = profile.summary.total_time.clone();
         let hotspot_count = hotspots.len();
+        let subject = if profile.summary.load_type.is_some() { "Load" } else { "Query" };
         
         if hotspots.is_empty() {
             return format!(
-                "Query completed in {} with no significant performance issues detected.",
-                total_time
+                "{} completed in {} with no significant performance issues detected.",
+                subject, total_time
             );
         }
         
@@ -131,18 +170,18 @@ impl OptimizationAdvisor {
         
         if critical_count > 0 {
             format!(
-                "Query completed in {} with {} critical performance bottleneck(s) and {} total issue(s) detected. Immediate attention recommended.",
-                total_time, critical_count, hotspot_count
+                "{} completed in {} with {} critical performance bottleneck(s) and {} total issue(s) detected. Immediate attention recommended.",
+                subject, total_time, critical_count, hotspot_count
             )
         } else if high_count > 0 {
             format!(
-                "Query completed in {} with {} high-severity issue(s) and {} total issue(s) detected. Optimization recommended.",
-                total_time, high_count, hotspot_count
+                "{} completed in {} with {} high-severity issue(s) and {} total issue(s) detected. Optimization recommended.",
+                subject, total_time, high_count, hotspot_count
             )
         } else {
             format!(
-                "Query completed in {} with {} minor performance issue(s) detected.",
-                total_time, hotspot_count
+                "{} completed in {} with {} minor performance issue(s) detected.",
+                subject, total_time, hotspot_count
             )
         }
     }
@@ -153,8 +192,15 @@ impl OptimizationAdvisor {
         let mut seen_categories: std::collections::HashSet<String> = std::collections::HashSet::new();
         
         for hotspot in hotspots {
-            // Skip if we already have a suggestion for this category
-            let category_key = format!("{:?}-{}", hotspot.severity, &hotspot.operator_name);
+            let title = hotspot.kind.title(&hotspot.operator_name, hotspot.node_type);
+            
+            // Skip if we already have a suggestion for this category.
+            // Misestimations of the same tables share one ANALYZE suggestion.
+            let category_key = if hotspot.kind.dedup_by_title() {
+                format!("{:?}-{}", hotspot.severity, &title)
+            } else {
+                hotspot.suggestion.clone().unwrap_or_default()
+            };
             if seen_categories.contains(&category_key) {
                 continue;
             }
@@ -164,7 +210,7 @@ impl OptimizationAdvisor {
                 let (priority, category) = Self::categorize_suggestion(hotspot);
                 
                 suggestions.push(Suggestion {
-                    title: format!("Optimize {} operator", hotspot.operator_name),
+                    title,
                     description: suggestion_text.clone(),
                     priority,
                     category,
@@ -250,15 +296,7 @@ impl OptimizationAdvisor {
             HotspotSeverity::None => SuggestionPriority::Low,
         };
         
-        let category = match hotspot.operator_name.as_str() {
-            name if name.contains("SCAN") => SuggestionCategory::Schema,
-            name if name.contains("JOIN") => SuggestionCategory::Query,
-            name if name.contains("EXCHANGE") => SuggestionCategory::Configuration,
-            name if name.contains("AGGREGATE") => SuggestionCategory::Query,
-            _ => SuggestionCategory::Query,
-        };
-        
-        (priority, category)
+        (priority, hotspot.kind.category(hotspot.node_type))
     }
     
     /// Get numeric rank for priority (higher = more important)
@@ -275,6 +313,7 @@ impl OptimizationAdvisor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::SeveritySuggestions;
     
     fn create_test_profile() -> Profile {
         Profile {
@@ -286,6 +325,10 @@ mod tests {
             },
             fragments: vec![],
             execution_tree: None,
+            detail_profile: None,
+            physical_plan: None,
+            runtime_filters: Vec::new(),
+            parse_warnings: Vec::new(),
         }
     }
     
@@ -294,6 +337,10 @@ mod tests {
         let profile = create_test_profile();
         let conclusion = OptimizationAdvisor::generate_conclusion(&[], &profile);
         assert!(conclusion.contains("no significant performance issues"));
+        
+        let mut load = create_test_profile();
+        load.summary.load_type = Some(LoadType::StreamLoad);
+        assert!(OptimizationAdvisor::generate_conclusion(&[], &load).starts_with("Load completed in 1s"));
     }
     
     #[test]
@@ -303,6 +350,34 @@ mod tests {
         assert!(score >= 90);
     }
     
+    #[test]
+    fn test_default_suggestion_by_node_type() {
+        let suggestions = |text: &str| SeveritySuggestions {
+            critical: vec![],
+            high: vec![text.to_string()],
+            medium: vec![],
+            low: vec![],
+        };
+        let config = DefaultSuggestionsConfig {
+            suggestions: [
+                ("HashJoin".to_string(), suggestions("join")),
+                ("DEFAULT".to_string(), suggestions("default")),
+            ].into_iter().collect(),
+        };
+        
+        let high = HotspotSeverity::High;
+        assert_eq!(OptimizationAdvisor::get_default_suggestion(NodeType::HashJoin, &high, &config), "join");
+        assert_eq!(OptimizationAdvisor::get_default_suggestion(NodeType::HashJoinBuild, &high, &config), "join");
+        assert_eq!(OptimizationAdvisor::get_default_suggestion(NodeType::FileScan, &high, &config), "default");
+        
+        // Configs keyed by the operator names still apply
+        let legacy = DefaultSuggestionsConfig {
+            suggestions: [("OLAP_SCAN_OPERATOR".to_string(), suggestions("scan"))].into_iter().collect(),
+        };
+        assert_eq!(OptimizationAdvisor::get_default_suggestion(NodeType::OlapScan, &high, &legacy), "scan");
+        assert_eq!(OptimizationAdvisor::get_default_suggestion(NodeType::HashJoin, &high, &legacy), "暂无优化建议");
+    }
+    
     #[test]
     fn test_score_category() {
         assert_eq!(OptimizationAdvisor::get_score_category(95), "Excellent");
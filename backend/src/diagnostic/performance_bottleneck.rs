@@ -1,5 +1,6 @@
 use crate::models::*;
 use crate::constants::thresholds;
+use crate::diagnostic::{BackendImbalance, CardinalityEstimation, CriticalPathAnalysis, DataSkew, LakehouseScanAnalysis, LoadSink, MemoryAnalysis, PipelineTimeAccounting, PlanningOverhead, RemoteIoAnalysis, RuntimeFilterEffectiveness, ShuffleAnalysis};
 
 /// PerformanceBottleneck analyzes execution tree nodes to identify performance bottlenecks
 pub struct PerformanceBottleneck;
@@ -17,6 +18,42 @@ impl PerformanceBottleneck {
             }
         }
         
+        // Planner estimates vs actual rows from the Appendix PhysicalPlan
+        hotspots.extend(CardinalityEstimation::analyze(profile));
+        
+        // Uneven work across instances from avg/max/min counters
+        hotspots.extend(DataSkew::analyze(profile));
+        
+        // Runtime filters that filter nothing or time out
+        hotspots.extend(RuntimeFilterEffectiveness::analyze(profile));
+        
+        // FE planning / split enumeration / scheduling from the Execution Summary
+        hotspots.extend(PlanningOverhead::analyze(profile));
+        
+        // Instances, splits and fragment RPC time per BE
+        hotspots.extend(BackendImbalance::analyze(profile));
+        
+        // Tablet writers, batch sizes and replica close wait of load table sinks
+        hotspots.extend(LoadSink::analyze(profile));
+        
+        // Operators on the chain that determined wall-clock latency
+        hotspots.extend(CriticalPathAnalysis::analyze(profile));
+        
+        // Worker thread starvation and pipelines blocked on dependencies
+        hotspots.extend(PipelineTimeAccounting::analyze(profile));
+        
+        // Memory limit failures, failed reservations, spills and large operators
+        hotspots.extend(MemoryAnalysis::analyze(profile));
+        
+        // Exchange senders blocked on the network and slow RPC tails
+        hotspots.extend(ShuffleAnalysis::analyze(profile));
+        
+        // Row-group / page pruning, small files, footer cache and delete files of external table scans
+        hotspots.extend(LakehouseScanAnalysis::analyze(profile));
+        
+        // Object storage throttling (429) and small GET requests of external table scans
+        hotspots.extend(RemoteIoAnalysis::analyze(profile));
+        
         // Sort hotspots by severity (most severe first)
         hotspots.sort_by(|a, b| {
             Self::severity_rank(&b.severity).cmp(&Self::severity_rank(&a.severity))
@@ -50,8 +87,10 @@ impl PerformanceBottleneck {
             node_id: node.id.clone(),
             node_path: Self::build_node_path(node),
             operator_name: node.operator_name.clone(),
+            node_type: node.node_type,
             severity,
             description,
+            kind: HotspotKind::TimeConsuming,
             time_percentage: node.time_percentage,
             suggestion: None,  // Will be filled by SuggestionEngine
             suggestion_source: None,  // Will be filled by SuggestionEngine
@@ -95,7 +134,7 @@ impl PerformanceBottleneck {
     }
     
     /// Build a human-readable path for the node
-    fn build_node_path(node: &ExecutionTreeNode) -> String {
+    pub(crate) fn build_node_path(node: &ExecutionTreeNode) -> String {
         let mut path = String::new();
         
         if let Some(ref frag_id) = node.fragment_id {
@@ -118,7 +157,7 @@ impl PerformanceBottleneck {
     }
     
     /// Get numeric rank for severity (higher = more severe)
-    fn severity_rank(severity: &HotspotSeverity) -> u8 {
+    pub(crate) fn severity_rank(severity: &HotspotSeverity) -> u8 {
         match severity {
             HotspotSeverity::Critical => 4,
             HotspotSeverity::High => 3,
@@ -132,7 +171,6 @@ impl PerformanceBottleneck {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
     
     #[test]
     fn test_severity_determination() {
@@ -140,23 +178,8 @@ mod tests {
             id: "test".to_string(),
             operator_name: "OLAP_SCAN".to_string(),
             node_type: NodeType::OlapScan,
-            plan_node_id: None,
-            parent_plan_node_id: None,
-            metrics: OperatorMetrics::default(),
-            children: vec![],
-            depth: 0,
-            is_hotspot: false,
-            hotspot_severity: HotspotSeverity::None,
-            fragment_id: None,
-            pipeline_id: None,
             time_percentage: Some(60.0),
-            is_most_consuming: false,
-            is_second_most_consuming: false,
-            unique_metrics: HashMap::new(),
-            plan_info: vec![],
-            common_counters: vec![],
-            custom_counters: vec![],
-            table_name: None,
+            ..Default::default()
         };
         
         let severity = PerformanceBottleneck::determine_severity(&node);
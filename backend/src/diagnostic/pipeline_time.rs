@@ -0,0 +1,420 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::{CriticalPathAnalysis, PerformanceBottleneck};
+use crate::diagnostic::counters;
+use crate::parser::engine::OperatorParser;
+use crate::parser::engine::operator_parser::ParsedOperator;
+
+/// Number of blocked pipelines reported as hotspots
+const MAX_DEPENDENCY_WAIT_HOTSPOTS: usize = 3;
+
+/// PipelineTimeAccounting splits the time of pipeline tasks into running,
+/// sinking, waiting for a worker thread and waiting for dependencies, and tells
+/// a CPU-starved BE apart from pipelines blocked on an exchange
+pub struct PipelineTimeAccounting;
+
+impl PipelineTimeAccounting {
+    /// Compute per-pipeline and per-fragment time from the DetailProfile tasks,
+    /// falling back to the MergedProfile averages
+    pub fn compute(profile: &Profile) -> Option<TimeAccounting> {
+        let detail = profile.detail_profile.as_ref().filter(|d| !d.fragments.is_empty());
+        let pipelines = match detail {
+            Some(detail) => Self::from_detail(detail),
+            None => Self::from_merged(&profile.fragments),
+        };
+        if pipelines.is_empty() {
+            return None;
+        }
+        
+        let mut fragments: Vec<FragmentTime> = Vec::new();
+        for pipeline in &pipelines {
+            match fragments.iter_mut().find(|f| f.fragment_id == pipeline.fragment_id) {
+                Some(fragment) => fragment.time.add(&pipeline.time),
+                None => fragments.push(FragmentTime {
+                    fragment_id: pipeline.fragment_id.clone(),
+                    time: pipeline.time.clone(),
+                }),
+            }
+        }
+        
+        Some(TimeAccounting {
+            from_detail_profile: detail.is_some(),
+            pipelines,
+            fragments,
+        })
+    }
+    
+    /// Analyze a profile for CPU-starved BEs and pipelines blocked on dependencies
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let Some(accounting) = Self::compute(profile) else {
+            return Vec::new();
+        };
+        
+        let mut hotspots = Vec::new();
+        hotspots.extend(Self::check_wait_worker(&accounting));
+        hotspots.extend(Self::check_dependency_wait(profile, &accounting));
+        hotspots
+    }
+    
+    /// Tasks spending a large share of their runnable time waiting for a worker thread
+    fn check_wait_worker(accounting: &TimeAccounting) -> Option<HotSpot> {
+        let mut total = TimeBreakdown::default();
+        for fragment in &accounting.fragments {
+            total.add(&fragment.time);
+        }
+        
+        // Blocked time is not competing for CPU
+        let runnable_ns = total.running_ns + total.sink_ns + total.wait_worker_ns;
+        if runnable_ns == 0 || total.task_count == 0 {
+            return None;
+        }
+        let pct = total.wait_worker_ns as f64 / runnable_ns as f64 * 100.0;
+        let avg_ns = total.wait_worker_ns as f64 / total.task_count as f64;
+        if avg_ns < thresholds::MIN_WAIT_WORKER_NS {
+            return None;
+        }
+        let severity = if pct >= thresholds::HIGH_WAIT_WORKER_PERCENTAGE {
+            HotspotSeverity::High
+        } else if pct >= thresholds::MEDIUM_WAIT_WORKER_PERCENTAGE {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        let worst = accounting.pipelines.iter()
+            .filter(|p| p.time.task_count > 0)
+            .max_by_key(|p| p.time.wait_worker_ns / p.time.task_count)?;
+        
+        Some(HotSpot {
+            node_id: "be/worker-threads".to_string(),
+            node_path: "BE pipeline worker threads".to_string(),
+            operator_name: "WaitWorkerTime".to_string(),
+            node_type: NodeType::Unknown,
+            severity,
+            description: format!(
+                "Pipeline tasks waited {:.1}% of their runnable time for a worker thread ({} per task); \
+                 longest in {} {} ({} per task)",
+                pct,
+                counters::format_time(avg_ns),
+                worst.fragment_id,
+                worst.pipeline_id,
+                counters::format_time(worst.time.wait_worker_ns as f64 / worst.time.task_count as f64),
+            ),
+            kind: HotspotKind::PipelineTime,
+            time_percentage: None,
+            suggestion: Some(
+                "Tasks are ready to run but wait for a pipeline worker thread: the BEs are CPU-starved.\n\
+                 Check BE CPU usage and concurrent queries; limit concurrency with workload groups or a lower parallel_pipeline_task_num, \
+                 or add BE CPU capacity".to_string()
+            ),
+            suggestion_source: Some("default".to_string()),
+        })
+    }
+    
+    /// Pipelines whose tasks are blocked on upstream dependencies for most of the query
+    fn check_dependency_wait(profile: &Profile, accounting: &TimeAccounting) -> Vec<HotSpot> {
+        let Some(total_ns) = profile.summary.total_time_ms.map(|ms| ms * 1_000_000.0).filter(|t| *t > 0.0) else {
+            return Vec::new();
+        };
+        
+        let mut blocked: Vec<(&PipelineTime, f64)> = accounting.pipelines.iter()
+            .filter(|p| p.time.task_count > 0)
+            .map(|p| (p, p.time.dependency_wait_ns as f64 / p.time.task_count as f64))
+            .filter(|(p, avg_ns)| {
+                *avg_ns >= thresholds::MIN_DEPENDENCY_WAIT_NS
+                    && p.time.share(p.time.dependency_wait_ns) >= thresholds::MIN_DEPENDENCY_WAIT_TASK_PERCENTAGE
+            })
+            .collect();
+        blocked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        
+        blocked.into_iter()
+            .take(MAX_DEPENDENCY_WAIT_HOTSPOTS)
+            .filter_map(|(pipeline, avg_ns)| {
+                let pct = (avg_ns / total_ns * 100.0).min(100.0);
+                let severity = if pct >= thresholds::HIGH_DEPENDENCY_WAIT_PERCENTAGE {
+                    HotspotSeverity::High
+                } else if pct >= thresholds::MEDIUM_DEPENDENCY_WAIT_PERCENTAGE {
+                    HotspotSeverity::Medium
+                } else {
+                    return None;
+                };
+                
+                let source = Self::source_node(profile, pipeline);
+                let node_type = source.map(|n| n.node_type).unwrap_or_default();
+                let waits_on = if node_type.is_exchange() {
+                    "data from an exchange"
+                } else {
+                    "a dependency"
+                };
+                let description = format!(
+                    "{} {} is blocked on {} for {} per task ({:.1}% of the query, {:.1}% of its task time)",
+                    pipeline.fragment_id,
+                    pipeline.pipeline_id,
+                    waits_on,
+                    counters::format_time(avg_ns),
+                    pct,
+                    pipeline.time.share(pipeline.time.dependency_wait_ns),
+                );
+                
+                Some(HotSpot {
+                    node_id: source.map(|n| n.id.clone())
+                        .unwrap_or_else(|| format!("{}-{}", pipeline.fragment_id, pipeline.pipeline_id)),
+                    node_path: source.map(PerformanceBottleneck::build_node_path)
+                        .unwrap_or_else(|| format!("{} > {}", pipeline.fragment_id, pipeline.pipeline_id)),
+                    operator_name: pipeline.source_operator.clone()
+                        .unwrap_or_else(|| pipeline.pipeline_id.clone()),
+                    node_type,
+                    severity,
+                    description,
+                    kind: HotspotKind::PipelineTime,
+                    time_percentage: Some(pct),
+                    suggestion: Some(Self::dependency_suggestion(node_type)),
+                    suggestion_source: Some("default".to_string()),
+                })
+            })
+            .collect()
+    }
+    
+    fn dependency_suggestion(node_type: NodeType) -> String {
+        let lines: &[&str] = if node_type.is_exchange() {
+            &[
+                "This pipeline mostly waits for data from another fragment; its own operators are not the bottleneck",
+                "Look at the fragment sending to this exchange (see the critical path), its scans and the network",
+            ]
+        } else {
+            &[
+                "This pipeline mostly waits for a dependency, e.g. a hash table build, a runtime filter or scanners",
+                "Speed up the operator it depends on, such as the join build side or the scan feeding it",
+            ]
+        };
+        lines.join("\n")
+    }
+    
+    /// Tree node of the operator the pipeline reads from
+    fn source_node<'a>(profile: &'a Profile, pipeline: &PipelineTime) -> Option<&'a ExecutionTreeNode> {
+        let tree = profile.execution_tree.as_ref()?;
+        let source = pipeline.source_operator.as_deref()?;
+        tree.nodes.iter().find(|n| {
+            n.fragment_id.as_deref() == Some(pipeline.fragment_id.as_str())
+                && n.pipeline_id.as_deref() == Some(pipeline.pipeline_id.as_str())
+                && n.operator_name == source
+        })
+    }
+    
+    /// Sum PipelineTask counters of every backend per pipeline
+    fn from_detail(detail: &DetailProfile) -> Vec<PipelineTime> {
+        let mut pipelines: Vec<PipelineTime> = Vec::new();
+        
+        for fragment in &detail.fragments {
+            for backend in &fragment.backends {
+                for pipeline in &backend.pipelines {
+                    let mut time = TimeBreakdown::default();
+                    for task in &pipeline.tasks {
+                        time.add(&Self::task_time(task));
+                    }
+                    // Operators are listed from the sink to the source
+                    let source_operator = pipeline.tasks.first()
+                        .and_then(|t| t.operators.last())
+                        .map(|o| o.operator_name.clone());
+                    
+                    match pipelines.iter_mut().find(|p| p.fragment_id == fragment.id && p.pipeline_id == pipeline.id) {
+                        Some(existing) => existing.time.add(&time),
+                        None => pipelines.push(PipelineTime {
+                            fragment_id: fragment.id.clone(),
+                            pipeline_id: pipeline.id.clone(),
+                            source_operator,
+                            time,
+                        }),
+                    }
+                }
+            }
+        }
+        
+        pipelines
+    }
+    
+    fn task_time(task: &PipelineTask) -> TimeBreakdown {
+        let count = |key: &str| {
+            OperatorParser::find_counter(&task.task_counters, key)
+                .and_then(CounterValue::total)
+                .map(|v| v as u64)
+        };
+        let sink_ns = task.task_counters.iter()
+            .find(|item| item.key == "ExecuteTime")
+            .and_then(|item| OperatorParser::find_counter(&item.children, "SinkTime"))
+            .and_then(CounterValue::total)
+            .unwrap_or(0.0) as u64;
+        let execute_ns = task.execute_time.unwrap_or(0);
+        // BlockedByDependency is only a time in BE versions that account it per task
+        let dependency_wait_ns = OperatorParser::find_counter(&task.task_counters, "BlockedByDependency")
+            .filter(|counter| counter.kind == CounterKind::Time)
+            .and_then(CounterValue::total)
+            .unwrap_or_else(|| {
+                task.operators.iter()
+                    .map(|op| Self::dependency_wait(&op.common_counters).max(Self::dependency_wait(&op.custom_counters)))
+                    .sum::<f64>()
+            });
+        
+        TimeBreakdown {
+            task_count: 1,
+            running_ns: execute_ns.saturating_sub(sink_ns),
+            sink_ns: sink_ns.min(execute_ns),
+            wait_worker_ns: task.wait_worker_time.unwrap_or(0),
+            dependency_wait_ns: dependency_wait_ns as u64,
+            task_cpu_ns: task.task_cpu_time,
+            schedule_times: count("NumScheduleTimes"),
+            yield_times: count("NumYieldTimes"),
+            core_change_times: count("CoreChangeTimes"),
+        }
+    }
+    
+    /// Estimate from per-instance averages of the MergedProfile: the first operator
+    /// of a pipeline is its sink, WaitWorkerTime is a pipeline counter
+    fn from_merged(fragments: &[Fragment]) -> Vec<PipelineTime> {
+        let mut pipelines = Vec::new();
+        
+        for fragment in fragments {
+            for pipeline in &fragment.pipelines {
+                let instances = pipeline.metrics.get("instance_num")
+                    .and_then(CounterValue::total)
+                    .unwrap_or(1.0);
+                let operators = OperatorParser::extract_parsed_operators(&pipeline.raw_text);
+                let exec_time = |op: &ParsedOperator| {
+                    OperatorParser::find_counter(&op.common_counters, "ExecTime")
+                        .and_then(CounterValue::average)
+                        .unwrap_or(0.0)
+                };
+                
+                let sink_ns = operators.first().map(exec_time).unwrap_or(0.0);
+                let running_ns: f64 = operators.iter().skip(1).map(exec_time).sum();
+                let dependency_wait_ns: f64 = operators.iter()
+                    .map(|op| Self::dependency_wait(&op.common_counters).max(Self::dependency_wait(&op.custom_counters)))
+                    .sum();
+                let wait_worker_ns = pipeline.metrics.get("WaitWorkerTime")
+                    .and_then(CounterValue::average)
+                    .unwrap_or(0.0);
+                
+                pipelines.push(PipelineTime {
+                    fragment_id: fragment.id.clone(),
+                    pipeline_id: pipeline.id.clone(),
+                    source_operator: operators.last().map(|op| op.name.clone()),
+                    time: TimeBreakdown {
+                        task_count: instances as u64,
+                        running_ns: (running_ns * instances) as u64,
+                        sink_ns: (sink_ns * instances) as u64,
+                        wait_worker_ns: (wait_worker_ns * instances) as u64,
+                        dependency_wait_ns: (dependency_wait_ns * instances) as u64,
+                        ..Default::default()
+                    },
+                });
+            }
+        }
+        
+        pipelines
+    }
+    
+    /// Longest dependency wait among the counters of an operator
+    fn dependency_wait(items: &[MetricItem]) -> f64 {
+        counters::max_matching(items, &CriticalPathAnalysis::is_wait_counter, CounterValue::average)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::counters::fixtures::{items, profile};
+    
+    fn pipeline(id: &str, tasks: u64, running_ms: u64, wait_worker_ms: u64, dependency_wait_ms: u64) -> PipelineTime {
+        let ms = 1_000_000;
+        PipelineTime {
+            fragment_id: "Fragment 1".to_string(),
+            pipeline_id: id.to_string(),
+            source_operator: None,
+            time: TimeBreakdown {
+                task_count: tasks,
+                running_ns: running_ms * ms,
+                wait_worker_ns: wait_worker_ms * ms,
+                dependency_wait_ns: dependency_wait_ms * ms,
+                ..Default::default()
+            },
+        }
+    }
+    
+    fn accounting(pipelines: Vec<PipelineTime>) -> TimeAccounting {
+        let mut time = TimeBreakdown::default();
+        for pipeline in &pipelines {
+            time.add(&pipeline.time);
+        }
+        TimeAccounting {
+            from_detail_profile: true,
+            pipelines,
+            fragments: vec![FragmentTime { fragment_id: "Fragment 1".to_string(), time }],
+        }
+    }
+    
+    #[test]
+    fn test_cpu_starved_tasks() {
+        // 8 tasks ran 800ms in total but waited 1.2s for a worker thread
+        let starved = accounting(vec![
+            pipeline("Pipeline 0", 4, 400, 1000, 0),
+            pipeline("Pipeline 1", 4, 400, 200, 0),
+        ]);
+        let hotspot = PipelineTimeAccounting::check_wait_worker(&starved).expect("Should flag CPU starvation");
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert_eq!(hotspot.node_type, NodeType::Unknown);
+        assert!(hotspot.description.contains("60.0%"));
+        assert!(hotspot.description.contains("Fragment 1 Pipeline 0 (250ms per task)"));
+        
+        // Long dependency waits are not CPU starvation
+        let blocked = accounting(vec![pipeline("Pipeline 0", 4, 400, 40, 8000)]);
+        assert!(PipelineTimeAccounting::check_wait_worker(&blocked).is_none());
+    }
+    
+    #[test]
+    fn test_blocked_pipelines() {
+        let profile = profile(vec![], ProfileSummary {
+            total_time_ms: Some(2000.0),
+            ..Default::default()
+        });
+        let blocked = accounting(vec![
+            pipeline("Pipeline 0", 2, 100, 0, 3000),
+            pipeline("Pipeline 1", 2, 100, 0, 1400),
+            pipeline("Pipeline 2", 2, 3000, 0, 1200),
+        ]);
+        
+        let hotspots = PipelineTimeAccounting::check_dependency_wait(&profile, &blocked);
+        assert_eq!(hotspots.len(), 2);
+        assert_eq!(hotspots[0].severity, HotspotSeverity::High);
+        assert_eq!(hotspots[0].time_percentage, Some(75.0));
+        assert_eq!(hotspots[0].node_path, "Fragment 1 > Pipeline 0");
+        assert_eq!(hotspots[1].severity, HotspotSeverity::Medium);
+        assert!(hotspots[1].description.contains("blocked on a dependency for 700ms per task"));
+    }
+    
+    #[test]
+    fn test_task_dependency_wait() {
+        let task = |blocked: &str| PipelineTask {
+            index: 0,
+            execute_time: Some(100_000_000),
+            task_cpu_time: None,
+            wait_worker_time: None,
+            task_counters: items(&[("BlockedByDependency", blocked)]),
+            operators: vec![TaskOperator {
+                operator_name: "EXCHANGE_OPERATOR".to_string(),
+                plan_node_id: 3,
+                nereids_id: None,
+                dest_id: None,
+                exec_time: None,
+                rows_produced: None,
+                input_rows: None,
+                memory_peak: None,
+                common_counters: items(&[("WaitForDependencyTime", "200.000ms")]),
+                custom_counters: vec![],
+            }],
+        };
+        
+        // The task-level counter when the BE reports it, the operator waits otherwise
+        assert_eq!(PipelineTimeAccounting::task_time(&task("1.500s")).dependency_wait_ns, 1_500_000_000);
+        assert_eq!(PipelineTimeAccounting::task_time(&task("")).dependency_wait_ns, 200_000_000);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::models::*;
+use crate::constants::thresholds;
+
+/// PlanningOverhead flags queries where FE planning, split enumeration or
+/// scheduling takes a large share of the total query time
+pub struct PlanningOverhead;
+
+/// FE phases checked, with the hotspot label used in suggestion titles
+const CHECKED_PHASES: &[(&str, &str)] = &[
+    ("Plan Time", "planning"),
+    ("Get Splits Time", "split enumeration"),
+    ("Schedule Time", "scheduling"),
+];
+
+impl PlanningOverhead {
+    /// Analyze a profile and return one hotspot per dominating FE phase
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let summary = &profile.summary;
+        let Some(total_ms) = summary.total_time_ms.filter(|t| *t > 0.0) else {
+            return Vec::new();
+        };
+        
+        CHECKED_PHASES.iter()
+            .filter_map(|(name, label)| {
+                let phase = FePhase::find(&summary.fe_phases, name)?;
+                Self::check_phase(phase, label, total_ms, summary)
+            })
+            .collect()
+    }
+    
+    /// Build a hotspot if the phase is long enough and a large enough share of the query
+    fn check_phase(phase: &FePhase, label: &str, total_ms: f64, summary: &ProfileSummary) -> Option<HotSpot> {
+        let duration_ms = phase.duration_ms?;
+        if duration_ms < thresholds::MIN_PLANNING_OVERHEAD_MS {
+            return None;
+        }
+        
+        let percentage = duration_ms / total_ms * 100.0;
+        let severity = Self::determine_severity(percentage);
+        if severity == HotspotSeverity::None {
+            return None;
+        }
+        
+        let mut description = format!(
+            "{} {} is {:.1}% of total query time {}",
+            phase.name, phase.value, percentage, summary.total_time
+        );
+        let slowest = phase.slowest_child().filter(|child| child.duration_ms.unwrap_or(0.0) > 0.0);
+        if let Some(child) = slowest {
+            description.push_str(&format!("; slowest sub-phase: {} {}", child.name, child.value));
+        }
+        
+        Some(HotSpot {
+            node_id: format!("fe/{}", phase.name),
+            node_path: format!("FE > {}", phase.name),
+            operator_name: label.to_string(),
+            node_type: NodeType::Unknown,
+            severity,
+            description,
+            kind: HotspotKind::PlanningOverhead,
+            time_percentage: Some(percentage),
+            suggestion: Some(Self::generate_suggestion(phase, slowest, summary)),
+            suggestion_source: Some("default".to_string()),
+        })
+    }
+    
+    /// Severity from the share of total query time
+    fn determine_severity(percentage: f64) -> HotspotSeverity {
+        if percentage >= thresholds::HIGH_PLANNING_PERCENTAGE {
+            HotspotSeverity::High
+        } else if percentage >= thresholds::MEDIUM_PLANNING_PERCENTAGE {
+            HotspotSeverity::Medium
+        } else if percentage >= thresholds::LOW_PLANNING_PERCENTAGE {
+            HotspotSeverity::Low
+        } else {
+            HotspotSeverity::None
+        }
+    }
+    
+    /// Suggest fixes for the phase, pointing at its slowest sub-phase when known
+    fn generate_suggestion(phase: &FePhase, slowest: Option<&FePhase>, summary: &ProfileSummary) -> String {
+        let mut lines = Vec::new();
+        
+        match phase.name.as_str() {
+            "Get Splits Time" => {
+                lines.push("The FE spends a long time listing the files / splits of external tables".to_string());
+                lines.push("Add partition predicates so fewer partitions are listed, compact small files, and keep the catalog file metadata cache enabled (file.meta.cache.ttl-second)".to_string());
+            }
+            "Schedule Time" => {
+                lines.push("The FE spends a long time sending fragments to the BEs".to_string());
+                let details: Vec<String> = ["Fragment Compressed Size", "Fragment RPC Count"].iter()
+                    .filter_map(|name| FePhase::find(&phase.children, name))
+                    .map(|p| format!("{} {}", p.name, p.value))
+                    .chain(summary.execution_summary.get("Total Instances Num").map(|n| format!("Total Instances Num {}", n)))
+                    .collect();
+                if !details.is_empty() {
+                    lines.push(format!("Fragment plan: {}", details.join(", ")));
+                }
+                lines.push("Reduce the number of instances (parallel_pipeline_task_num) for small queries, and check FE-BE network latency and BE RPC thread pool load".to_string());
+            }
+            _ => {
+                let hint = match slowest.map(|p| p.name.as_str()) {
+                    Some(name) if name.contains("Translate") || name.contains("Scan Node") => {
+                        "Most of it is spent building scan ranges. For external tables check split enumeration and metadata cache; for internal tables reduce the number of scanned partitions / tablets"
+                    }
+                    Some(name) if name.contains("Optimize") || name.contains("Rewrite") => {
+                        "Most of it is spent in the Nereids optimizer. Simplify the query (fewer joined tables, fewer nested subqueries) or materialize intermediate results"
+                    }
+                    Some(name) if name.contains("Lock") || name.contains("Collect Table Partition") || name.contains("Meta") => {
+                        "Most of it is spent waiting for table metadata. Check for concurrent DDL, loads holding table locks, or slow external metastores"
+                    }
+                    _ => "Check which sub-phase of Plan Time dominates in the Execution Summary",
+                };
+                lines.push("FE planning takes a large share of this query".to_string());
+                lines.push(hint.to_string());
+                lines.push("For frequently repeated short queries, use prepared statements or the SQL cache (enable_sql_cache) to skip planning".to_string());
+            }
+        }
+        
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn phase(name: &str, value: &str, duration_ms: f64, children: Vec<FePhase>) -> FePhase {
+        FePhase {
+            name: name.to_string(),
+            value: value.to_string(),
+            duration_ms: Some(duration_ms),
+            children,
+        }
+    }
+    
+    fn create_profile(total_ms: f64, fe_phases: Vec<FePhase>) -> Profile {
+        Profile {
+            summary: ProfileSummary {
+                total_time: "1sec240ms".to_string(),
+                total_time_ms: Some(total_ms),
+                fe_phases,
+                ..Default::default()
+            },
+            fragments: Vec::new(),
+            execution_tree: None,
+            detail_profile: None,
+            physical_plan: None,
+            runtime_filters: Vec::new(),
+            parse_warnings: Vec::new(),
+        }
+    }
+    
+    #[test]
+    fn test_planning_overhead() {
+        let plan = phase("Plan Time", "368ms", 368.0, vec![
+            phase("Nereids Analysis Time", "4ms", 4.0, Vec::new()),
+            phase("Nereids Translate Time", "337ms", 337.0, vec![
+                phase("Get Splits Time", "43ms", 43.0, Vec::new()),
+            ]),
+        ]);
+        let schedule = phase("Schedule Time", "33ms", 33.0, Vec::new());
+        let profile = create_profile(1240.0, vec![plan, schedule]);
+        
+        let hotspots = PlanningOverhead::analyze(&profile);
+        assert_eq!(hotspots.len(), 1, "short splits and scheduling are not reported");
+        assert_eq!(hotspots[0].severity, HotspotSeverity::Medium);
+        assert_eq!(hotspots[0].operator_name, "planning");
+        assert_eq!(
+            hotspots[0].description,
+            "Plan Time 368ms is 29.7% of total query time 1sec240ms; slowest sub-phase: Nereids Translate Time 337ms"
+        );
+        assert!(hotspots[0].suggestion.as_ref().unwrap().contains("scan ranges"));
+    }
+    
+    #[test]
+    fn test_determine_severity() {
+        assert_eq!(PlanningOverhead::determine_severity(60.0), HotspotSeverity::High);
+        assert_eq!(PlanningOverhead::determine_severity(30.0), HotspotSeverity::Medium);
+        assert_eq!(PlanningOverhead::determine_severity(12.0), HotspotSeverity::Low);
+        assert_eq!(PlanningOverhead::determine_severity(5.0), HotspotSeverity::None);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::counters::{self, OperatorCounters};
+
+/// RemoteIoAnalysis reads the S3Profile counters of FILE_SCAN_OPERATOR and
+/// reports request rate, request size and throttling per scan, so a query slowed
+/// by object storage 429s can be told apart from one short of CPU
+pub struct RemoteIoAnalysis;
+
+impl RemoteIoAnalysis {
+    /// Collect the object storage requests of every external table scan
+    pub fn compute(profile: &Profile) -> Option<RemoteIoModel> {
+        let tree = profile.execution_tree.as_ref()?;
+        let task_operators = counters::task_operators(profile);
+        let total_seconds = profile.summary.total_time_ms.map(|ms| ms / 1000.0).filter(|s| *s > 0.0);
+        
+        let scans: Vec<RemoteIoScan> = tree.nodes.iter()
+            .filter(|n| n.node_type == NodeType::FileScan)
+            .filter_map(|node| {
+                let plan_node_id = node.plan_node_id?;
+                let counters = OperatorCounters::new(&task_operators, node, |op| op.plan_node_id == plan_node_id);
+                Self::scan(&counters, total_seconds)
+            })
+            .collect();
+        
+        if scans.is_empty() {
+            return None;
+        }
+        Some(RemoteIoModel { scans })
+    }
+    
+    /// Analyze a profile for scans throttled by object storage or bound by small requests
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let (Some(model), Some(tree)) = (Self::compute(profile), profile.execution_tree.as_ref()) else {
+            return Vec::new();
+        };
+        
+        let mut hotspots = Vec::new();
+        for scan in &model.scans {
+            let Some(node) = tree.nodes.iter().find(|n| n.id == scan.node_id) else {
+                continue;
+            };
+            // Throttling explains small requests too, so report only one of them
+            if let Some(hotspot) = Self::check_throttling(node, scan) {
+                hotspots.push(hotspot);
+            } else {
+                hotspots.extend(Self::check_small_requests(node, scan));
+            }
+        }
+        hotspots
+    }
+    
+    /// Requests rejected with 429 Too Many Requests and retried after a back-off
+    fn check_throttling(node: &ExecutionTreeNode, scan: &RemoteIoScan) -> Option<HotSpot> {
+        let throttled = scan.throttled_requests.filter(|t| *t > 0)?;
+        let pct = scan.throttle_percentage?;
+        let severity = if pct >= thresholds::HIGH_THROTTLE_PERCENTAGE {
+            HotspotSeverity::High
+        } else if pct >= thresholds::MEDIUM_THROTTLE_PERCENTAGE {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        let description = format!(
+            "{} was throttled by object storage: {} of {} GET requests were rejected with 429 Too Many Requests \
+             and the scanners slept {} ({:.1}% of scanner time) before retrying; {}",
+            Self::scan_label(scan),
+            throttled,
+            scan.get_requests,
+            counters::format_time(scan.throttle_sleep_ns.unwrap_or(0) as f64),
+            pct,
+            Self::request_summary(scan),
+        );
+        
+        let mut suggestion = vec![
+            "The scan sends more requests than the bucket or prefix allows, so object storage rejects them and the scanners back off.",
+        ];
+        if Self::is_unmerged(scan) {
+            suggestion.push("Small column chunks are fetched one GET each: read fewer columns so merged IO can combine adjacent ranges into fewer, larger requests.");
+        }
+        suggestion.push("Compact small files into larger ones; every file costs a footer request and at least one data request.");
+        suggestion.push("Lower the scanner concurrency (parallel_pipeline_task_num, or doris_scanner_thread_pool_thread_num on the BEs) so fewer requests are in flight at once.");
+        suggestion.push("Spread the data over more prefixes or ask the storage provider to raise the request rate limit of the bucket.");
+        
+        Some(counters::hotspot(node, HotspotKind::RemoteIo, severity, description, suggestion.join("\n")))
+    }
+    
+    /// Many small GET requests whose latency dominates the scanner time
+    fn check_small_requests(node: &ExecutionTreeNode, scan: &RemoteIoScan) -> Option<HotSpot> {
+        let avg_bytes = scan.avg_request_bytes?;
+        let request_ns = scan.get_request_time_ns? as f64;
+        let scanner_ns = scan.scanner_time_ns.filter(|t| *t > 0)? as f64;
+        let pct = (request_ns / scanner_ns * 100.0).min(100.0);
+        if scan.get_requests < thresholds::MIN_SMALL_REQUESTS
+            || avg_bytes >= thresholds::SMALL_REQUEST_BYTES
+            || pct < thresholds::HIGH_REQUEST_TIME_PERCENTAGE
+        {
+            return None;
+        }
+        
+        let description = format!(
+            "{} waited for object storage {:.1}% of its scanner time: {}",
+            Self::scan_label(scan),
+            pct,
+            Self::request_summary(scan),
+        );
+        
+        let mut suggestion = vec![
+            "Each GET pays the full object storage latency, so many small requests make the scan latency-bound rather than bandwidth-bound.",
+        ];
+        if Self::is_unmerged(scan) {
+            suggestion.push("Few requests were merged: read fewer columns so merged IO can combine adjacent ranges into larger requests.");
+        }
+        suggestion.push("Compact small files into larger ones with larger row groups.");
+        suggestion.push("Enable the BE file cache (enable_file_cache) so repeated scans read from local disk.");
+        
+        Some(counters::hotspot(node, HotspotKind::RemoteIo, HotspotSeverity::Medium, description, suggestion.join("\n")))
+    }
+    
+    fn scan(counters: &OperatorCounters, total_seconds: Option<f64>) -> Option<RemoteIoScan> {
+        let node = counters.node;
+        // Only scans reading from object storage have an S3Profile
+        let get_requests = counters.sum("TotalGetRequest")?;
+        let get_request_time_ns = counters.sum("TotalGetRequestTime");
+        let bytes_read = counters.sum("TotalBytesRead");
+        let throttle_sleep_ns = counters.sum("TooManyRequestSleepTime");
+        let scanner_time_ns = counters.sum("FileScannerGetBlockTime");
+        
+        let throttle_percentage = match (throttle_sleep_ns, scanner_time_ns) {
+            (Some(sleep), Some(scanner)) if scanner > 0 => Some((sleep as f64 / scanner as f64 * 100.0).min(100.0)),
+            _ => None,
+        };
+        
+        Some(RemoteIoScan {
+            node_id: node.id.clone(),
+            table_name: node.table_name.clone(),
+            fragment_id: node.fragment_id.clone(),
+            get_requests,
+            get_request_time_ns,
+            bytes_read,
+            requests_per_second: total_seconds.map(|s| get_requests as f64 / s),
+            avg_request_bytes: bytes_read.filter(|_| get_requests > 0).map(|b| b / get_requests),
+            avg_request_time_ns: get_request_time_ns.filter(|_| get_requests > 0).map(|t| t / get_requests),
+            throttled_requests: counters.sum("TooManyRequestErr"),
+            throttle_sleep_ns,
+            throttle_percentage,
+            request_io: counters.sum("RequestIO"),
+            merged_io: counters.sum("MergedIO"),
+            scanner_time_ns,
+        })
+    }
+    
+    /// Merged IO did not combine the reads, or the profile shows no merged IO at all
+    fn is_unmerged(scan: &RemoteIoScan) -> bool {
+        match (scan.request_io, scan.merged_io) {
+            (Some(requested), Some(merged)) => merged >= requested,
+            _ => true,
+        }
+    }
+    
+    fn request_summary(scan: &RemoteIoScan) -> String {
+        let mut summary = format!("{} GET requests", scan.get_requests);
+        if let Some(rate) = scan.requests_per_second {
+            summary.push_str(&format!(" ({:.0}/s)", rate));
+        }
+        if let Some(bytes) = scan.avg_request_bytes {
+            summary.push_str(&format!(" of {} on average", counters::format_bytes(bytes)));
+        }
+        if let Some(ns) = scan.avg_request_time_ns {
+            summary.push_str(&format!(", {} each", counters::format_time(ns as f64)));
+        }
+        summary
+    }
+    
+    fn scan_label(scan: &RemoteIoScan) -> String {
+        match scan.table_name {
+            Some(ref table) => format!("The scan of {}", table),
+            None => "The file scan".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::counters::fixtures::node;
+    
+    fn file_scan() -> ExecutionTreeNode {
+        ExecutionTreeNode {
+            plan_node_id: Some(20),
+            time_percentage: Some(60.0),
+            table_name: Some("orders".to_string()),
+            ..node(1, 4, "id20", "FILE_SCAN_OPERATOR", NodeType::FileScan)
+        }
+    }
+    
+    fn scan() -> RemoteIoScan {
+        RemoteIoScan {
+            node_id: "Fragment 1-Pipeline 4-id20".to_string(),
+            table_name: Some("orders".to_string()),
+            get_requests: 20_000,
+            get_request_time_ns: Some(80_000_000_000),
+            bytes_read: Some(20_000 * 64 * 1024),
+            requests_per_second: Some(2000.0),
+            avg_request_bytes: Some(64 * 1024),
+            avg_request_time_ns: Some(4_000_000),
+            throttled_requests: Some(0),
+            throttle_sleep_ns: Some(0),
+            throttle_percentage: Some(0.0),
+            request_io: Some(30_000),
+            merged_io: Some(20_000),
+            scanner_time_ns: Some(100_000_000_000),
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn test_throttled_scan() {
+        assert!(RemoteIoAnalysis::check_throttling(&file_scan(), &scan()).is_none());
+        
+        let throttled = RemoteIoScan {
+            throttled_requests: Some(1500),
+            throttle_sleep_ns: Some(30_000_000_000),
+            throttle_percentage: Some(30.0),
+            ..scan()
+        };
+        let hotspot = RemoteIoAnalysis::check_throttling(&file_scan(), &throttled).unwrap();
+        assert_eq!(hotspot.kind, HotspotKind::RemoteIo);
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert_eq!(
+            hotspot.description,
+            "The scan of orders was throttled by object storage: 1500 of 20000 GET requests were rejected with 429 Too Many Requests \
+             and the scanners slept 30.00s (30.0% of scanner time) before retrying; 20000 GET requests (2000/s) of 64.00 KB on average, 4ms each"
+        );
+        let suggestion = hotspot.suggestion.unwrap();
+        assert!(suggestion.contains("parallel_pipeline_task_num"));
+        assert!(!suggestion.contains("merged IO"));
+        
+        let unmerged = RemoteIoScan { merged_io: Some(30_000), throttle_percentage: Some(8.0), ..throttled.clone() };
+        let hotspot = RemoteIoAnalysis::check_throttling(&file_scan(), &unmerged).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::Medium);
+        assert!(hotspot.suggestion.unwrap().contains("merged IO"));
+        
+        let brief = RemoteIoScan { throttle_percentage: Some(1.0), ..throttled };
+        assert!(RemoteIoAnalysis::check_throttling(&file_scan(), &brief).is_none());
+    }
+    
+    #[test]
+    fn test_small_requests() {
+        let hotspot = RemoteIoAnalysis::check_small_requests(&file_scan(), &scan()).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::Medium);
+        assert_eq!(
+            hotspot.description,
+            "The scan of orders waited for object storage 80.0% of its scanner time: 20000 GET requests (2000/s) of 64.00 KB on average, 4ms each"
+        );
+        
+        let large = RemoteIoScan { avg_request_bytes: Some(4 * 1024 * 1024), ..scan() };
+        assert!(RemoteIoAnalysis::check_small_requests(&file_scan(), &large).is_none());
+        
+        let few = RemoteIoScan { get_requests: 200, ..scan() };
+        assert!(RemoteIoAnalysis::check_small_requests(&file_scan(), &few).is_none());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::PerformanceBottleneck;
+
+/// RuntimeFilterEffectiveness flags runtime filters that did not filter any rows
+/// or whose scans stopped waiting for them because of the timeout
+pub struct RuntimeFilterEffectiveness;
+
+/// Problem found for one filter on one scan
+enum FilterIssue<'a> {
+    Timeout(&'a RuntimeFilter, &'a RuntimeFilterTarget),
+    NoRowsFiltered(&'a RuntimeFilter, &'a RuntimeFilterTarget),
+}
+
+impl RuntimeFilterEffectiveness {
+    /// Analyze a profile and return one hotspot per scan with ineffective filters
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let mut issues_by_node: Vec<(&str, Vec<FilterIssue>)> = Vec::new();
+        
+        for filter in &profile.runtime_filters {
+            for target in &filter.targets {
+                let Some(issue) = Self::check_target(filter, target) else {
+                    continue;
+                };
+                match issues_by_node.iter_mut().find(|(id, _)| *id == target.node_id) {
+                    Some((_, issues)) => issues.push(issue),
+                    None => issues_by_node.push((target.node_id.as_str(), vec![issue])),
+                }
+            }
+        }
+        
+        issues_by_node.into_iter()
+            .filter_map(|(node_id, issues)| Self::build_hotspot(profile, node_id, &issues))
+            .collect()
+    }
+    
+    /// Check a single filter target
+    fn check_target<'a>(filter: &'a RuntimeFilter, target: &'a RuntimeFilterTarget) -> Option<FilterIssue<'a>> {
+        if target.reached_timeout {
+            return Some(FilterIssue::Timeout(filter, target));
+        }
+        
+        let input = target.input_rows.unwrap_or(0);
+        if target.filter_rows == Some(0) && input >= thresholds::MIN_RUNTIME_FILTER_INPUT_ROWS {
+            return Some(FilterIssue::NoRowsFiltered(filter, target));
+        }
+        
+        None
+    }
+    
+    /// Build a hotspot for all issues on one scan
+    fn build_hotspot(profile: &Profile, node_id: &str, issues: &[FilterIssue]) -> Option<HotSpot> {
+        let tree = profile.execution_tree.as_ref()?;
+        let node = tree.nodes.iter().find(|n| n.id == node_id)?;
+        
+        let has_timeout = issues.iter().any(|i| matches!(i, FilterIssue::Timeout(..)));
+        let severity = if has_timeout { HotspotSeverity::High } else { HotspotSeverity::Medium };
+        
+        let details: Vec<String> = issues.iter().map(Self::describe_issue).collect();
+        
+        Some(HotSpot {
+            node_id: node.id.clone(),
+            node_path: PerformanceBottleneck::build_node_path(node),
+            operator_name: node.operator_name.clone(),
+            node_type: node.node_type,
+            severity,
+            description: format!("Ineffective runtime filters: {}", details.join("; ")),
+            kind: HotspotKind::RuntimeFilter,
+            time_percentage: node.time_percentage,
+            suggestion: Some(Self::generate_suggestion(issues)),
+            suggestion_source: Some("default".to_string()),
+        })
+    }
+    
+    /// Describe one issue, e.g. "RF9 (in_or_bloom) filtered 0 of 2221724 rows"
+    fn describe_issue(issue: &FilterIssue) -> String {
+        match issue {
+            FilterIssue::Timeout(filter, target) => {
+                let limit = target.timeout_limit
+                    .map(|ns| format!(" after {}ms", ns / 1_000_000))
+                    .unwrap_or_default();
+                format!("{}{} reached timeout{}", filter.id, Self::filter_label(filter), limit)
+            }
+            FilterIssue::NoRowsFiltered(filter, target) => {
+                format!(
+                    "{}{} filtered 0 of {} rows",
+                    filter.id, Self::filter_label(filter), target.input_rows.unwrap_or(0)
+                )
+            }
+        }
+    }
+    
+    /// Filter type and publishing join for descriptions
+    fn filter_label(filter: &RuntimeFilter) -> String {
+        let mut parts = Vec::new();
+        if let Some(ref filter_type) = filter.filter_type {
+            parts.push(filter_type.clone());
+        }
+        if let Some(ref builder) = filter.builder {
+            parts.push(match builder.nereids_id {
+                Some(id) => format!("from join {}", id),
+                None => format!("from {}", builder.operator_name),
+            });
+        }
+        
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", parts.join(", "))
+        }
+    }
+    
+    /// Suggest fixes depending on the kinds of issues found
+    fn generate_suggestion(issues: &[FilterIssue]) -> String {
+        let mut lines = Vec::new();
+        
+        if issues.iter().any(|i| matches!(i, FilterIssue::Timeout(..))) {
+            lines.push("The scan stopped waiting for some runtime filters and ran without them. The build side of the publishing join is too slow");
+            lines.push("Increase runtime_filter_wait_time_ms, or reduce the build side (filter it earlier, or swap join order so the smaller table builds)");
+        }
+        
+        if issues.iter().any(|i| matches!(i, FilterIssue::NoRowsFiltered(..))) {
+            lines.push("Some runtime filters did not filter any rows. The build side keys likely cover the whole probe key range");
+            lines.push("Such filters only add evaluation cost. If this is typical for the query, drop that filter type via runtime_filter_type");
+        }
+        
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn create_target(input_rows: u64, filter_rows: u64, reached_timeout: bool) -> RuntimeFilterTarget {
+        RuntimeFilterTarget {
+            node_id: "scan".to_string(),
+            operator_name: "FILE_SCAN_OPERATOR".to_string(),
+            table_name: Some("web_sales".to_string()),
+            target_expr: None,
+            input_rows: Some(input_rows),
+            filter_rows: Some(filter_rows),
+            filter_rate: None,
+            wait_time: None,
+            timeout_limit: Some(5_000_000_000),
+            reached_timeout,
+        }
+    }
+    
+    fn create_filter(target: RuntimeFilterTarget) -> RuntimeFilter {
+        RuntimeFilter {
+            id: "RF9".to_string(),
+            filter_type: Some("in_or_bloom".to_string()),
+            source_expr: None,
+            builder: None,
+            targets: vec![target],
+        }
+    }
+    
+    #[test]
+    fn test_check_target() {
+        let zero = create_filter(create_target(2_221_724, 0, false));
+        assert!(matches!(
+            RuntimeFilterEffectiveness::check_target(&zero, &zero.targets[0]),
+            Some(FilterIssue::NoRowsFiltered(..))
+        ));
+        
+        let timeout = create_filter(create_target(2_221_724, 100, true));
+        let issue = RuntimeFilterEffectiveness::check_target(&timeout, &timeout.targets[0]).unwrap();
+        assert!(matches!(issue, FilterIssue::Timeout(..)));
+        assert_eq!(
+            RuntimeFilterEffectiveness::describe_issue(&issue),
+            "RF9 (in_or_bloom) reached timeout after 5000ms"
+        );
+        
+        let effective = create_filter(create_target(2_221_724, 1_000_000, false));
+        assert!(RuntimeFilterEffectiveness::check_target(&effective, &effective.targets[0]).is_none());
+        
+        let small = create_filter(create_target(100, 0, false));
+        assert!(RuntimeFilterEffectiveness::check_target(&small, &small.targets[0]).is_none());
+    }
+}
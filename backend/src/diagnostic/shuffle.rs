@@ -0,0 +1,244 @@
+use crate::models::*;
+use crate::constants::thresholds;
+use crate::diagnostic::counters::{self, OperatorCounters};
+
+/// ShuffleAnalysis pairs every DATA_STREAM_SINK with the EXCHANGE it sends to
+/// and reports the data moved per edge, flagging senders blocked on the network
+/// or on their receivers and RPCs with a long tail
+pub struct ShuffleAnalysis;
+
+impl ShuffleAnalysis {
+    /// Build one edge per sink -> exchange pair connected in the execution tree
+    pub fn compute(profile: &Profile) -> Option<ShuffleModel> {
+        let tree = profile.execution_tree.as_ref()?;
+        let task_operators = counters::task_operators(profile);
+        
+        let mut edges = Vec::new();
+        for exchange in tree.nodes.iter().filter(|n| n.node_type == NodeType::Exchange) {
+            let Some(exchange_id) = exchange.plan_node_id else {
+                continue;
+            };
+            // connect_nodes makes the sinks sending to an exchange its children
+            let sinks = exchange.children.iter()
+                .filter_map(|id| tree.nodes.iter().find(|n| &n.id == id))
+                .filter(|n| n.node_type == NodeType::DataStreamSink);
+            
+            for sink in sinks {
+                let sink_counters = OperatorCounters::new(&task_operators, sink, |op| op.dest_id == Some(exchange_id));
+                let exchange_counters = OperatorCounters::new(&task_operators, exchange, |op| op.plan_node_id == exchange_id);
+                edges.push(Self::edge(sink, exchange, exchange_id, &sink_counters, &exchange_counters));
+            }
+        }
+        
+        if edges.is_empty() {
+            return None;
+        }
+        Some(ShuffleModel { edges })
+    }
+    
+    /// Analyze a profile for network-bound exchange senders and RPCs with a long tail
+    pub fn analyze(profile: &Profile) -> Vec<HotSpot> {
+        let (Some(model), Some(tree)) = (Self::compute(profile), profile.execution_tree.as_ref()) else {
+            return Vec::new();
+        };
+        let total_ns = profile.summary.total_time_ms.map(|ms| ms * 1_000_000.0).filter(|t| *t > 0.0);
+        
+        let mut hotspots = Vec::new();
+        for edge in &model.edges {
+            let Some(sink) = tree.nodes.iter().find(|n| n.id == edge.sink_node_id) else {
+                continue;
+            };
+            hotspots.extend(Self::check_rpc_buffer_wait(sink, edge, total_ns));
+            hotspots.extend(Self::check_rpc_tail(sink, edge, total_ns));
+        }
+        hotspots
+    }
+    
+    /// Senders blocked because earlier RPCs to the receivers have not completed:
+    /// the network or the receiving fragment cannot keep up
+    fn check_rpc_buffer_wait(sink: &ExecutionTreeNode, edge: &ShuffleEdge, total_ns: Option<f64>) -> Option<HotSpot> {
+        let wait_ns = edge.rpc_buffer_wait_ns? as f64;
+        let total_ns = total_ns?;
+        if wait_ns < thresholds::MIN_RPC_BUFFER_WAIT_NS {
+            return None;
+        }
+        let pct = (wait_ns / total_ns * 100.0).min(100.0);
+        let severity = if pct >= thresholds::HIGH_RPC_BUFFER_WAIT_PERCENTAGE {
+            HotspotSeverity::High
+        } else if pct >= thresholds::MEDIUM_RPC_BUFFER_WAIT_PERCENTAGE {
+            HotspotSeverity::Medium
+        } else {
+            return None;
+        };
+        
+        let mut description = format!(
+            "{} is network-bound: its sender to exchange {} waited {} ({:.1}% of the query) for the RPC buffer queue",
+            Self::fragment_label(&edge.sender_fragment_id),
+            edge.exchange_id,
+            counters::format_time(wait_ns),
+            pct,
+        );
+        if let Some(bytes) = edge.bytes_sent {
+            description.push_str(&format!("; {} sent over RPC", counters::format_bytes(bytes)));
+            if let Some(local) = edge.local_percentage {
+                description.push_str(&format!(", {:.1}% of the data stayed local", local));
+            }
+        }
+        
+        let mut suggestion = vec![
+            "Senders wait until earlier RPCs to the receivers complete: the network or the receiving fragment cannot keep up.",
+            "Check the bandwidth between BEs and whether the receiving fragment is slow or blocked itself.",
+            "Send less data: filter and project columns before the exchange, pre-aggregate, or use a bucket shuffle / colocate join instead of a shuffle.",
+        ];
+        if edge.compression_ratio.map_or(true, |ratio| ratio <= 1.0) {
+            suggestion.push("Blocks are sent uncompressed; set fragment_transmission_compression_codec = lz4 to trade CPU for network.");
+        }
+        
+        Some(HotSpot {
+            time_percentage: Some(pct),
+            ..counters::hotspot(sink, HotspotKind::Shuffle, severity, description, suggestion.join("\n"))
+        })
+    }
+    
+    /// A few RPCs far slower than the average point at one slow BE or link
+    fn check_rpc_tail(sink: &ExecutionTreeNode, edge: &ShuffleEdge, total_ns: Option<f64>) -> Option<HotSpot> {
+        let max_ns = edge.rpc_max_ns? as f64;
+        let avg_ns = edge.rpc_avg_ns.filter(|avg| *avg > 0)? as f64;
+        if max_ns < thresholds::MIN_RPC_TAIL_NS || max_ns / avg_ns < thresholds::RPC_TAIL_RATIO {
+            return None;
+        }
+        let pct = total_ns.map(|total| (max_ns / total * 100.0).min(100.0));
+        let severity = match pct {
+            Some(pct) if pct >= thresholds::HIGH_RPC_TAIL_PERCENTAGE => HotspotSeverity::High,
+            _ => HotspotSeverity::Medium,
+        };
+        
+        let description = format!(
+            "The slowest RPC from {} to exchange {} took {}, {:.0}x the average of {} over {} RPCs",
+            Self::fragment_label(&edge.sender_fragment_id),
+            edge.exchange_id,
+            counters::format_time(max_ns),
+            max_ns / avg_ns,
+            counters::format_time(avg_ns),
+            edge.rpc_count.unwrap_or(0),
+        );
+        
+        Some(HotSpot {
+            time_percentage: pct,
+            ..counters::hotspot(
+                sink,
+                HotspotKind::Shuffle,
+                severity,
+                description,
+                "A few exchange RPCs are far slower than the rest, which usually points at one busy BE or a congested link.\n\
+                 Compare the BEs of the sending and receiving fragments (CPU, network, brpc thread pools) and retry after the load drops",
+            )
+        })
+    }
+    
+    fn edge(
+        sink: &ExecutionTreeNode,
+        exchange: &ExecutionTreeNode,
+        exchange_id: i32,
+        sink_counters: &OperatorCounters,
+        exchange_counters: &OperatorCounters,
+    ) -> ShuffleEdge {
+        let bytes_sent = sink_counters.sum("BytesSent");
+        let local_bytes_sent = sink_counters.sum("LocalBytesSent");
+        let uncompressed_bytes = sink_counters.sum("UncompressedRowBatchSize");
+        
+        let compression_ratio = match (uncompressed_bytes, bytes_sent) {
+            (Some(raw), Some(sent)) if raw > 0 && sent > 0 => Some(raw as f64 / sent as f64),
+            _ => None,
+        };
+        let local_percentage = match (local_bytes_sent, bytes_sent) {
+            (Some(local), Some(sent)) if local + sent > 0 => Some(local as f64 / (local + sent) as f64 * 100.0),
+            _ => None,
+        };
+        
+        ShuffleEdge {
+            sink_node_id: sink.id.clone(),
+            exchange_node_id: exchange.id.clone(),
+            exchange_id,
+            sender_fragment_id: sink.fragment_id.clone(),
+            receiver_fragment_id: exchange.fragment_id.clone(),
+            rows: sink_counters.sum("InputRows").or_else(|| exchange_counters.sum("RowsProduced")),
+            bytes_sent,
+            local_bytes_sent,
+            uncompressed_bytes,
+            remote_bytes_received: exchange_counters.sum("RemoteBytesReceived"),
+            compression_ratio,
+            local_percentage,
+            rpc_count: sink_counters.sum("RpcCount"),
+            rpc_avg_ns: sink_counters.stat("RpcAvgTime").map(|s| s.avg as u64),
+            rpc_max_ns: sink_counters.max("RpcMaxTime"),
+            rpc_buffer_wait_ns: sink_counters.max("WaitForRpcBufferQueue"),
+            compress_time_ns: sink_counters.max("CompressTime"),
+            decompress_bytes: exchange_counters.sum("DecompressBytes"),
+            decompress_time_ns: exchange_counters.max("DecompressTime"),
+        }
+    }
+    
+    fn fragment_label(fragment_id: &Option<String>) -> String {
+        fragment_id.clone().unwrap_or_else(|| "The sending fragment".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::counters::fixtures::node;
+    
+    fn sink() -> ExecutionTreeNode {
+        node(3, 0, "dest17", "DATA_STREAM_SINK_OPERATOR", NodeType::DataStreamSink)
+    }
+    
+    fn edge() -> ShuffleEdge {
+        ShuffleEdge {
+            exchange_id: 17,
+            sender_fragment_id: Some("Fragment 3".to_string()),
+            receiver_fragment_id: Some("Fragment 1".to_string()),
+            bytes_sent: Some(6 * 1024 * 1024 * 1024),
+            local_bytes_sent: Some(2 * 1024 * 1024 * 1024),
+            local_percentage: Some(25.0),
+            rpc_count: Some(4000),
+            rpc_avg_ns: Some(20_000_000),
+            rpc_max_ns: Some(400_000_000),
+            rpc_buffer_wait_ns: Some(4_500_000_000),
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn test_network_bound_sender() {
+        let hotspot = ShuffleAnalysis::check_rpc_buffer_wait(&sink(), &edge(), Some(10_000_000_000.0)).unwrap();
+        assert_eq!(hotspot.kind, HotspotKind::Shuffle);
+        assert_eq!(hotspot.severity, HotspotSeverity::High);
+        assert_eq!(
+            hotspot.description,
+            "Fragment 3 is network-bound: its sender to exchange 17 waited 4.50s (45.0% of the query) for the RPC buffer queue; \
+             6.00 GB sent over RPC, 25.0% of the data stayed local"
+        );
+        assert!(hotspot.suggestion.as_ref().unwrap().contains("fragment_transmission_compression_codec"));
+        
+        let compressed = ShuffleEdge { compression_ratio: Some(3.0), ..edge() };
+        let hotspot = ShuffleAnalysis::check_rpc_buffer_wait(&sink(), &compressed, Some(20_000_000_000.0)).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::Medium);
+        assert!(!hotspot.suggestion.unwrap().contains("fragment_transmission_compression_codec"));
+        
+        assert!(ShuffleAnalysis::check_rpc_buffer_wait(&sink(), &edge(), Some(60_000_000_000.0)).is_none());
+    }
+    
+    #[test]
+    fn test_rpc_tail() {
+        let hotspot = ShuffleAnalysis::check_rpc_tail(&sink(), &edge(), Some(10_000_000_000.0)).unwrap();
+        assert_eq!(hotspot.severity, HotspotSeverity::Medium);
+        assert_eq!(
+            hotspot.description,
+            "The slowest RPC from Fragment 3 to exchange 17 took 400ms, 20x the average of 20ms over 4000 RPCs"
+        );
+        
+        let even = ShuffleEdge { rpc_avg_ns: Some(100_000_000), ..edge() };
+        assert!(ShuffleAnalysis::check_rpc_tail(&sink(), &even, Some(10_000_000_000.0)).is_none());
+    }
+}
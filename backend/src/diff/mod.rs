@@ -0,0 +1,3 @@
+pub mod profile_diff;
+
+pub use profile_diff::*;
@@ -0,0 +1,270 @@
+use crate::models::*;
+use std::collections::{HashMap, HashSet};
+
+/// ProfileDiff compares two parsed profiles of the same query
+pub struct ProfileDiff;
+
+/// Key used to match execution nodes across profiles
+type NodeKey = (Option<i32>, String);
+
+impl ProfileDiff {
+    /// Compare `before` and `after` profiles
+    pub fn compare(before: &Profile, after: &Profile) -> ProfileComparison {
+        let before_nodes = Self::tree_nodes(before);
+        let after_nodes = Self::tree_nodes(after);
+        
+        let (mut node_diffs, added_operators, removed_operators) =
+            Self::match_nodes(&before_nodes, &after_nodes);
+        
+        // Largest time changes first
+        node_diffs.sort_by(|a, b| {
+            let da = a.time.delta.map(f64::abs).unwrap_or(0.0);
+            let db = b.time.delta.map(f64::abs).unwrap_or(0.0);
+            db.partial_cmp(&da).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        
+        ProfileComparison {
+            before_query_id: before.summary.query_id.clone(),
+            after_query_id: after.summary.query_id.clone(),
+            total_time: Self::metric_delta(before.summary.total_time_ms, after.summary.total_time_ms),
+            node_diffs,
+            added_operators,
+            removed_operators,
+            session_variable_changes: Self::compare_session_variables(
+                &before.summary.session_variables,
+                &after.summary.session_variables,
+            ),
+            before_parse_warnings: before.parse_warnings.clone(),
+            after_parse_warnings: after.parse_warnings.clone(),
+        }
+    }
+    
+    /// Get execution tree nodes of a profile (empty if there is no tree)
+    fn tree_nodes(profile: &Profile) -> Vec<&ExecutionTreeNode> {
+        profile.execution_tree.as_ref()
+            .map(|tree| tree.nodes.iter().collect())
+            .unwrap_or_default()
+    }
+    
+    /// Match nodes by plan_node_id and operator name.
+    /// Repeated keys (e.g. local exchanges with the same id) are paired in tree order.
+    fn match_nodes(
+        before: &[&ExecutionTreeNode],
+        after: &[&ExecutionTreeNode],
+    ) -> (Vec<NodeDiff>, Vec<OperatorRef>, Vec<OperatorRef>) {
+        let mut after_by_key: HashMap<NodeKey, Vec<&ExecutionTreeNode>> = HashMap::new();
+        for node in after {
+            after_by_key.entry(Self::node_key(node)).or_default().push(node);
+        }
+        // Pop from the front to keep tree order
+        for nodes in after_by_key.values_mut() {
+            nodes.reverse();
+        }
+        
+        let mut node_diffs = Vec::new();
+        let mut removed = Vec::new();
+        
+        for node in before {
+            match after_by_key.get_mut(&Self::node_key(node)).and_then(|nodes| nodes.pop()) {
+                Some(after_node) => node_diffs.push(Self::diff_node(node, after_node)),
+                None => removed.push(Self::operator_ref(node)),
+            }
+        }
+        
+        // Whatever is left only exists in the after profile, kept in tree order
+        let unmatched: HashSet<&str> = after_by_key.values()
+            .flatten()
+            .map(|n| n.id.as_str())
+            .collect();
+        let added: Vec<OperatorRef> = after.iter()
+            .filter(|node| unmatched.contains(node.id.as_str()))
+            .map(|node| Self::operator_ref(node))
+            .collect();
+        
+        (node_diffs, added, removed)
+    }
+    
+    /// Build the per-node deltas for a matched pair
+    fn diff_node(before: &ExecutionTreeNode, after: &ExecutionTreeNode) -> NodeDiff {
+        let to_f64 = |v: Option<u64>| v.map(|v| v as f64);
+        
+        NodeDiff {
+            operator_name: before.operator_name.clone(),
+            plan_node_id: before.plan_node_id,
+            before_node_id: before.id.clone(),
+            after_node_id: after.id.clone(),
+            time: Self::metric_delta(
+                to_f64(before.metrics.operator_total_time),
+                to_f64(after.metrics.operator_total_time),
+            ),
+            rows: Self::metric_delta(
+                to_f64(before.metrics.rows_returned),
+                to_f64(after.metrics.rows_returned),
+            ),
+            memory: Self::metric_delta(
+                to_f64(before.metrics.memory_used),
+                to_f64(after.metrics.memory_used),
+            ),
+        }
+    }
+    
+    /// Compute delta and relative change of a metric
+    pub fn metric_delta(before: Option<f64>, after: Option<f64>) -> MetricDelta {
+        let delta = match (before, after) {
+            (Some(b), Some(a)) => Some(a - b),
+            _ => None,
+        };
+        let delta_percentage = match (before, delta) {
+            (Some(b), Some(d)) if b != 0.0 => Some(d / b * 100.0),
+            _ => None,
+        };
+        
+        MetricDelta { before, after, delta, delta_percentage }
+    }
+    
+    /// Compare changed session variables; a missing side falls back to the default value
+    fn compare_session_variables(
+        before: &[SessionVariable],
+        after: &[SessionVariable],
+    ) -> Vec<SessionVariableChange> {
+        let mut changes = Vec::new();
+        
+        for var in before {
+            let after_value = after.iter()
+                .find(|v| v.var_name == var.var_name)
+                .map(|v| v.current_value.clone())
+                .unwrap_or_else(|| var.default_value.clone());
+            
+            if after_value != var.current_value {
+                changes.push(SessionVariableChange {
+                    name: var.var_name.clone(),
+                    before: Some(var.current_value.clone()),
+                    after: Some(after_value),
+                });
+            }
+        }
+        
+        for var in after {
+            if before.iter().any(|v| v.var_name == var.var_name) {
+                continue;
+            }
+            if var.current_value != var.default_value {
+                changes.push(SessionVariableChange {
+                    name: var.var_name.clone(),
+                    before: Some(var.default_value.clone()),
+                    after: Some(var.current_value.clone()),
+                });
+            }
+        }
+        
+        changes
+    }
+    
+    fn node_key(node: &ExecutionTreeNode) -> NodeKey {
+        (node.plan_node_id, node.operator_name.clone())
+    }
+    
+    fn operator_ref(node: &ExecutionTreeNode) -> OperatorRef {
+        OperatorRef {
+            node_id: node.id.clone(),
+            operator_name: node.operator_name.clone(),
+            plan_node_id: node.plan_node_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn create_node(id: &str, operator_name: &str, plan_node_id: i32, time_ns: u64, rows: u64) -> ExecutionTreeNode {
+        ExecutionTreeNode {
+            id: id.to_string(),
+            operator_name: operator_name.to_string(),
+            plan_node_id: Some(plan_node_id),
+            metrics: OperatorMetrics {
+                operator_total_time: Some(time_ns),
+                rows_returned: Some(rows),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+    
+    fn create_profile(nodes: Vec<ExecutionTreeNode>, variables: Vec<(&str, &str, &str)>) -> Profile {
+        Profile {
+            summary: ProfileSummary {
+                query_id: "q".to_string(),
+                total_time_ms: Some(1000.0),
+                session_variables: variables.into_iter()
+                    .map(|(name, current, default)| SessionVariable {
+                        var_name: name.to_string(),
+                        current_value: current.to_string(),
+                        default_value: default.to_string(),
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            fragments: vec![],
+            execution_tree: Some(ExecutionTree { root: nodes[0].clone(), nodes }),
+            detail_profile: None,
+            physical_plan: None,
+            runtime_filters: Vec::new(),
+            parse_warnings: Vec::new(),
+        }
+    }
+    
+    #[test]
+    fn test_compare_nodes() {
+        let before = create_profile(vec![
+            create_node("b-scan", "OLAP_SCAN_OPERATOR", 0, 2_000_000, 1000),
+            create_node("b-agg", "AGGREGATION_OPERATOR", 1, 500_000, 10),
+        ], vec![]);
+        let after = create_profile(vec![
+            create_node("a-scan", "OLAP_SCAN_OPERATOR", 0, 1_000_000, 1000),
+            create_node("a-join", "HASH_JOIN_OPERATOR", 2, 300_000, 10),
+        ], vec![]);
+        
+        let diff = ProfileDiff::compare(&before, &after);
+        assert_eq!(diff.node_diffs.len(), 1);
+        
+        let scan = &diff.node_diffs[0];
+        assert_eq!(scan.before_node_id, "b-scan");
+        assert_eq!(scan.after_node_id, "a-scan");
+        assert_eq!(scan.time.delta, Some(-1_000_000.0));
+        assert_eq!(scan.time.delta_percentage, Some(-50.0));
+        assert_eq!(scan.rows.delta, Some(0.0));
+        
+        assert_eq!(diff.removed_operators.len(), 1);
+        assert_eq!(diff.removed_operators[0].operator_name, "AGGREGATION_OPERATOR");
+        assert_eq!(diff.added_operators.len(), 1);
+        assert_eq!(diff.added_operators[0].operator_name, "HASH_JOIN_OPERATOR");
+    }
+    
+    #[test]
+    fn test_compare_session_variables() {
+        let node = || vec![create_node("scan", "OLAP_SCAN_OPERATOR", 0, 1, 1)];
+        let before = create_profile(node(), vec![
+            ("enable_profile", "true", "false"),
+            ("parallel_pipeline_task_num", "8", "0"),
+        ]);
+        let after = create_profile(node(), vec![
+            ("enable_profile", "true", "false"),
+            ("runtime_filter_wait_time_ms", "10000", "1000"),
+        ]);
+        
+        let changes = ProfileDiff::compare(&before, &after).session_variable_changes;
+        assert_eq!(changes, vec![
+            SessionVariableChange {
+                name: "parallel_pipeline_task_num".to_string(),
+                before: Some("8".to_string()),
+                after: Some("0".to_string()),
+            },
+            SessionVariableChange {
+                name: "runtime_filter_wait_time_ms".to_string(),
+                before: Some("1000".to_string()),
+                after: Some("10000".to_string()),
+            },
+        ]);
+    }
+}
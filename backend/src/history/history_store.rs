@@ -0,0 +1,295 @@
+use crate::models::*;
+use crate::constants::history;
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Files kept per query directory
+const RAW_FILE: &str = "profile.txt";
+const PROFILE_FILE: &str = "profile.json";
+const ANALYSIS_FILE: &str = "analysis.json";
+/// Written last, so a directory without it is an incomplete save and is ignored
+const ENTRY_FILE: &str = "entry.json";
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// HistoryStore keeps analyzed profiles on disk, one directory per query_id,
+/// with an in-memory index of the entries for searching
+pub struct HistoryStore {
+    root: PathBuf,
+    index: RwLock<HashMap<String, HistoryEntry>>,
+}
+
+impl HistoryStore {
+    /// Open (or create) a store in `root` and load the index of stored analyses
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self, String> {
+        let root = root.into();
+        fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create history directory {}: {}", root.display(), e))?;
+        
+        let mut index = HashMap::new();
+        let dirs = fs::read_dir(&root)
+            .map_err(|e| format!("Failed to read history directory {}: {}", root.display(), e))?;
+        for dir in dirs.flatten() {
+            let Ok(content) = fs::read_to_string(dir.path().join(ENTRY_FILE)) else {
+                continue;
+            };
+            match serde_json::from_str::<HistoryEntry>(&content) {
+                Ok(entry) => {
+                    index.insert(entry.query_id.clone(), entry);
+                }
+                Err(e) => eprintln!("Skipping corrupt history entry {}: {}", dir.path().display(), e),
+            }
+        }
+        
+        Ok(Self {
+            root,
+            index: RwLock::new(index),
+        })
+    }
+    
+    /// Store an analysis, replacing an earlier one of the same query
+    pub fn save(
+        &self,
+        profile_text: &str,
+        profile: &Profile,
+        analysis: &ProfileAnalysisResponse,
+    ) -> Result<HistoryEntry, String> {
+        let summary = &profile.summary;
+        let dir = self.query_dir(&summary.query_id)?;
+        
+        let entry = HistoryEntry {
+            query_id: summary.query_id.clone(),
+            user: summary.user.clone(),
+            default_db: summary.default_db.clone(),
+            start_time: summary.start_time.clone(),
+            total_time_ms: summary.total_time_ms,
+            query_state: summary.query_state.clone(),
+            performance_score: analysis.performance_score,
+            hotspot_count: analysis.hotspots.len(),
+            analyzed_at: chrono::Utc::now().to_rfc3339(),
+        };
+        
+        // Hold the lock while writing so concurrent saves of one query don't interleave
+        let mut index = self.index.write().map_err(|_| "History index lock poisoned".to_string())?;
+        
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        Self::write_file(&dir.join(RAW_FILE), profile_text.to_string())?;
+        Self::write_file(&dir.join(PROFILE_FILE), Self::to_json(profile)?)?;
+        Self::write_file(&dir.join(ANALYSIS_FILE), Self::to_json(analysis)?)?;
+        Self::write_file(&dir.join(ENTRY_FILE), Self::to_json(&entry)?)?;
+        
+        index.insert(entry.query_id.clone(), entry.clone());
+        Ok(entry)
+    }
+    
+    /// Find stored analyses matching the filter, most recently started first
+    pub fn search(&self, filter: &HistoryFilter) -> Result<Vec<HistoryEntry>, String> {
+        let start_from = filter.start_from.as_deref()
+            .map(|s| Self::parse_time(s, false))
+            .transpose()?;
+        let start_to = filter.start_to.as_deref()
+            .map(|s| Self::parse_time(s, true))
+            .transpose()?;
+        
+        let index = self.index.read().map_err(|_| "History index lock poisoned".to_string())?;
+        let mut entries: Vec<HistoryEntry> = index.values()
+            .filter(|entry| Self::matches(entry, filter, start_from, start_to))
+            .cloned()
+            .collect();
+        
+        entries.sort_by(|a, b| {
+            b.start_time.cmp(&a.start_time).then_with(|| a.query_id.cmp(&b.query_id))
+        });
+        entries.truncate(filter.limit.unwrap_or(history::DEFAULT_SEARCH_LIMIT));
+        Ok(entries)
+    }
+    
+    /// Load a stored analysis with its parsed profile
+    pub fn get(&self, query_id: &str) -> Result<Option<HistoryRecord>, String> {
+        let dir = self.query_dir(query_id)?;
+        let Some(entry) = self.entry(query_id)? else {
+            return Ok(None);
+        };
+        
+        Ok(Some(HistoryRecord {
+            entry,
+            profile: Self::read_json(&dir.join(PROFILE_FILE))?,
+            analysis: Self::read_json(&dir.join(ANALYSIS_FILE))?,
+        }))
+    }
+    
+    /// Load the raw profile text of a stored analysis
+    pub fn raw_profile(&self, query_id: &str) -> Result<Option<String>, String> {
+        let path = self.query_dir(query_id)?.join(RAW_FILE);
+        if self.entry(query_id)?.is_none() {
+            return Ok(None);
+        }
+        
+        fs::read_to_string(&path)
+            .map(Some)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    }
+    
+    fn entry(&self, query_id: &str) -> Result<Option<HistoryEntry>, String> {
+        let index = self.index.read().map_err(|_| "History index lock poisoned".to_string())?;
+        Ok(index.get(query_id).cloned())
+    }
+    
+    /// Directory of a query; ids are used as directory names, so only safe ids are accepted
+    fn query_dir(&self, query_id: &str) -> Result<PathBuf, String> {
+        if query_id.is_empty() || !query_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("Invalid query id: {}", query_id));
+        }
+        Ok(self.root.join(query_id))
+    }
+    
+    fn matches(
+        entry: &HistoryEntry,
+        filter: &HistoryFilter,
+        start_from: Option<NaiveDateTime>,
+        start_to: Option<NaiveDateTime>,
+    ) -> bool {
+        let eq = |value: Option<&str>, wanted: &Option<String>| {
+            wanted.as_deref().map_or(true, |w| value.is_some_and(|v| v.eq_ignore_ascii_case(w)))
+        };
+        if !eq(entry.user.as_deref(), &filter.user)
+            || !eq(entry.default_db.as_deref(), &filter.default_db)
+            || !eq(Some(entry.query_state.as_str()), &filter.query_state)
+        {
+            return false;
+        }
+        
+        if filter.min_score.is_some_and(|min| entry.performance_score < min)
+            || filter.max_score.is_some_and(|max| entry.performance_score > max)
+        {
+            return false;
+        }
+        
+        if start_from.is_some() || start_to.is_some() {
+            let Ok(start) = NaiveDateTime::parse_from_str(&entry.start_time, TIME_FORMAT) else {
+                return false;
+            };
+            if start_from.is_some_and(|from| start < from) || start_to.is_some_and(|to| start > to) {
+                return false;
+            }
+        }
+        
+        true
+    }
+    
+    /// Parse a filter time; a bare date means the start (or end, for upper bounds) of that day
+    fn parse_time(value: &str, end_of_day: bool) -> Result<NaiveDateTime, String> {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, TIME_FORMAT) {
+            return Ok(time);
+        }
+        
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid time '{}', expected YYYY-MM-DD HH:MM:SS or YYYY-MM-DD", value))?;
+        let time = if end_of_day { date.and_hms_opt(23, 59, 59) } else { date.and_hms_opt(0, 0, 0) };
+        time.ok_or_else(|| format!("Invalid time '{}'", value))
+    }
+    
+    fn to_json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+        serde_json::to_string(value).map_err(|e| format!("Failed to serialize: {}", e))
+    }
+    
+    fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+    
+    fn write_file(path: &Path, content: String) -> Result<(), String> {
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProfileComposer;
+    
+    struct TempDir(PathBuf);
+    
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+    
+    fn temp_dir() -> TempDir {
+        TempDir(std::env::temp_dir().join(format!("doris-history-{}", uuid::Uuid::new_v4())))
+    }
+    
+    fn save_test_profile(store: &HistoryStore, file: &str) -> HistoryEntry {
+        let text = fs::read_to_string(format!("../test/{}", file)).expect("Failed to read test profile");
+        let profile = ProfileComposer::new().parse(&text).expect("Failed to parse profile");
+        let analysis = crate::analyze_profile(&text).expect("Failed to analyze profile");
+        store.save(&text, &profile, &analysis).expect("Failed to save")
+    }
+    
+    #[test]
+    fn test_save_and_search() {
+        let dir = temp_dir();
+        let store = HistoryStore::open(&dir.0).unwrap();
+        let internal = save_test_profile(&store, "test-profile-internal-2.txt");
+        let external = save_test_profile(&store, "test-profile-external-1.txt");
+        assert_eq!(internal.default_db.as_deref(), Some("tpcds"));
+        
+        let all = store.search(&HistoryFilter::default()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].query_id, internal.query_id, "latest start time first");
+        
+        let by_db = store.search(&HistoryFilter {
+            default_db: Some("TPCDS1000_PARQUET".to_string()),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(by_db, vec![external.clone()]);
+        
+        let by_time = store.search(&HistoryFilter {
+            start_from: Some("2025-11-27".to_string()),
+            start_to: Some("2025-11-27".to_string()),
+            user: Some("root".to_string()),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(by_time, vec![external.clone()]);
+        
+        let by_score = store.search(&HistoryFilter {
+            min_score: Some(internal.performance_score + 1),
+            ..Default::default()
+        }).unwrap();
+        assert!(by_score.iter().all(|e| e.performance_score > internal.performance_score));
+        
+        let invalid = store.search(&HistoryFilter {
+            start_from: Some("yesterday".to_string()),
+            ..Default::default()
+        });
+        assert!(invalid.is_err());
+    }
+    
+    #[test]
+    fn test_reopen_and_get() {
+        let dir = temp_dir();
+        let entry = {
+            let store = HistoryStore::open(&dir.0).unwrap();
+            save_test_profile(&store, "test-profile-internal-2.txt")
+        };
+        
+        let store = HistoryStore::open(&dir.0).unwrap();
+        let record = store.get(&entry.query_id).unwrap().expect("Stored analysis not found");
+        assert_eq!(record.entry, entry);
+        assert_eq!(record.profile.summary.query_id, entry.query_id);
+        assert_eq!(record.analysis.performance_score, entry.performance_score);
+        
+        let raw = store.raw_profile(&entry.query_id).unwrap().unwrap();
+        assert!(raw.contains(&entry.query_id));
+        
+        assert!(store.get("unknown-query").unwrap().is_none());
+        assert!(store.get("../etc").is_err());
+    }
+}
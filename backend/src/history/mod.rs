@@ -0,0 +1,3 @@
+pub mod history_store;
+
+pub use history_store::*;
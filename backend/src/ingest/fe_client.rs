@@ -0,0 +1,231 @@
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use crate::config::FeConnectionConfig;
+use crate::models::FeQueryInfo;
+
+/// Envelope of FE REST responses: `{"msg": "success", "code": 0, "data": ...}`
+#[derive(Deserialize)]
+struct FeResponse {
+    #[serde(default)]
+    msg: String,
+    code: i32,
+    #[serde(default)]
+    data: Value,
+}
+
+/// Client for the query and profile APIs of a Doris FE
+pub struct FeClient {
+    config: FeConnectionConfig,
+    client: Client,
+}
+
+impl FeClient {
+    pub fn new(config: &FeConnectionConfig) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        
+        Self {
+            config: config.clone(),
+            client,
+        }
+    }
+    
+    /// List recent queries known to the FE (all FE nodes)
+    pub async fn list_queries(&self) -> Result<Vec<FeQueryInfo>, String> {
+        let data = self.get_data("/rest/v2/manager/query/query_info?is_all_node=true").await?;
+        
+        let columns: Vec<String> = data["column_names"].as_array()
+            .map(|names| names.iter().map(|n| n.as_str().unwrap_or_default().to_string()).collect())
+            .unwrap_or_default();
+        let rows = data["rows"].as_array().cloned().unwrap_or_default();
+        
+        Ok(rows.iter()
+            .filter_map(|row| Self::parse_query_row(&columns, row.as_array()?))
+            .collect())
+    }
+    
+    /// Fetch the text profile of a query
+    pub async fn fetch_profile(&self, query_id: &str) -> Result<String, String> {
+        if query_id.is_empty() || !query_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Invalid query id: {}", query_id));
+        }
+        
+        let data = self.get_data(&format!(
+            "/rest/v2/manager/query/profile/text/{}?is_all_node=true",
+            query_id
+        )).await?;
+        
+        data["profile"].as_str()
+            .filter(|profile| !profile.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| format!("FE returned no profile for query {}", query_id))
+    }
+    
+    /// GET an FE REST path and unwrap the `data` field
+    async fn get_data(&self, path: &str) -> Result<Value, String> {
+        let url = format!("{}{}", self.config.endpoint.trim_end_matches('/'), path);
+        
+        let response = self.client
+            .get(&url)
+            .basic_auth(&self.config.user, Some(&self.config.password))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to FE {}: {}", self.config.endpoint, e))?;
+        
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("FE request {} failed with HTTP {}", path, status));
+        }
+        
+        let body: FeResponse = response.json().await
+            .map_err(|e| format!("Invalid FE response for {}: {}", path, e))?;
+        if body.code != 0 {
+            return Err(format!("FE request {} failed: {}", path, body.msg));
+        }
+        
+        Ok(body.data)
+    }
+    
+    /// Map a row of the query info table by column name
+    fn parse_query_row(columns: &[String], row: &[Value]) -> Option<FeQueryInfo> {
+        let get = |name: &str| {
+            columns.iter()
+                .position(|c| c.eq_ignore_ascii_case(name))
+                .and_then(|i| row.get(i))
+                .and_then(|v| match v {
+                    Value::String(s) => Some(s.clone()),
+                    Value::Null => None,
+                    other => Some(other.to_string()),
+                })
+                .filter(|s| !s.is_empty())
+        };
+        
+        Some(FeQueryInfo {
+            query_id: get("Query ID")?,
+            fe_node: get("FE Node"),
+            user: get("Query User"),
+            database: get("Execution Database"),
+            sql: get("Sql"),
+            query_type: get("Query Type"),
+            start_time: get("Start Time"),
+            end_time: get("End Time"),
+            duration: get("Execution Duration"),
+            state: get("Status"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use warp::Filter;
+    
+    /// Basic auth header for root:secret
+    const AUTH: &str = "Basic cm9vdDpzZWNyZXQ=";
+    const QUERY_ID: &str = "af3662951b1149f2-bcd99115180f7329";
+    
+    #[derive(Debug)]
+    struct Unauthorized;
+    impl warp::reject::Reject for Unauthorized {}
+    
+    /// Start a mock FE on an ephemeral port and return its endpoint
+    fn start_mock_fe() -> String {
+        let profile = std::fs::read_to_string("../test/test-profile-internal-2.txt")
+            .expect("Failed to read test profile");
+        
+        let authorized = warp::header::optional::<String>("authorization")
+            .and_then(|auth: Option<String>| async move {
+                if auth.as_deref() == Some(AUTH) {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(Unauthorized))
+                }
+            })
+            .untuple_one();
+        
+        let queries = warp::path!("rest" / "v2" / "manager" / "query" / "query_info")
+            .map(|| warp::reply::json(&json!({
+                "msg": "success",
+                "code": 0,
+                "data": {
+                    "column_names": ["Query ID", "FE Node", "Query User", "Execution Database", "Sql",
+                                     "Query Type", "Start Time", "End Time", "Execution Duration", "Status"],
+                    "rows": [
+                        [QUERY_ID, "127.0.0.1:9030", "root", "tpch", "select 1", "Query",
+                         "2025-12-01 10:00:00", "2025-12-01 10:00:00", "16ms", "FINISHED"],
+                        ["", "127.0.0.1:9030", "root", "", "", "", "", "", "", ""]
+                    ]
+                },
+                "count": 0
+            })));
+        
+        let profile_text = warp::path!("rest" / "v2" / "manager" / "query" / "profile" / "text" / String)
+            .map(move |query_id: String| {
+                let body = if query_id == QUERY_ID {
+                    json!({"msg": "success", "code": 0, "data": {"profile": profile}, "count": 0})
+                } else {
+                    json!({"msg": "Query not found", "code": 404, "data": null, "count": 0})
+                };
+                warp::reply::json(&body)
+            });
+        
+        let routes = warp::get()
+            .and(authorized)
+            .and(queries.or(profile_text))
+            .recover(|_: warp::Rejection| async {
+                Ok::<_, std::convert::Infallible>(warp::http::StatusCode::UNAUTHORIZED)
+            });
+        let (addr, server) = warp::serve(routes).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+    
+    fn create_client(endpoint: String, password: &str) -> FeClient {
+        FeClient::new(&FeConnectionConfig {
+            enabled: true,
+            endpoint,
+            user: "root".to_string(),
+            password: password.to_string(),
+            timeout_seconds: 5,
+        })
+    }
+    
+    #[tokio::test]
+    async fn test_list_queries() {
+        let client = create_client(start_mock_fe(), "secret");
+        
+        let queries = client.list_queries().await.unwrap();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].query_id, QUERY_ID);
+        assert_eq!(queries[0].database.as_deref(), Some("tpch"));
+        assert_eq!(queries[0].duration.as_deref(), Some("16ms"));
+        assert_eq!(queries[0].state.as_deref(), Some("FINISHED"));
+    }
+    
+    #[tokio::test]
+    async fn test_fetch_and_analyze_profile() {
+        let client = create_client(start_mock_fe(), "secret");
+        
+        let profile = client.fetch_profile(QUERY_ID).await.unwrap();
+        let result = crate::analyze_profile(&profile).unwrap();
+        assert_eq!(result.summary.unwrap().query_id, QUERY_ID);
+        
+        let missing = client.fetch_profile("0-0").await.unwrap_err();
+        assert!(missing.contains("Query not found"));
+        
+        assert!(client.fetch_profile("../../api/health").await.unwrap_err().contains("Invalid query id"));
+    }
+    
+    #[tokio::test]
+    async fn test_wrong_credentials() {
+        let client = create_client(start_mock_fe(), "wrong");
+        
+        let err = client.list_queries().await.unwrap_err();
+        assert!(err.contains("HTTP 401"), "{}", err);
+    }
+}
@@ -0,0 +1,5 @@
+mod fe_client;
+mod upload_reader;
+
+pub use fe_client::FeClient;
+pub use upload_reader::{ChunkReader, ProfileUpload, UploadReader};
@@ -0,0 +1,296 @@
+//! Upload reader for /api/analyze-file
+//! Decompresses .gz / .zst / .zip uploads and splits text holding several concatenated
+//! profiles, reading the upload as it streams in
+
+use crate::constants::file_limits;
+use bytes::{Buf, Bytes};
+use flate2::read::MultiGzDecoder;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use tokio::sync::mpsc::Receiver;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// A single profile found in an upload
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileUpload {
+    /// File name, archive entry ("profiles.zip/q1.txt") and position ("#2") when a file holds several profiles
+    pub source: String,
+    pub profile_text: String,
+}
+
+pub struct UploadReader;
+
+impl UploadReader {
+    /// Read every profile of an uploaded file named `name`.
+    /// The format is detected from the content, so the file extension does not matter
+    pub fn read_profiles<R: Read>(name: &str, reader: R) -> Result<Vec<ProfileUpload>, String> {
+        let mut profiles = Vec::new();
+        let mut budget = file_limits::MAX_DECOMPRESSED_SIZE;
+        Self::read_file(name, reader, 0, &mut profiles, &mut budget)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        
+        if profiles.is_empty() {
+            return Err(format!("No profile found in {}", name));
+        }
+        Ok(profiles)
+    }
+    
+    /// Decompress a file by its magic bytes and split it into profiles.
+    /// `depth` is the number of archives the file is nested in
+    fn read_file<R: Read>(
+        source: &str,
+        mut reader: R,
+        depth: usize,
+        profiles: &mut Vec<ProfileUpload>,
+        budget: &mut u64,
+    ) -> io::Result<()> {
+        let mut magic = Vec::with_capacity(ZSTD_MAGIC.len());
+        (&mut reader).take(ZSTD_MAGIC.len() as u64).read_to_end(&mut magic)?;
+        let reader = Read::chain(Cursor::new(magic.clone()), reader);
+        
+        if magic.starts_with(GZIP_MAGIC) {
+            Self::split_profiles(source, MultiGzDecoder::new(reader), profiles, budget)
+        } else if magic.starts_with(ZSTD_MAGIC) {
+            let decoder = ruzstd::decoding::StreamingDecoder::new(reader)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            Self::split_profiles(source, decoder, profiles, budget)
+        } else if magic.starts_with(ZIP_MAGIC) {
+            if depth >= file_limits::MAX_ARCHIVE_DEPTH {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} is nested in more than {} archives", source, file_limits::MAX_ARCHIVE_DEPTH),
+                ));
+            }
+            Self::read_zip(source, reader, depth, profiles, budget)
+        } else {
+            Self::split_profiles(source, reader, profiles, budget)
+        }
+    }
+    
+    /// Read every file entry of a zip archive. The central directory is at the end,
+    /// so the compressed archive is buffered; entries are decompressed one at a time.
+    /// A nested archive was decompressed from its parent, so it is charged to the budget
+    fn read_zip<R: Read>(
+        source: &str,
+        reader: R,
+        depth: usize,
+        profiles: &mut Vec<ProfileUpload>,
+        budget: &mut u64,
+    ) -> io::Result<()> {
+        let mut data = Vec::new();
+        reader.take(file_limits::MAX_UPLOAD_SIZE).read_to_end(&mut data)?;
+        if depth > 0 {
+            Self::charge(budget, data.len())?;
+        }
+        let mut archive = zip::ZipArchive::new(Cursor::new(data))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        
+        for idx in 0..archive.len() {
+            let entry = archive.by_index(idx)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            let name = entry.name().to_string();
+            // Skip directories and macOS metadata ("__MACOSX/", "._file")
+            let file_name = name.rsplit('/').next().unwrap_or_default();
+            if entry.is_dir() || name.starts_with("__MACOSX/") || file_name.starts_with('.') {
+                continue;
+            }
+            Self::read_file(&format!("{}/{}", source, name), entry, depth + 1, profiles, budget)?;
+        }
+        Ok(())
+    }
+    
+    /// Split decompressed text into profiles. A profile starts at an unindented
+    /// "Summary:" line, or "Query:" for non-pipeline profiles
+    fn split_profiles<R: Read>(
+        source: &str,
+        reader: R,
+        profiles: &mut Vec<ProfileUpload>,
+        budget: &mut u64,
+    ) -> io::Result<()> {
+        let mut reader = BufReader::new(reader);
+        let mut texts = Vec::new();
+        let mut current = Vec::new();
+        
+        loop {
+            let line_start = current.len();
+            let read = reader.read_until(b'\n', &mut current)?;
+            if read == 0 {
+                break;
+            }
+            Self::charge(budget, read)?;
+            
+            if Self::is_profile_start(&current[line_start..]) && !Self::is_blank(&current[..line_start]) {
+                let next = current.split_off(line_start);
+                texts.push(std::mem::replace(&mut current, next));
+            }
+        }
+        if !Self::is_blank(&current) {
+            texts.push(current);
+        }
+        
+        let count = texts.len();
+        for (idx, text) in texts.into_iter().enumerate() {
+            let source = if count > 1 {
+                format!("{}#{}", source, idx + 1)
+            } else {
+                source.to_string()
+            };
+            // Invalid UTF-8 (e.g. a truncated multi-byte SQL literal) is replaced rather than rejected
+            let profile_text = String::from_utf8(text)
+                .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned());
+            profiles.push(ProfileUpload { source, profile_text });
+        }
+        Ok(())
+    }
+    
+    /// Subtract decompressed bytes from the budget, failing once it is exhausted
+    fn charge(budget: &mut u64, bytes: usize) -> io::Result<()> {
+        *budget = budget.checked_sub(bytes as u64).ok_or_else(|| io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("decompressed size exceeds {}MB", file_limits::MAX_DECOMPRESSED_SIZE / 1024 / 1024),
+        ))?;
+        Ok(())
+    }
+    
+    fn is_profile_start(line: &[u8]) -> bool {
+        let line = line.trim_ascii_end();
+        line == b"Summary:" || line == b"Query:"
+    }
+    
+    fn is_blank(text: &[u8]) -> bool {
+        text.iter().all(u8::is_ascii_whitespace)
+    }
+}
+
+/// Blocking reader over chunks sent from an async upload stream, so an upload is
+/// decoded by a blocking task while it is still being received
+pub struct ChunkReader {
+    receiver: Receiver<Bytes>,
+    chunk: Bytes,
+}
+
+impl ChunkReader {
+    pub fn new(receiver: Receiver<Bytes>) -> Self {
+        Self {
+            receiver,
+            chunk: Bytes::new(),
+        }
+    }
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while !self.chunk.has_remaining() {
+            match self.receiver.blocking_recv() {
+                Some(chunk) => self.chunk = chunk,
+                None => return Ok(0),
+            }
+        }
+        
+        let len = buf.len().min(self.chunk.len());
+        buf[..len].copy_from_slice(&self.chunk[..len]);
+        self.chunk.advance(len);
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    
+    const FIRST: &str = "Summary:\n   - Profile ID: q1\nMergedProfile:\n     Fragments:\n";
+    const SECOND: &str = "Query:\n  Summary:\n     - Query ID: q2\n";
+    
+    fn concatenated() -> String {
+        format!("{}\n{}", FIRST, SECOND)
+    }
+    
+    #[test]
+    fn test_split_concatenated_profiles() {
+        let profiles = UploadReader::read_profiles("all.txt", concatenated().as_bytes()).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].source, "all.txt#1");
+        assert_eq!(profiles[0].profile_text, format!("{}\n", FIRST));
+        assert_eq!(profiles[1].source, "all.txt#2");
+        assert_eq!(profiles[1].profile_text, SECOND);
+        
+        let single = UploadReader::read_profiles("q1.txt", FIRST.as_bytes()).unwrap();
+        assert_eq!(single, vec![ProfileUpload { source: "q1.txt".to_string(), profile_text: FIRST.to_string() }]);
+        
+        assert!(UploadReader::read_profiles("empty.txt", " \n\n".as_bytes()).is_err());
+    }
+    
+    #[test]
+    fn test_read_compressed() {
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(concatenated().as_bytes()).unwrap();
+        let profiles = UploadReader::read_profiles("all.gz", gz.finish().unwrap().as_slice()).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[1].profile_text, SECOND);
+        
+        let zst = ruzstd::encoding::compress_to_vec(FIRST.as_bytes(), ruzstd::encoding::CompressionLevel::Fastest);
+        let profiles = UploadReader::read_profiles("q1.zst", zst.as_slice()).unwrap();
+        assert_eq!(profiles[0].profile_text, FIRST);
+        
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("q1.txt", options).unwrap();
+        zip.write_all(FIRST.as_bytes()).unwrap();
+        zip.start_file("__MACOSX/._q1.txt", options).unwrap();
+        zip.write_all(b"\0\0").unwrap();
+        zip.start_file("nested/all.txt", options).unwrap();
+        zip.write_all(concatenated().as_bytes()).unwrap();
+        let data = zip.finish().unwrap().into_inner();
+        
+        let sources: Vec<String> = UploadReader::read_profiles("profiles.zip", data.as_slice()).unwrap()
+            .into_iter()
+            .map(|p| p.source)
+            .collect();
+        assert_eq!(sources, vec!["profiles.zip/q1.txt", "profiles.zip/nested/all.txt#1", "profiles.zip/nested/all.txt#2"]);
+    }
+    
+    fn zip_of(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+        zip.write_all(data).unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+    
+    #[test]
+    fn test_nested_archives() {
+        let inner = zip_of("q1.txt", FIRST.as_bytes());
+        let outer = zip_of("inner.zip", &inner);
+        let profiles = UploadReader::read_profiles("outer.zip", outer.as_slice()).unwrap();
+        assert_eq!(profiles[0].source, "outer.zip/inner.zip/q1.txt");
+        
+        // A third level is rejected
+        let error = UploadReader::read_profiles("bomb.zip", zip_of("outer.zip", &outer).as_slice()).unwrap_err();
+        assert!(error.contains("nested in more than 2 archives"), "{}", error);
+        
+        // The nested archive counts against the budget, besides the profile it holds
+        let mut profiles = Vec::new();
+        let mut budget = (inner.len() + FIRST.len()) as u64;
+        UploadReader::read_file("outer.zip", outer.as_slice(), 0, &mut profiles, &mut budget).unwrap();
+        assert_eq!(budget, 0);
+        let mut budget = (inner.len() + FIRST.len() - 1) as u64;
+        assert!(UploadReader::read_file("outer.zip", outer.as_slice(), 0, &mut profiles, &mut budget).is_err());
+    }
+    
+    #[test]
+    fn test_chunk_reader() {
+        let (sender, receiver) = tokio::sync::mpsc::channel(4);
+        let reader = std::thread::spawn(move || UploadReader::read_profiles("q1.txt", ChunkReader::new(receiver)));
+        
+        // Split inside the magic bytes and inside a line
+        for chunk in ["Su", "mmary:\n   - Profile", " ID: q1\n"] {
+            sender.blocking_send(Bytes::from(chunk)).unwrap();
+        }
+        drop(sender);
+        
+        let profiles = reader.join().unwrap().unwrap();
+        assert_eq!(profiles[0].profile_text, "Summary:\n   - Profile ID: q1\n");
+    }
+}
@@ -1,40 +1,132 @@
 pub mod parser;
 pub mod models;
 pub mod diagnostic;
+pub mod diff;
+pub mod batch;
+pub mod history;
+pub mod redact;
 pub mod api;
 pub mod constants;
 pub mod static_files;
 pub mod config;
 pub mod ai;
+pub mod ingest;
+pub mod report;
 
 pub use models::*;
 pub use diagnostic::performance_bottleneck::PerformanceBottleneck;
 pub use diagnostic::optimization_advisor::OptimizationAdvisor;
+pub use diagnostic::critical_path::CriticalPathAnalysis;
+pub use diagnostic::pipeline_time::PipelineTimeAccounting;
+pub use diagnostic::memory::MemoryAnalysis;
+pub use diagnostic::shuffle::ShuffleAnalysis;
+pub use diagnostic::lakehouse_scan::LakehouseScanAnalysis;
+pub use diagnostic::remote_io::RemoteIoAnalysis;
+pub use diff::ProfileDiff;
+pub use batch::BatchAnalyzer;
+pub use history::HistoryStore;
+pub use redact::Redactor;
 pub use parser::ProfileComposer;
 pub use config::ConfigLoader;
 pub use ai::AiDiagnosisService;
+pub use ingest::FeClient;
+pub use report::TerminalReport;
 
-/// Main entry point for analyzing a Doris profile text
+use config::DefaultSuggestionsConfig;
+
+/// Main entry point for analyzing a Doris profile text.
+/// Parses leniently: truncated or partly unknown profiles are analyzed as far as
+/// possible and the skipped parts are listed in `parse_warnings`
 pub fn analyze_profile(profile_text: &str) -> Result<ProfileAnalysisResponse, String> {
-    let mut composer = ProfileComposer::new();
+    let mut composer = ProfileComposer::lenient();
     let profile = composer.parse(profile_text)
         .map_err(|e| format!("Failed to parse profile: {:?}", e))?;
 
-    let hotspots = PerformanceBottleneck::analyze(&profile);
-    let conclusion = OptimizationAdvisor::generate_conclusion(&hotspots, &profile);
+    Ok(analyze_parsed_profile(&profile))
+}
+
+/// Same as `analyze_profile` for an already parsed profile
+pub fn analyze_parsed_profile(profile: &Profile) -> ProfileAnalysisResponse {
+    analysis_response(profile, PerformanceBottleneck::analyze(profile))
+}
+
+/// Analyze a profile and fill hotspot suggestions from the default suggestions config
+pub async fn analyze_profile_with_defaults(
+    profile_text: &str,
+    ai_service: Option<&AiDiagnosisService>,
+    default_config: &DefaultSuggestionsConfig,
+) -> Result<ProfileAnalysisResponse, String> {
+    let mut composer = ProfileComposer::lenient();
+    let profile = composer.parse(profile_text)
+        .map_err(|e| format!("Failed to parse profile: {:?}", e))?;
+
+    Ok(analyze_parsed_profile_with_defaults(&profile, ai_service, default_config).await)
+}
+
+/// Same as `analyze_profile_with_defaults` for an already parsed profile
+pub async fn analyze_parsed_profile_with_defaults(
+    profile: &Profile,
+    ai_service: Option<&AiDiagnosisService>,
+    default_config: &DefaultSuggestionsConfig,
+) -> ProfileAnalysisResponse {
+    let mut hotspots = PerformanceBottleneck::analyze(profile);
+    OptimizationAdvisor::fill_suggestions(
+        &mut hotspots,
+        profile,
+        ai_service,
+        default_config,
+        true,  // skip_ai = true, AI suggestions are requested per node
+    ).await;
+
+    analysis_response(profile, hotspots)
+}
+
+/// Build the analysis response of a profile from its final hotspots
+fn analysis_response(profile: &Profile, hotspots: Vec<HotSpot>) -> ProfileAnalysisResponse {
+    let conclusion = OptimizationAdvisor::generate_conclusion(&hotspots, profile);
     let suggestions = OptimizationAdvisor::generate_suggestions(&hotspots);
-    let performance_score = OptimizationAdvisor::calculate_performance_score(&hotspots, &profile);
-    let execution_tree = profile.execution_tree.clone();
-    let summary = Some(profile.summary.clone());
+    let performance_score = OptimizationAdvisor::calculate_performance_score(&hotspots, profile);
 
-    Ok(ProfileAnalysisResponse {
+    ProfileAnalysisResponse {
         hotspots,
         conclusion,
         suggestions,
         performance_score,
-        execution_tree,
-        summary,
-    })
+        execution_tree: profile.execution_tree.clone(),
+        summary: Some(profile.summary.clone()),
+        physical_plan: profile.physical_plan.clone(),
+        runtime_filters: profile.runtime_filters.clone(),
+        parse_warnings: profile.parse_warnings.clone(),
+        critical_path: CriticalPathAnalysis::compute(profile),
+        time_accounting: PipelineTimeAccounting::compute(profile),
+        memory: MemoryAnalysis::compute(profile),
+        shuffle: ShuffleAnalysis::compute(profile),
+        lakehouse: LakehouseScanAnalysis::compute(profile),
+        remote_io: RemoteIoAnalysis::compute(profile),
+    }
+}
+
+/// Compare two profiles of the same query (e.g. before and after a change).
+/// Parses leniently like `analyze_profile`; the warnings of each side are returned
+pub fn compare_profiles(before_text: &str, after_text: &str) -> Result<ProfileComparison, String> {
+    let before = ProfileComposer::lenient().parse(before_text)
+        .map_err(|e| format!("Failed to parse before profile: {:?}", e))?;
+    let after = ProfileComposer::lenient().parse(after_text)
+        .map_err(|e| format!("Failed to parse after profile: {:?}", e))?;
+
+    Ok(ProfileDiff::compare(&before, &after))
+}
+
+/// Redact user names, SQL literals, BE addresses and catalog/db/table names of a profile.
+/// The same salt gives the same aliases, so redacted profiles stay comparable
+pub fn redact_profile(profile_text: &str, salt: Option<&str>) -> RedactedProfile {
+    let mut redactor = Redactor::with_salt(salt.unwrap_or_default());
+    let profile_text = redactor.redact_text(profile_text);
+
+    RedactedProfile {
+        profile_text,
+        mapping: redactor.mapping(),
+    }
 }
 
 #[cfg(test)]
@@ -44,7 +136,7 @@ mod integration_tests {
 
     #[test]
     fn test_full_analysis_pipeline() {
-        let profile_text = fs::read_to_string("../test/test-profile.txt")
+        let profile_text = fs::read_to_string("../test/test-profile-external-2.txt")
             .expect("Failed to read test profile");
         
         let result = analyze_profile(&profile_text);
@@ -87,5 +179,481 @@ mod integration_tests {
             );
         }
     }
+    
+    #[test]
+    fn test_cardinality_misestimation_detected() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        
+        // web_sales scan: stats=720,000,376 actualRows=183750
+        let misestimates: Vec<_> = response.hotspots.iter()
+            .filter(|h| h.kind == HotspotKind::CardinalityMisestimation)
+            .collect();
+        assert!(misestimates.iter().any(|h| h.description.contains("PhysicalFileScan[web_sales]")));
+        
+        let schema_suggestion = response.suggestions.iter()
+            .find(|s| s.category == SuggestionCategory::Schema && s.description.contains("ANALYZE TABLE"));
+        assert!(schema_suggestion.is_some(), "Should recommend ANALYZE TABLE");
+        
+        for hotspot in &misestimates {
+            println!("  {:?}: {}", hotspot.severity, hotspot.description);
+        }
+    }
+    
+    #[test]
+    fn test_operator_types_and_join_attributes() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-2.txt")
+            .expect("Failed to read test profile");
+        
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        let tree = response.execution_tree.expect("Should build execution tree");
+        
+        assert!(tree.nodes.iter().any(|n| n.node_type == NodeType::FileScan));
+        assert!(!tree.nodes.iter().any(|n| n.node_type == NodeType::OlapScan));
+        
+        // The build sink gets the join type printed only on the probe side
+        for node_type in [NodeType::HashJoin, NodeType::HashJoinBuild] {
+            let join = tree.nodes.iter()
+                .find(|n| n.node_type == node_type)
+                .expect("Should find both halves of a hash join");
+            assert_eq!(join.attributes.join_type, Some(JoinType::Inner));
+            assert_eq!(join.attributes.join_distribution, Some(JoinDistribution::Broadcast));
+        }
+        
+        let sink = tree.nodes.iter()
+            .find(|n| n.node_type == NodeType::DataStreamSink)
+            .expect("Should find a data stream sink");
+        assert!(sink.attributes.dest_id.is_some());
+    }
+    
+    #[test]
+    fn test_critical_path() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-2.txt")
+            .expect("Failed to read test profile");
+        
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        let path = response.critical_path.expect("Should compute a critical path");
+        let tree = response.execution_tree.unwrap();
+        
+        assert_eq!(path.steps[0].node_id, tree.root.id);
+        assert_eq!(path.steps.iter().map(|s| s.contribution_ns).sum::<u64>(), path.latency_ns);
+        assert!(path.steps.iter().all(|s| s.percentage <= 100.0));
+        
+        for step in &path.steps {
+            println!(
+                "  {} {:.1}% (exec {}ns, wait {}ns)",
+                step.operator_name, step.percentage, step.exec_time_ns, step.wait_time_ns
+            );
+        }
+    }
+    
+    #[test]
+    fn test_pipeline_time_accounting() {
+        for (file, from_detail) in [
+            ("../test/test-profile-external-full.txt", true),
+            ("../test/test-profile-external-2.txt", false),
+        ] {
+            let profile_text = fs::read_to_string(file).expect("Failed to read test profile");
+            let response = analyze_profile(&profile_text).expect("Analysis failed");
+            let accounting = response.time_accounting.expect("Should account pipeline time");
+            assert_eq!(accounting.from_detail_profile, from_detail);
+            assert_eq!(accounting.pipelines.len(), 34);
+            
+            // Scheduling counters only exist per PipelineTask
+            let scan = accounting.pipelines.iter()
+                .find(|p| p.fragment_id == "Fragment 1" && p.pipeline_id == "Pipeline 4")
+                .expect("Should account the scan pipeline");
+            assert_eq!(scan.source_operator.as_deref(), Some("FILE_SCAN_OPERATOR"));
+            assert_eq!(scan.time.task_count, 48);
+            assert_eq!(scan.time.schedule_times.is_some(), from_detail);
+            assert_eq!(scan.time.wait_worker_ns > 0, from_detail);
+            
+            for fragment in &accounting.fragments {
+                let pipelines: Vec<_> = accounting.pipelines.iter()
+                    .filter(|p| p.fragment_id == fragment.fragment_id)
+                    .collect();
+                assert!(!pipelines.is_empty());
+                let tasks: u64 = pipelines.iter().map(|p| p.time.task_count).sum();
+                let total: u64 = pipelines.iter().map(|p| p.time.total_ns()).sum();
+                assert_eq!(fragment.time.task_count, tasks);
+                assert_eq!(fragment.time.total_ns(), total);
+            }
+            
+            // The coordinator fragment sits idle until the scans upstream finish
+            let blocked: Vec<_> = response.hotspots.iter()
+                .filter(|h| h.kind == HotspotKind::PipelineTime)
+                .collect();
+            assert_eq!(blocked.len(), 3);
+            assert!(blocked.iter().all(|h| h.node_path.starts_with("Fragment 0")));
+            assert!(blocked[0].description.contains("blocked on data from an exchange"));
+        }
+    }
+    
+    #[test]
+    fn test_memory_breakdown() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        let memory = response.memory.expect("Should break down memory");
+        
+        // Reservations come from the PipelineTasks of the DetailProfile
+        assert_eq!(memory.reserve_times, Some(37411));
+        assert_eq!(memory.reserve_failed_times, Some(0));
+        assert_eq!(memory.operators.len(), 10);
+        assert!(memory.operators.windows(2).all(|w| w[0].peak_bytes >= w[1].peak_bytes));
+        assert_eq!(memory.operators[0].operator_name, "FILE_SCAN_OPERATOR");
+        assert!(memory.operator_peak_bytes >= memory.operators.iter().map(|op| op.peak_bytes).sum());
+        
+        // A few hundred MB without spilling is not worth a hotspot
+        assert!(!response.hotspots.iter().any(|h| h.kind == HotspotKind::Memory));
+    }
+    
+    #[test]
+    fn test_shuffle_edges() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        let shuffle = response.shuffle.expect("Should model the shuffle");
+        assert_eq!(shuffle.edges.len(), 9);
+        
+        // Exchange counters are only in the DetailProfile of this profile
+        let edge = shuffle.edges.iter()
+            .find(|e| e.exchange_id == 17)
+            .expect("Should pair the sink with dest_id=17");
+        assert_eq!(edge.sender_fragment_id.as_deref(), Some("Fragment 3"));
+        assert_eq!(edge.receiver_fragment_id.as_deref(), Some("Fragment 1"));
+        assert_eq!(edge.rows, Some(7200));
+        assert_eq!(edge.bytes_sent, Some(55294));
+        assert_eq!(edge.local_bytes_sent, Some(64798));
+        assert_eq!(edge.remote_bytes_received, Some(55458));
+        assert!((edge.compression_ratio.unwrap() - 2.5).abs() < 0.01);
+        assert!((edge.local_percentage.unwrap() - 54.0).abs() < 0.1);
+        assert_eq!(edge.rpc_count, Some(544));
+        assert_eq!(edge.rpc_max_ns, Some(5_350_000));
+        
+        // A few KB over fast RPCs is not network-bound
+        assert!(!response.hotspots.iter().any(|h| h.kind == HotspotKind::Shuffle));
+        
+        // Without a DetailProfile only the merged counters remain
+        let profile_text = fs::read_to_string("../test/test-profile-external-2.txt")
+            .expect("Failed to read test profile");
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        let shuffle = response.shuffle.expect("Should model the shuffle");
+        let edge = shuffle.edges.iter().find(|e| e.exchange_id == 17).unwrap();
+        assert_eq!(edge.rows, Some(7200));
+        assert_eq!(edge.bytes_sent, None);
+        assert_eq!(edge.rpc_max_ns, Some(5_350_000));
+    }
+    
+    #[test]
+    fn test_lakehouse_scans() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        let lakehouse = response.lakehouse.expect("Should collect the reader counters");
+        assert_eq!(lakehouse.scans.len(), 8);
+        
+        // Reader counters are only in the DetailProfile of this profile
+        let web_sales = lakehouse.scans.iter()
+            .find(|s| s.node_id == "Fragment 1-Pipeline 4-id20")
+            .expect("Should collect the web_sales scan");
+        assert_eq!(web_sales.table_name.as_deref(), Some("web_sales"));
+        assert_eq!(web_sales.format.as_deref(), Some("parquet"));
+        assert_eq!(web_sales.files, Some(648));
+        assert_eq!(web_sales.empty_files, Some(0));
+        assert_eq!(web_sales.raw_rows_read, Some(720_000_376));
+        assert_eq!(web_sales.rows_produced, Some(183_750));
+        assert_eq!(web_sales.pruned_percentage, Some(0.0));
+        assert_eq!(web_sales.footer_hit_percentage, Some(100.0));
+        assert_eq!(web_sales.delete_files, Some(0));
+        
+        // Both web_sales scans decode 720M rows to keep ~0.03%, without any pruning
+        let pruning: Vec<_> = response.hotspots.iter()
+            .filter(|h| h.kind == HotspotKind::LakehouseScan)
+            .collect();
+        assert_eq!(pruning.len(), 2);
+        assert!(pruning.iter().all(|h| h.severity == HotspotSeverity::High));
+        assert!(pruning[0].description.starts_with("The scan of web_sales read 720000376 rows but kept"));
+        
+        // Merged-only profiles carry no reader counters
+        let profile_text = fs::read_to_string("../test/test-profile-external-2.txt")
+            .expect("Failed to read test profile");
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        assert!(response.lakehouse.is_none());
+    }
+    
+    #[test]
+    fn test_remote_io() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        let remote_io = response.remote_io.expect("Should collect the S3 requests");
+        assert_eq!(remote_io.scans.len(), 8);
+        
+        let web_sales = remote_io.scans.iter()
+            .find(|s| s.node_id == "Fragment 1-Pipeline 4-id20")
+            .expect("Should collect the web_sales scan");
+        assert_eq!(web_sales.get_requests, 1248);
+        assert_eq!(web_sales.bytes_read, Some(1_816_165_068));
+        assert_eq!(web_sales.avg_request_bytes, Some(1_455_260));
+        assert!((web_sales.requests_per_second.unwrap() - 1006.45).abs() < 0.01);
+        assert_eq!(web_sales.throttled_requests, Some(0));
+        assert_eq!(web_sales.request_io, Some(1968));
+        assert_eq!(web_sales.merged_io, Some(1304));
+        
+        // MB-sized requests without 429s: the scan is not held back by object storage
+        assert!(!response.hotspots.iter().any(|h| h.kind == HotspotKind::RemoteIo));
+        
+        let profile_text = fs::read_to_string("../test/test-profile-external-2.txt")
+            .expect("Failed to read test profile");
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        assert!(response.remote_io.is_none());
+    }
+    
+    #[test]
+    fn test_data_skew_detected() {
+        let profile_text = fs::read_to_string("../test/multi-cast-simple.txt")
+            .expect("Failed to read test profile");
+        
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        
+        // All 128397 rows of the join land on one of 144 instances
+        let skewed: Vec<_> = response.hotspots.iter()
+            .filter(|h| h.kind == HotspotKind::DataSkew)
+            .collect();
+        let join = skewed.iter()
+            .find(|h| h.operator_name == "HASH_JOIN_OPERATOR")
+            .expect("Should detect skewed hash join");
+        assert_eq!(join.severity, HotspotSeverity::Critical);
+        assert!(join.description.contains("RowsProduced"));
+        
+        for hotspot in &skewed {
+            println!("  {:?} {}: {}", hotspot.severity, hotspot.node_path, hotspot.description);
+        }
+    }
+    
+    #[test]
+    fn test_runtime_filter_issues_detected() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        
+        let rf_hotspots: Vec<_> = response.hotspots.iter()
+            .filter(|h| h.kind == HotspotKind::RuntimeFilter)
+            .collect();
+        assert!(rf_hotspots.iter().any(|h| h.description.contains("RF9 (in_or_bloom, from join 1833) filtered 0 of 2221724 rows")));
+        assert!(!response.runtime_filters.is_empty());
+        
+        for hotspot in &rf_hotspots {
+            println!("  {:?} {}: {}", hotspot.severity, hotspot.node_path, hotspot.description);
+        }
+    }
+    
+    #[test]
+    fn test_planning_overhead_detected() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        
+        let summary = response.summary.as_ref().unwrap();
+        let plan = FePhase::find(&summary.fe_phases, "Plan Time").expect("Should parse FE phases");
+        assert_eq!(plan.duration_ms, Some(368.0));
+        
+        // Plan Time 368ms of 1sec240ms total
+        let planning: Vec<_> = response.hotspots.iter()
+            .filter(|h| h.kind == HotspotKind::PlanningOverhead)
+            .collect();
+        assert_eq!(planning.len(), 1);
+        assert_eq!(planning[0].node_path, "FE > Plan Time");
+        assert!(planning[0].description.contains("Nereids Translate Time 337ms"));
+        assert!(response.suggestions.iter().any(|s| s.title == "Reduce FE planning overhead"));
+    }
+    
+    #[test]
+    fn test_backend_imbalance_detected() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        
+        let backends = &response.summary.as_ref().unwrap().backend_schedules;
+        assert_eq!(backends.len(), 3);
+        assert!(backends.iter().all(|b| b.instances_num.is_some() && b.split_weight.is_some() && b.rpc_phase2.is_some()));
+        
+        // 172.20.56.83 gets 129 instances, the other BEs 32 and 33
+        let imbalanced: Vec<_> = response.hotspots.iter()
+            .filter(|h| h.kind == HotspotKind::BackendImbalance)
+            .collect();
+        assert_eq!(imbalanced.len(), 1);
+        assert_eq!(imbalanced[0].operator_name, "instance assignment");
+        assert_eq!(imbalanced[0].node_path, "BE 172.20.56.83");
+        assert_eq!(imbalanced[0].severity, HotspotSeverity::High);
+    }
+    
+    #[test]
+    fn test_legacy_non_pipeline_profile() {
+        let profile_text = fs::read_to_string("../test/test-profile-legacy-2.0.txt")
+            .expect("Failed to read test profile");
+        
+        let response = analyze_profile(&profile_text).expect("Analysis failed");
+        
+        let summary = response.summary.unwrap();
+        assert_eq!(summary.format, ProfileFormat::NonPipeline);
+        assert_eq!(summary.query_id, "9f3c2a1b7d4e4f10-8a6b5c4d3e2f1a09");
+        
+        // Fragments are linked through exchanges into a single tree rooted at the result sink
+        let tree = response.execution_tree.expect("Should build execution tree");
+        assert_eq!(tree.root.operator_name, "RESULT_SINK_OPERATOR");
+        assert_eq!(tree.nodes.len(), 11);
+        let join = tree.nodes.iter().find(|n| n.operator_name == "HASH_JOIN_OPERATOR").unwrap();
+        assert_eq!(join.children.len(), 2);
+        let scan = tree.nodes.iter().find(|n| n.table_name.as_deref() == Some("lineitem")).unwrap();
+        assert_eq!(scan.operator_name, "OLAP_SCAN_OPERATOR");
+        assert_eq!(scan.metrics.rows_returned, Some(4500605));
+        
+        // Diagnostics run on the merged counters like on pipeline profiles
+        assert!(!response.hotspots.is_empty(), "Should detect hotspots");
+        assert!(response.hotspots.iter().any(|h| h.operator_name == "HASH_JOIN_OPERATOR"));
+        
+        for hotspot in &response.hotspots {
+            println!("  {:?} {}: {}", hotspot.severity, hotspot.node_path, hotspot.description);
+        }
+    }
+    
+    #[test]
+    fn test_profile_truncated_after_pipeline_header() {
+        for file in ["../test/test-profile-external-1.txt", "../test/test-profile-internal-1.txt"] {
+            let profile_text = fs::read_to_string(file).expect("Failed to read test profile");
+            
+            // Cut the profile off right after the first Pipeline header of the MergedProfile
+            let merged = profile_text.find("MergedProfile").expect("No MergedProfile");
+            let pipeline = merged + profile_text[merged..].find("Pipeline 0(").expect("No Pipeline header");
+            let end = pipeline + profile_text[pipeline..].find('\n').unwrap() + 1;
+            
+            let response = analyze_profile(&profile_text[..end]).expect("Analysis failed");
+            assert!(response.execution_tree.is_none(), "{}", file);
+            assert!(response.summary.is_some());
+            let warning = response.parse_warnings.iter()
+                .find(|w| w.message.contains("No operators found"))
+                .expect("Should warn that no execution tree was built");
+            assert_eq!(warning.section, "MergedProfile");
+            assert!(profile_text.lines().nth(warning.line - 1).unwrap().trim().starts_with("Fragment "));
+        }
+    }
+    
+    #[test]
+    fn test_compare_profiles() {
+        let merged_only = fs::read_to_string("../test/test-profile-external-2.txt")
+            .expect("Failed to read test profile");
+        let full = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        
+        // Same query: every operator matches and nothing changed
+        let same = compare_profiles(&merged_only, &full).expect("Compare failed");
+        assert_eq!(same.before_query_id, same.after_query_id);
+        assert!(same.added_operators.is_empty());
+        assert!(same.removed_operators.is_empty());
+        assert!(same.session_variable_changes.is_empty());
+        assert!(same.node_diffs.iter().all(|d| d.rows.delta == Some(0.0) || d.rows.delta.is_none()));
+        
+        // A truncated profile is compared as far as it was parsed, with its warnings
+        let merged = merged_only.find("MergedProfile").unwrap();
+        let cut = merged + merged_only[merged..].find("HASH_JOIN_OPERATOR").unwrap();
+        let cut = cut + merged_only[cut..].find('\n').unwrap() + 1;
+        let truncated = compare_profiles(&merged_only, &merged_only[..cut]).expect("Compare failed");
+        assert!(truncated.before_parse_warnings.is_empty());
+        assert!(truncated.after_parse_warnings.iter().any(|w| w.message.contains("truncated")));
+        assert!(!truncated.removed_operators.is_empty());
+        
+        // Different queries: operators are added and removed
+        let other = fs::read_to_string("../test/test-profile-external-1.txt")
+            .expect("Failed to read test profile");
+        let diff = compare_profiles(&merged_only, &other).expect("Compare failed");
+        assert!(!diff.added_operators.is_empty() || !diff.removed_operators.is_empty());
+        println!("Matched {} nodes, {} added, {} removed, total time {:?}",
+            diff.node_diffs.len(), diff.added_operators.len(), diff.removed_operators.len(), diff.total_time.delta);
+    }
+    
+    #[test]
+    fn test_redacted_profile_hides_literals() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-1.txt")
+            .expect("Failed to read test profile");
+        
+        // The statement spans many lines and its constants reappear in PlanInfo
+        let redacted = redact_profile(&profile_text, None).profile_text;
+        for original in ["'1999-06-22'", "'1999-05-23'", "0.99 ", "1.49\n", "= 1.49)", "from inventory", ",warehouse\n", ",date_dim\n", ",item\n"] {
+            assert!(!redacted.contains(original), "{} is not redacted", original);
+        }
+        assert!(redacted.contains("   where i_current_price between ? and ?\n"));
+        assert!(redacted.contains("- predicates: (i_current_price >= ?), (i_current_price <= ?)"));
+        assert!(redacted.contains("\n   - Distributed Plan: N/A\n"));
+        assert_eq!(redacted.lines().count(), profile_text.lines().count());
+    }
+    
+    #[test]
+    fn test_redacted_profile_analyzes_the_same() {
+        let profile_text = fs::read_to_string("../test/test-profile-internal-2.txt")
+            .expect("Failed to read test profile");
+        
+        let redacted = redact_profile(&profile_text, Some("salt"));
+        for original in ["User: root", "Default Db: tpcds", "store_sales", "172.20.32."] {
+            assert!(!redacted.profile_text.contains(original), "{} is not redacted", original);
+        }
+        assert!(redacted.mapping.iter().any(|e| e.kind == RedactionKind::Table && e.original == "store_sales"));
+        let sql = redacted.profile_text.lines().find(|l| l.contains("Sql Statement:")).unwrap();
+        assert!(sql.ends_with(" limit ?"));
+        
+        let original = analyze_profile(&profile_text).expect("Analysis failed");
+        let response = analyze_profile(&redacted.profile_text).expect("Analysis of redacted profile failed");
+        assert_eq!(response.performance_score, original.performance_score);
+        assert_eq!(response.hotspots.len(), original.hotspots.len());
+        assert_eq!(response.execution_tree.unwrap().nodes.len(), original.execution_tree.unwrap().nodes.len());
+        assert_eq!(response.summary.unwrap().backend_schedules.len(), 1);
+        
+        // Misestimated scans are reported under the alias of their table
+        let store_sales = &redacted.mapping.iter().find(|e| e.original == "store_sales").unwrap().redacted;
+        assert!(response.hotspots.iter().any(|h| h.description.contains(&format!("PhysicalOlapScan[{}]", store_sales))));
+        
+        // The parsed profile gets the same aliases as the text
+        let mut profile = ProfileComposer::new().parse(&profile_text).expect("Parse failed");
+        let mut redactor = Redactor::with_salt("salt");
+        redactor.redact_profile(&mut profile);
+        let redacted_profile = ProfileComposer::new().parse(&redacted.profile_text).expect("Parse failed");
+        assert_eq!(profile.summary.sql_statement, redacted_profile.summary.sql_statement);
+        assert_eq!(profile.summary.user, redacted_profile.summary.user);
+        let backends = |p: &Profile| {
+            let mut backends = p.summary.backend_schedules.clone();
+            backends.sort_by(|a, b| a.host.cmp(&b.host));
+            backends
+        };
+        assert_eq!(backends(&profile), backends(&redacted_profile));
+        let tables = |p: &Profile| p.execution_tree.as_ref().unwrap().nodes.iter().filter_map(|n| n.table_name.clone()).collect::<Vec<_>>();
+        assert_eq!(tables(&profile), tables(&redacted_profile));
+    }
+    
+    #[test]
+    fn test_batch_analyze_test_profiles() {
+        let report = BatchAnalyzer::analyze_dir(std::path::Path::new("../test"), 4)
+            .expect("Batch analysis failed");
+        
+        assert_eq!(report.total_files, 8);
+        assert_eq!(report.analyzed_count + report.failures.len(), report.total_files);
+        assert!(!report.slowest_queries.is_empty());
+        assert!(!report.hotspot_operators.is_empty());
+        assert_eq!(
+            report.score_distribution.iter().map(|b| b.count).sum::<usize>(),
+            report.analyzed_count
+        );
+        
+        // Entries keep file order regardless of which worker analyzed them
+        let files: Vec<&str> = report.entries.iter().map(|e| e.file.as_str()).collect();
+        let mut sorted = files.clone();
+        sorted.sort();
+        assert_eq!(files, sorted);
+        println!("Analyzed {}/{} profiles, failures: {:?}", report.analyzed_count, report.total_files, report.failures);
+    }
 }
-
@@ -1,5 +1,6 @@
-use clap::Parser;
-use doris_profile_analyzer::{ConfigLoader, AiDiagnosisService};
+use clap::{Args, Parser, Subcommand};
+use doris_profile_analyzer::{ConfigLoader, AiDiagnosisService, BatchAnalyzer, FeClient, HistoryStore, TerminalReport};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 #[derive(Parser, Debug)]
@@ -7,24 +8,119 @@ use std::sync::Arc;
 #[command(author = "Doris Community")]
 #[command(version = "0.1.0")]
 #[command(about = "Doris Profile Analyzer - Analyze query profiles with embedded web UI", long_about = None)]
-struct Args {
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    
+    /// Server options used when no subcommand is given
+    #[command(flatten)]
+    serve: ServeArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Start the web server (default)
+    Serve(ServeArgs),
+    
+    /// Analyze a profile file and print the report
+    Analyze {
+        /// Profile text file
+        file: PathBuf,
+        
+        /// Print the analysis result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Compare two profiles of the same query
+    Compare {
+        /// Profile before the change
+        before: PathBuf,
+        
+        /// Profile after the change
+        after: PathBuf,
+        
+        /// Print the comparison as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Analyze every profile in a directory and print an aggregate report
+    Batch {
+        /// Directory with profile files (searched recursively)
+        dir: PathBuf,
+        
+        /// Number of files analyzed in parallel (defaults to the number of CPUs)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        
+        /// Print the report as JSON
+        #[arg(long, conflicts_with = "csv")]
+        json: bool,
+        
+        /// Print one CSV row per file
+        #[arg(long)]
+        csv: bool,
+    },
+    
+    /// Redact user names, SQL literals, addresses and table names before sharing a profile
+    ///
+    /// Column names and plan expressions are kept. In the SQL statement, the names after
+    /// FROM, JOIN, INTO, UPDATE and TABLE are aliased as tables, including views and CTEs
+    Redact {
+        /// Profile text file
+        file: PathBuf,
+        
+        /// Write the redacted profile to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Salt for the aliases; use the same salt to keep profiles comparable
+        #[arg(long)]
+        salt: Option<String>,
+        
+        /// Write the original -> alias mapping as JSON to this file (keep it private)
+        #[arg(long)]
+        mapping: Option<PathBuf>,
+    },
+}
+
+#[derive(Args, Debug)]
+struct ServeArgs {
     /// Server port
     #[arg(short, long, default_value = "3030")]
     port: u16,
-
+    
     /// Server host
     #[arg(long, default_value = "0.0.0.0")]
     host: String,
+    
+    /// Directory where analyzed profiles, including their SQL, are stored for
+    /// /api/history; nothing is stored unless it is given
+    #[arg(long)]
+    history_dir: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+    let cli = Cli::parse();
+    
+    match cli.command {
+        None => serve(cli.serve).await,
+        Some(Command::Serve(args)) => serve(args).await,
+        Some(Command::Analyze { file, json }) => analyze(&file, json).await,
+        Some(Command::Compare { before, after, json }) => compare(&before, &after, json),
+        Some(Command::Batch { dir, jobs, json, csv }) => batch(&dir, jobs, json, csv),
+        Some(Command::Redact { file, output, salt, mapping }) => redact(&file, output.as_deref(), salt.as_deref(), mapping.as_deref()),
+    }
+}
 
+async fn serve(args: ServeArgs) -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
         .init();
-
+    
     println!("Doris Profile Analyzer v0.1.0");
     
     // Load configurations
@@ -49,17 +145,117 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
     
+    // Create FE client for pulling profiles directly from Doris
+    let fe_client = match ConfigLoader::load_fe_config() {
+        Ok(config) if config.fe_connection.enabled => {
+            println!("FE Connection: {}", config.fe_connection.endpoint);
+            Some(Arc::new(FeClient::new(&config.fe_connection)))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("Failed to load FE config: {}, FE connection disabled", e);
+            None
+        }
+    };
+    
+    let history = match args.history_dir {
+        Some(ref dir) => match HistoryStore::open(dir) {
+            Ok(store) => {
+                println!("History: {}", dir.display());
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                eprintln!("{}, history disabled", e);
+                None
+            }
+        },
+        None => None,
+    };
+    
     println!("Starting server on http://{}:{}", args.host, args.port);
     println!("Frontend: http://{}:{}", args.host, args.port);
     println!("API: http://{}:{}/health, /api/analyze, /api/analyze-file", args.host, args.port);
     println!();
-
+    
     doris_profile_analyzer::api::start_server(
         args.host,
         args.port,
         ai_service,
         Arc::new(default_suggestions),
+        fe_client,
+        history,
     ).await;
     Ok(())
 }
 
+async fn analyze(file: &Path, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let profile_text = read_profile(file)?;
+    
+    // Without the suggestions config only diagnostics with built-in suggestions get one
+    let result = match ConfigLoader::load_default_suggestions() {
+        Ok(config) => doris_profile_analyzer::analyze_profile_with_defaults(&profile_text, None, &config).await?,
+        Err(e) => {
+            eprintln!("Failed to load default suggestions: {}", e);
+            doris_profile_analyzer::analyze_profile(&profile_text)?
+        }
+    };
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        print!("{}", TerminalReport::render_analysis(&result));
+    }
+    Ok(())
+}
+
+fn compare(before: &Path, after: &Path, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let comparison = doris_profile_analyzer::compare_profiles(&read_profile(before)?, &read_profile(after)?)?;
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&comparison)?);
+    } else {
+        print!("{}", TerminalReport::render_comparison(&comparison));
+    }
+    Ok(())
+}
+
+fn batch(dir: &Path, jobs: Option<usize>, json: bool, csv: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let jobs = jobs.unwrap_or_else(|| {
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    });
+    let report = BatchAnalyzer::analyze_dir(dir, jobs)?;
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if csv {
+        print!("{}", BatchAnalyzer::to_csv(&report));
+    } else {
+        print!("{}", TerminalReport::render_batch(&report));
+    }
+    Ok(())
+}
+
+fn redact(
+    file: &Path,
+    output: Option<&Path>,
+    salt: Option<&str>,
+    mapping: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let redacted = doris_profile_analyzer::redact_profile(&read_profile(file)?, salt);
+    
+    if let Some(path) = mapping {
+        std::fs::write(path, serde_json::to_string_pretty(&redacted.mapping)?)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    match output {
+        Some(path) => std::fs::write(path, &redacted.profile_text)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?,
+        None => print!("{}", redacted.profile_text),
+    }
+    Ok(())
+}
+
+fn read_profile(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e).into())
+}
@@ -7,6 +7,18 @@ pub struct Profile {
     pub summary: ProfileSummary,
     pub fragments: Vec<Fragment>,
     pub execution_tree: Option<ExecutionTree>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub detail_profile: Option<DetailProfile>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub physical_plan: Option<PhysicalPlan>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub runtime_filters: Vec<RuntimeFilter>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub parse_warnings: Vec<ParseWarning>,
 }
 
 /// Session variable with name, current and default values
@@ -22,6 +34,16 @@ pub struct SessionVariable {
     pub default_value: String,
 }
 
+/// Problem found while parsing leniently; the affected part was skipped or defaulted.
+/// `line` and `column` are 1-based positions in the original profile text
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParseWarning {
+    pub line: usize,
+    pub column: usize,
+    pub section: String,
+    pub message: String,
+}
+
 /// Summary information about the query
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProfileSummary {
@@ -36,6 +58,10 @@ pub struct ProfileSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub query_type: Option<String>,
     
+    /// Kind of load job, None for queries
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub load_type: Option<LoadType>,
+    
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
     
@@ -51,11 +77,121 @@ pub struct ProfileSummary {
     #[serde(skip_serializing_if = "HashMap::is_empty", default)]
     pub execution_summary: HashMap<String, String>,
     
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub fe_phases: Vec<FePhase>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub backend_schedules: Vec<BackendSchedule>,
+    
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total_time_ms: Option<f64>,
     
     #[serde(skip_serializing_if = "Option::is_none")]
     pub query_peak_memory: Option<u64>,
+    
+    #[serde(default)]
+    pub format: ProfileFormat,
+}
+
+/// FE phase from the Execution Summary, nested the way the profile indents it
+/// (Plan Time > Nereids Translate Time > Finalize Scan Node Time > Get Splits Time)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FePhase {
+    pub name: String,
+    pub value: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub children: Vec<FePhase>,
+}
+
+impl FePhase {
+    /// Find a phase by name anywhere in the tree (depth-first)
+    pub fn find<'a>(phases: &'a [FePhase], name: &str) -> Option<&'a FePhase> {
+        phases.iter().find_map(|phase| {
+            if phase.name == name {
+                Some(phase)
+            } else {
+                Self::find(&phase.children, name)
+            }
+        })
+    }
+    
+    /// Sub-phase with the longest duration
+    pub fn slowest_child(&self) -> Option<&FePhase> {
+        self.children.iter()
+            .filter(|child| child.duration_ms.is_some())
+            .max_by(|a, b| a.duration_ms.partial_cmp(&b.duration_ms).unwrap_or(std::cmp::Ordering::Equal))
+    }
+}
+
+/// Per-backend scheduling data from the Execution Summary: "Instances Num Per BE",
+/// "Splits Assignment Weight" and "Schedule Time Of BE". Entries are keyed by host,
+/// because these counters report different ports of the same BE
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct BackendSchedule {
+    pub host: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instances_num: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_weight: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpc_phase1: Option<ScheduleRpc>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpc_phase2: Option<ScheduleRpc>,
+}
+
+/// Fragment RPC timings of one BE in one schedule phase (milliseconds)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ScheduleRpc {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub work_time_ms: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_time_ms: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_to_be_ms: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_to_fe_ms: Option<f64>,
+}
+
+impl ScheduleRpc {
+    /// Time spent outside the RPC handler: network both ways plus BE queueing
+    pub fn latency_ms(&self) -> f64 {
+        [self.latency_to_be_ms, self.queue_time_ms, self.latency_to_fe_ms].iter()
+            .flatten()
+            .map(|ms| ms.max(0.0))
+            .sum()
+    }
+}
+
+/// Kind of load job a profile belongs to, from the Task Type and SQL statement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LoadType {
+    /// INSERT INTO / INSERT OVERWRITE
+    Insert,
+    /// Stream Load over HTTP, which has no SQL statement
+    StreamLoad,
+    /// Broker / S3 Load ("LOAD LABEL ...")
+    BrokerLoad,
+}
+
+/// Layout of the execution part of a profile, which selects the parser path
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum ProfileFormat {
+    /// Doris 2.1+ pipelineX profiles: MergedProfile / Pipeline N / *_OPERATOR
+    #[default]
+    Pipeline,
+    /// Doris 1.2 / 2.0 profiles: Execution Profile / Instance / V*_NODE
+    NonPipeline,
 }
 
 /// Execution fragment containing pipelines
@@ -71,7 +207,7 @@ pub struct Fragment {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pipeline {
     pub id: String,
-    pub metrics: HashMap<String, String>,
+    pub metrics: HashMap<String, CounterValue>,
     pub operators: Vec<Operator>,
     pub raw_text: String,  // Store original pipeline text for re-parsing
 }
@@ -81,7 +217,197 @@ pub struct Pipeline {
 pub struct Operator {
     pub id: String,
     pub name: String,
-    pub metrics: HashMap<String, String>,
+    pub metrics: HashMap<String, CounterValue>,
+}
+
+/// Per-backend, per-task execution data parsed from the DetailProfile section
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DetailProfile {
+    pub fragments: Vec<DetailFragment>,
+}
+
+/// Fragment in the DetailProfile, split by the backends it ran on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailFragment {
+    pub id: String,
+    pub backends: Vec<BackendFragment>,
+}
+
+/// Execution of one fragment on a single backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendFragment {
+    pub host: String,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub fragment_counters: Vec<MetricItem>,
+    
+    pub pipelines: Vec<BackendPipeline>,
+}
+
+/// Pipeline on a single backend with its tasks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendPipeline {
+    pub id: String,
+    pub tasks: Vec<PipelineTask>,
+}
+
+/// A single PipelineTask (one instance of a pipeline on a backend)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineTask {
+    pub index: u32,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execute_time: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_cpu_time: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_worker_time: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub task_counters: Vec<MetricItem>,
+    
+    pub operators: Vec<TaskOperator>,
+}
+
+/// Operator counters of a single PipelineTask
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskOperator {
+    pub operator_name: String,
+    pub plan_node_id: i32,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nereids_id: Option<i32>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dest_id: Option<i32>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exec_time: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows_produced: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_rows: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_peak: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub common_counters: Vec<MetricItem>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub custom_counters: Vec<MetricItem>,
+}
+
+/// Nereids physical plan from the Appendix section.
+/// The first node is the root of the plan tree.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PhysicalPlan {
+    pub nodes: Vec<PhysicalPlanNode>,
+}
+
+/// Node in the physical plan tree, e.g.
+/// "PhysicalHashJoin[1854]@15 ( stats=720,000,376 actualRows=183750, type=INNER_JOIN, ... )"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhysicalPlanNode {
+    pub id: String,
+    pub name: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nereids_id: Option<i32>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<i32>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_rows: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_rows: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub attributes: Vec<MetricItem>,
+    
+    pub children: Vec<String>,
+    pub depth: usize,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub execution_node_ids: Vec<String>,
+}
+
+/// Runtime filter published by a join build side and applied by scans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeFilter {
+    /// Filter id as printed in counters, e.g. "RF6"
+    pub id: String,
+    
+    /// Filter type from PlanInfo, e.g. "min_max", "in_or_bloom"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_type: Option<String>,
+    
+    /// Build side expression from the physical plan, e.g. "wp_web_page_sk#49"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_expr: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builder: Option<RuntimeFilterBuilder>,
+    
+    pub targets: Vec<RuntimeFilterTarget>,
+}
+
+/// Join that builds and publishes a runtime filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeFilterBuilder {
+    pub node_id: String,
+    pub operator_name: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nereids_id: Option<i32>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_time: Option<u64>,  // ns
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_time: Option<u64>,  // ns
+}
+
+/// Scan that applies a runtime filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeFilterTarget {
+    pub node_id: String,
+    pub operator_name: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_expr: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_rows: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_rows: Option<u64>,
+    
+    /// filter_rows / input_rows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_rate: Option<f64>,
+    
+    /// Max wait time across instances (ns), from DetailProfile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_time: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_limit: Option<u64>,  // ns
+    
+    #[serde(default)]
+    pub reached_timeout: bool,
 }
 
 /// Execution tree for visualization
@@ -92,7 +418,7 @@ pub struct ExecutionTree {
 }
 
 /// Node in the execution tree
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ExecutionTreeNode {
     pub id: String,
     pub operator_name: String,
@@ -104,6 +430,9 @@ pub struct ExecutionTreeNode {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_plan_node_id: Option<i32>,
     
+    #[serde(default)]
+    pub attributes: OperatorAttributes,
+    
     pub metrics: OperatorMetrics,
     pub children: Vec<String>,
     pub depth: usize,
@@ -129,7 +458,7 @@ pub struct ExecutionTreeNode {
     pub is_second_most_consuming: bool,
     
     #[serde(skip_serializing_if = "HashMap::is_empty", default)]
-    pub unique_metrics: HashMap<String, String>,
+    pub unique_metrics: HashMap<String, CounterValue>,
     
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub plan_info: Vec<MetricItem>,
@@ -139,30 +468,174 @@ pub struct ExecutionTreeNode {
     
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub custom_counters: Vec<MetricItem>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub physical_plan_node_id: Option<String>,
 }
 
 /// Represents a metric item with potential nested children
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricItem {
     pub key: String,
+    /// Value as printed in the profile, kept for display
     pub value: String,
+    #[serde(default)]
+    pub counter: CounterValue,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub children: Vec<MetricItem>,
 }
 
-/// Type of execution node
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Kind of a counter value; numeric kinds are stored in normalized units
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum CounterKind {
+    /// Nanoseconds
+    Time,
+    /// Bytes
+    Bytes,
+    /// Plain number (rows, blocks, ids, ...)
+    Count,
+    /// Fraction, "50%" is 0.5
+    Ratio,
+    #[default]
+    Text,
+    /// "N/A"
+    NotAvailable,
+}
+
+/// Typed counter value parsed from a profile metric
+///
+/// Single values set `value`; aggregated values ("sum .., avg .., max .., min ..")
+/// set the parts present in the profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct CounterValue {
+    pub kind: CounterKind,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub value: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub sum: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub avg: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub min: Option<f64>,
+    
+    /// Content of a text counter
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub text: Option<String>,
+}
+
+impl CounterValue {
+    pub fn count(value: f64) -> Self {
+        Self {
+            kind: CounterKind::Count,
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+    
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            kind: CounterKind::Text,
+            text: Some(text.into()),
+            ..Default::default()
+        }
+    }
+    
+    pub fn is_numeric(&self) -> bool {
+        !matches!(self.kind, CounterKind::Text | CounterKind::NotAvailable)
+    }
+    
+    /// Total over all instances: the single value, else the sum, else the average
+    pub fn total(&self) -> Option<f64> {
+        self.value.or(self.sum).or(self.avg)
+    }
+    
+    /// Per-instance average, or the single value
+    pub fn average(&self) -> Option<f64> {
+        self.avg.or(self.value)
+    }
+    
+    /// Largest instance value, or the single value
+    pub fn maximum(&self) -> Option<f64> {
+        self.max.or(self.value)
+    }
+    
+    /// Smallest instance value, or the single value
+    pub fn minimum(&self) -> Option<f64> {
+        self.min.or(self.value)
+    }
+    
+    /// Integer value of an id-like counter
+    pub fn as_i32(&self) -> Option<i32> {
+        self.value.map(|v| v as i32)
+    }
+    
+    pub fn as_text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+}
+
+/// Type of execution node, one per Doris operator. Build / sink halves of
+/// blocking operators are separate types from the operator reading their result
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
 pub enum NodeType {
     OlapScan,
+    FileScan,
+    JdbcScan,
+    EsScan,
+    SchemaScan,
+    MetaScan,
+    GroupCommitScan,
+    /// Table-valued functions generating data, e.g. numbers()
+    DataGenScan,
+    EmptySet,
     Exchange,
+    LocalExchange,
+    LocalExchangeSink,
+    /// Probe side of a hash join
     HashJoin,
+    /// HASH_JOIN_SINK, which builds the hash table
+    HashJoinBuild,
+    /// Probe side of a nested loop join (CROSS_JOIN in Doris 2.1)
+    NestedLoopJoin,
+    NestedLoopJoinBuild,
     Aggregate,
+    AggregateSink,
+    StreamingAggregate,
+    DistinctStreamingAggregate,
     Sort,
+    SortSink,
+    /// Merges sorted streams of local instances (LOCAL_MERGE_SORT_SOURCE)
+    LocalMergeSort,
+    /// Per-partition top-n ahead of a window function
+    PartitionSort,
+    PartitionSortSink,
+    /// Window functions
+    AnalyticEval,
+    AnalyticEvalSink,
+    TableFunction,
+    Repeat,
+    AssertNumRows,
+    CacheSource,
     Limit,
     Project,
+    /// SELECT operator evaluating conjuncts
     Filter,
     Union,
+    UnionSink,
     ResultSink,
+    /// OLAP_TABLE_SINK(_V2) and group commit sinks, which write tablets of a load
+    OlapTableSink,
+    /// HIVE / ICEBERG / JDBC table sinks
+    ExternalTableSink,
+    /// SELECT ... INTO OUTFILE
+    ResultFileSink,
     DataStreamSink,
     MultiCastSink,
     MultiCastSource,
@@ -170,13 +643,98 @@ pub enum NodeType {
     SetProbeSink,
     Intersect,
     Except,
+    #[default]
     Unknown,
 }
 
-impl Default for NodeType {
-    fn default() -> Self {
-        NodeType::Unknown
+impl NodeType {
+    /// Sink ending the plan: the root of a query or load execution tree
+    pub fn is_final_sink(&self) -> bool {
+        matches!(self, NodeType::ResultSink | NodeType::OlapTableSink | NodeType::ExternalTableSink | NodeType::ResultFileSink)
+    }
+    
+    pub fn is_scan(&self) -> bool {
+        matches!(
+            self,
+            NodeType::OlapScan | NodeType::FileScan | NodeType::JdbcScan | NodeType::EsScan
+                | NodeType::SchemaScan | NodeType::MetaScan | NodeType::GroupCommitScan | NodeType::DataGenScan
+        )
+    }
+    
+    pub fn is_join(&self) -> bool {
+        matches!(self, NodeType::HashJoin | NodeType::HashJoinBuild | NodeType::NestedLoopJoin | NodeType::NestedLoopJoinBuild)
+    }
+    
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self, NodeType::Aggregate | NodeType::AggregateSink | NodeType::StreamingAggregate | NodeType::DistinctStreamingAggregate)
+    }
+    
+    pub fn is_sort(&self) -> bool {
+        matches!(self, NodeType::Sort | NodeType::SortSink | NodeType::LocalMergeSort | NodeType::PartitionSort | NodeType::PartitionSortSink)
     }
+    
+    /// Operators moving data between instances or fragments
+    pub fn is_exchange(&self) -> bool {
+        matches!(
+            self,
+            NodeType::Exchange | NodeType::LocalExchange | NodeType::LocalExchangeSink
+                | NodeType::DataStreamSink | NodeType::MultiCastSink | NodeType::MultiCastSource
+        )
+    }
+}
+
+/// Attributes parsed from an operator header ("(nereids_id=..)", "(dest_id=..)",
+/// "(PASSTHROUGH)") and its PlanInfo ("join op")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct OperatorAttributes {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub nereids_id: Option<i32>,
+    
+    /// Exchange a DATA_STREAM_SINK sends to
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub dest_id: Option<i32>,
+    
+    /// Sources a MULTI_CAST_DATA_STREAM_SINK sends to
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub dest_ids: Vec<i32>,
+    
+    /// Id of a MULTI_CAST_DATA_STREAM_SOURCE, matching a dest_id of its sink
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub source_id: Option<i32>,
+    
+    /// Partitioning of a LOCAL_EXCHANGE: PASSTHROUGH, HASH_SHUFFLE, BUCKET_HASH_SHUFFLE, ...
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub exchange_type: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub join_type: Option<JoinType>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub join_distribution: Option<JoinDistribution>,
+}
+
+/// Join type from "join op: LEFT SEMI JOIN(BROADCAST)[]"
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JoinType {
+    Inner,
+    LeftOuter,
+    RightOuter,
+    FullOuter,
+    LeftSemi,
+    RightSemi,
+    LeftAnti,
+    RightAnti,
+    NullAwareLeftAnti,
+    Cross,
+}
+
+/// How the two sides of a join are distributed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JoinDistribution {
+    Broadcast,
+    Partitioned,
+    BucketShuffle,
+    Colocate,
 }
 
 /// Metrics for an operator
@@ -217,18 +775,107 @@ pub struct OperatorMetrics {
 }
 
 /// Severity level for hotspots
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
 pub enum HotspotSeverity {
     Critical,
     High,
     Medium,
     Low,
+    #[default]
     None,
 }
 
-impl Default for HotspotSeverity {
-    fn default() -> Self {
-        HotspotSeverity::None
+/// Kind of problem a hotspot describes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum HotspotKind {
+    /// Operator consuming a large share of execution time
+    #[default]
+    TimeConsuming,
+    /// Planner row estimate far from the actual row count
+    CardinalityMisestimation,
+    /// Instances of an operator processing very uneven amounts of work
+    DataSkew,
+    /// Runtime filter that filters nothing or times out
+    RuntimeFilter,
+    /// FE planning, split enumeration or scheduling taking a large share of the query
+    PlanningOverhead,
+    /// Instances, splits or fragment RPC time concentrated on one BE
+    BackendImbalance,
+    /// Table sink of a load with slow tablet writers, small batches or long replica close wait
+    LoadSink,
+    /// Operator contributing a large share of the chain that determined wall-clock latency
+    CriticalPath,
+    /// Pipeline tasks waiting for worker threads (CPU-starved BE) or blocked on upstream dependencies
+    PipelineTime,
+    /// Memory limit failures, failed reservations, spilling or operators holding a lot of memory
+    Memory,
+    /// Exchange senders blocked on the network or receivers, or RPCs with a long tail
+    Shuffle,
+    /// External table scans with ineffective row-group / page pruning, small or empty
+    /// files, footer cache misses, slow decompression or Iceberg delete files
+    LakehouseScan,
+    /// Scans throttled by object storage (429 Too Many Requests) or bound by many small GET requests
+    RemoteIo,
+}
+
+impl HotspotKind {
+    /// Title of the suggestion for a hotspot of this kind on an operator
+    pub fn title(self, operator_name: &str, node_type: NodeType) -> String {
+        match self {
+            HotspotKind::TimeConsuming => format!("Optimize {} operator", operator_name),
+            HotspotKind::CardinalityMisestimation => format!("Collect statistics for {} input tables", operator_name),
+            HotspotKind::DataSkew => format!("Fix data skew in {} operator", operator_name),
+            HotspotKind::RuntimeFilter => format!("Review runtime filters on {} operator", operator_name),
+            HotspotKind::PlanningOverhead => format!("Reduce FE {} overhead", operator_name),
+            HotspotKind::BackendImbalance => format!("Balance {} across BEs", operator_name),
+            HotspotKind::LoadSink => format!("Speed up load writes of {}", operator_name),
+            HotspotKind::CriticalPath => format!("Shorten the critical path at {} operator", operator_name),
+            HotspotKind::PipelineTime => format!("Reduce pipeline waiting on {}", operator_name),
+            HotspotKind::Memory if node_type == NodeType::Unknown => "Keep the query within its memory limit".to_string(),
+            HotspotKind::Memory => format!("Reduce memory of {} operator", operator_name),
+            HotspotKind::Shuffle => format!("Reduce network shuffle at {} operator", operator_name),
+            HotspotKind::LakehouseScan => format!("Read less data in {} operator", operator_name),
+            HotspotKind::RemoteIo => format!("Relieve object storage requests of {} operator", operator_name),
+        }
+    }
+    
+    /// Category of the suggestion for a hotspot of this kind; query-wide hotspots
+    /// have an Unknown node type, the others mostly follow their operator
+    pub fn category(self, node_type: NodeType) -> SuggestionCategory {
+        match self {
+            HotspotKind::CardinalityMisestimation => SuggestionCategory::Schema,
+            HotspotKind::RuntimeFilter => SuggestionCategory::Configuration,
+            HotspotKind::PlanningOverhead => SuggestionCategory::Configuration,
+            HotspotKind::BackendImbalance => SuggestionCategory::Configuration,
+            HotspotKind::LoadSink => SuggestionCategory::Configuration,
+            HotspotKind::PipelineTime if node_type == NodeType::Unknown => SuggestionCategory::Configuration,
+            HotspotKind::Memory if node_type == NodeType::Unknown => SuggestionCategory::Resource,
+            HotspotKind::RemoteIo => SuggestionCategory::Resource,
+            _ if node_type.is_scan() => SuggestionCategory::Schema,
+            _ if node_type.is_exchange() => SuggestionCategory::Configuration,
+            _ => SuggestionCategory::Query,
+        }
+    }
+    
+    /// Whether hotspots of this kind share a suggestion when severity and title match;
+    /// the others share one per suggestion text, e.g. misestimations of the same
+    /// tables or checks of one table sink that share a title
+    pub fn dedup_by_title(self) -> bool {
+        match self {
+            HotspotKind::TimeConsuming => true,
+            HotspotKind::CardinalityMisestimation => false,
+            HotspotKind::DataSkew => true,
+            HotspotKind::RuntimeFilter => true,
+            HotspotKind::PlanningOverhead => true,
+            HotspotKind::BackendImbalance => true,
+            HotspotKind::LoadSink => false,
+            HotspotKind::CriticalPath => true,
+            HotspotKind::PipelineTime => false,
+            HotspotKind::Memory => false,
+            HotspotKind::Shuffle => false,
+            HotspotKind::LakehouseScan => false,
+            HotspotKind::RemoteIo => false,
+        }
     }
 }
 
@@ -238,9 +885,15 @@ pub struct HotSpot {
     pub node_id: String,
     pub node_path: String,
     pub operator_name: String,
+    
+    #[serde(default)]
+    pub node_type: NodeType,
     pub severity: HotspotSeverity,
     pub description: String,
     
+    #[serde(default)]
+    pub kind: HotspotKind,
+    
     #[serde(skip_serializing_if = "Option::is_none")]
     pub time_percentage: Option<f64>,
     
@@ -287,5 +940,726 @@ pub struct ProfileAnalysisResponse {
     pub performance_score: u32,
     pub execution_tree: Option<ExecutionTree>,
     pub summary: Option<ProfileSummary>,
-}
-
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub physical_plan: Option<PhysicalPlan>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub runtime_filters: Vec<RuntimeFilter>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub parse_warnings: Vec<ParseWarning>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub critical_path: Option<CriticalPath>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub time_accounting: Option<TimeAccounting>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub memory: Option<MemoryBreakdown>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub shuffle: Option<ShuffleModel>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub lakehouse: Option<LakehouseScanModel>,
+    
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub remote_io: Option<RemoteIoModel>,
+}
+
+/// Data moved between fragments, one edge per DATA_STREAM_SINK -> EXCHANGE pair
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ShuffleModel {
+    pub edges: Vec<ShuffleEdge>,
+}
+
+/// Counters of a sink and the exchange it sends to, summed over instances
+/// (times are of the slowest instance)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ShuffleEdge {
+    pub sink_node_id: String,
+    pub exchange_node_id: String,
+    
+    /// dest_id of the sink, the plan node id of the exchange
+    pub exchange_id: i32,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_fragment_id: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receiver_fragment_id: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u64>,
+    
+    /// BytesSent: serialized blocks sent over RPC
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_sent: Option<u64>,
+    
+    /// LocalBytesSent: blocks handed to receivers on the same BE without RPC
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_bytes_sent: Option<u64>,
+    
+    /// UncompressedRowBatchSize: serialized blocks before compression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uncompressed_bytes: Option<u64>,
+    
+    /// RemoteBytesReceived of the exchange
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_bytes_received: Option<u64>,
+    
+    /// uncompressed_bytes / bytes_sent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression_ratio: Option<f64>,
+    
+    /// Share of the sent bytes that stayed on the same BE
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_percentage: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpc_count: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpc_avg_ns: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpc_max_ns: Option<u64>,
+    
+    /// WaitForRpcBufferQueue: sender blocked until earlier RPCs complete
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpc_buffer_wait_ns: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compress_time_ns: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decompress_bytes: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decompress_time_ns: Option<u64>,
+}
+
+/// Reader counters of the external table (FILE_SCAN_OPERATOR) scans
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LakehouseScanModel {
+    pub scans: Vec<LakehouseScan>,
+}
+
+/// Scanner counters of one FILE_SCAN_OPERATOR summed over its instances; times
+/// are summed over the scanners too, so compare them with reader_time_ns
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LakehouseScan {
+    pub node_id: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fragment_id: Option<String>,
+    
+    /// "parquet" or "orc", from the reader counter group
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    
+    /// FileNumber: files (splits) opened by the scanners
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub empty_files: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_read_bytes: Option<u64>,
+    
+    /// RawRowsRead: rows decoded after row-group and page pruning
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_rows_read: Option<u64>,
+    
+    /// RowsProduced of the operator, after predicates and runtime filters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows_produced: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filtered_rows_by_group: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filtered_rows_by_page: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_row_groups: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filtered_row_groups: Option<u64>,
+    
+    /// Share of the rows in the files skipped by row-group statistics or the page index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pruned_percentage: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_index_read_calls: Option<u64>,
+    
+    /// FileFooterHitCache / FileFooterReadCalls: footers found in the file meta
+    /// cache vs read and parsed from the file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer_hit_cache: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer_read_calls: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer_hit_percentage: Option<f64>,
+    
+    /// FileScannerGetBlockTime: time the scanners spent producing blocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reader_time_ns: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_group_filter_time_ns: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_footer_time_ns: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decompress_time_ns: Option<u64>,
+    
+    /// NumDeleteFiles / NumDeleteRows of the Iceberg reader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_files: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_rows: Option<u64>,
+    
+    /// DeleteFileReadTime + DeleteRowsSortTime
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_time_ns: Option<u64>,
+}
+
+/// Object storage (S3 / OSS / COS ...) requests of the external table scans
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RemoteIoModel {
+    pub scans: Vec<RemoteIoScan>,
+}
+
+/// S3Profile counters of one FILE_SCAN_OPERATOR summed over its instances
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RemoteIoScan {
+    pub node_id: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fragment_id: Option<String>,
+    
+    /// TotalGetRequest
+    pub get_requests: u64,
+    
+    /// TotalGetRequestTime, summed over the scanners
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get_request_time_ns: Option<u64>,
+    
+    /// TotalBytesRead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_read: Option<u64>,
+    
+    /// GET requests per second of query time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_per_second: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_request_bytes: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_request_time_ns: Option<u64>,
+    
+    /// TooManyRequestErr: requests rejected with 429 Too Many Requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throttled_requests: Option<u64>,
+    
+    /// TooManyRequestSleepTime: back-off slept before retrying throttled requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throttle_sleep_ns: Option<u64>,
+    
+    /// throttle_sleep_ns as a share of the scanner time (FileScannerGetBlockTime)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throttle_percentage: Option<f64>,
+    
+    /// RequestIO / MergedIO of MergedSmallIO: reads asked for by the readers vs
+    /// reads issued after merging adjacent small ranges
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_io: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merged_io: Option<u64>,
+    
+    /// FileScannerGetBlockTime: time the scanners spent producing blocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scanner_time_ns: Option<u64>,
+}
+
+/// Peak memory of the query broken down by operator and operator component
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MemoryBreakdown {
+    /// Query peak reported by the FE summary
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_peak_bytes: Option<u64>,
+    
+    /// exec_mem_limit of the session, when set in the changed session variables
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mem_limit_bytes: Option<u64>,
+    
+    /// Sum of the operator peaks; an upper bound of the query peak, as operators
+    /// do not all peak at the same time
+    pub operator_peak_bytes: u64,
+    
+    /// MemoryReserveTimes / MemoryReserveFailedTimes summed over the PipelineTasks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserve_times: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserve_failed_times: Option<u64>,
+    
+    /// Largest operators first
+    pub operators: Vec<OperatorMemory>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorMemory {
+    pub node_id: String,
+    pub operator_name: String,
+    pub node_type: NodeType,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fragment_id: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pipeline_id: Option<String>,
+    
+    /// MemoryUsagePeak summed over the instances
+    pub peak_bytes: u64,
+    
+    /// MemoryUsagePeak of the largest instance
+    pub max_instance_peak_bytes: u64,
+    
+    /// MemoryUsageHashTable, MemoryUsageBuildBlocks, arenas, ... largest first
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub components: Vec<MemoryComponent>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spill: Option<SpillStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryComponent {
+    /// Counter name without the MemoryUsage prefix, e.g. HashTable or BuildKeyArena
+    pub name: String,
+    pub bytes: u64,
+}
+
+/// Spill counters of an operator, summed over the instances
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SpillStats {
+    pub write_bytes: u64,
+    pub write_rows: u64,
+    pub read_bytes: u64,
+    
+    /// SpillTotalTime of the slowest instance
+    pub spill_time_ns: u64,
+}
+
+/// Where pipeline tasks spent their time, per pipeline and per fragment
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TimeAccounting {
+    /// Built from PipelineTask counters of the DetailProfile, or estimated from
+    /// MergedProfile averages when the profile has no detail section
+    pub from_detail_profile: bool,
+    pub pipelines: Vec<PipelineTime>,
+    pub fragments: Vec<FragmentTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineTime {
+    pub fragment_id: String,
+    pub pipeline_id: String,
+    
+    /// Operator the pipeline reads from, e.g. EXCHANGE_OPERATOR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_operator: Option<String>,
+    
+    #[serde(flatten)]
+    pub time: TimeBreakdown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FragmentTime {
+    pub fragment_id: String,
+    
+    #[serde(flatten)]
+    pub time: TimeBreakdown,
+}
+
+/// Task time summed over all tasks (ns)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TimeBreakdown {
+    pub task_count: u64,
+    
+    /// ExecuteTime without SinkTime: running operators on a worker thread
+    pub running_ns: u64,
+    
+    /// SinkTime: pushing blocks into the sink operator
+    pub sink_ns: u64,
+    
+    /// WaitWorkerTime: runnable, but waiting for a free worker thread
+    pub wait_worker_ns: u64,
+    
+    /// WaitForDependency times: blocked until upstream data or a build side is ready
+    pub dependency_wait_ns: u64,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_cpu_ns: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_times: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yield_times: Option<u64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_change_times: Option<u64>,
+}
+
+impl TimeBreakdown {
+    /// Sum of running, sink, worker wait and dependency wait
+    pub fn total_ns(&self) -> u64 {
+        self.running_ns + self.sink_ns + self.wait_worker_ns + self.dependency_wait_ns
+    }
+    
+    /// Share of the total in percent
+    pub fn share(&self, part_ns: u64) -> f64 {
+        match self.total_ns() {
+            0 => 0.0,
+            total => part_ns as f64 / total as f64 * 100.0,
+        }
+    }
+    
+    pub fn add(&mut self, other: &TimeBreakdown) {
+        fn add_opt(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+            match (a, b) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+            }
+        }
+        self.task_count += other.task_count;
+        self.running_ns += other.running_ns;
+        self.sink_ns += other.sink_ns;
+        self.wait_worker_ns += other.wait_worker_ns;
+        self.dependency_wait_ns += other.dependency_wait_ns;
+        self.task_cpu_ns = add_opt(self.task_cpu_ns, other.task_cpu_ns);
+        self.schedule_times = add_opt(self.schedule_times, other.schedule_times);
+        self.yield_times = add_opt(self.yield_times, other.yield_times);
+        self.core_change_times = add_opt(self.core_change_times, other.core_change_times);
+    }
+}
+
+/// Chain of operators from the final sink down to a leaf that determined
+/// the wall-clock latency of the query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriticalPath {
+    /// Estimated latency of the chain: the sum of the step contributions
+    pub latency_ns: u64,
+    
+    /// Steps from the final sink to the leaf
+    pub steps: Vec<CriticalPathStep>,
+}
+
+/// Operator on the critical path with the time it added to the chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriticalPathStep {
+    pub node_id: String,
+    pub operator_name: String,
+    pub node_type: NodeType,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fragment_id: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pipeline_id: Option<String>,
+    
+    /// ExecTime of the slowest instance
+    pub exec_time_ns: u64,
+    
+    /// Dependency / data wait of the slowest instance not covered by the next step
+    /// of the path, i.e. waiting on work the tree does not show (scanners, network)
+    pub wait_time_ns: u64,
+    
+    /// exec_time_ns + wait_time_ns
+    pub contribution_ns: u64,
+    
+    /// Share of the query time, or of the path latency when the total time is unknown
+    pub percentage: f64,
+}
+
+/// Comparison of two profiles of the same query (e.g. before/after a change)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileComparison {
+    pub before_query_id: String,
+    pub after_query_id: String,
+    pub total_time: MetricDelta,  // ms
+    pub node_diffs: Vec<NodeDiff>,
+    pub added_operators: Vec<OperatorRef>,
+    pub removed_operators: Vec<OperatorRef>,
+    pub session_variable_changes: Vec<SessionVariableChange>,
+    
+    /// Parts of the before profile that were skipped or defaulted while parsing
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub before_parse_warnings: Vec<ParseWarning>,
+    
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub after_parse_warnings: Vec<ParseWarning>,
+}
+
+/// Per-node deltas for execution nodes present in both profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeDiff {
+    pub operator_name: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan_node_id: Option<i32>,
+    
+    pub before_node_id: String,
+    pub after_node_id: String,
+    pub time: MetricDelta,  // ns
+    pub rows: MetricDelta,
+    pub memory: MetricDelta,  // bytes
+}
+
+/// Before/after values of a single metric
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct MetricDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<f64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<f64>,
+    
+    /// Change relative to `before`, in percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta_percentage: Option<f64>,
+}
+
+/// Reference to an operator that only exists in one of the compared profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorRef {
+    pub node_id: String,
+    pub operator_name: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan_node_id: Option<i32>,
+}
+
+/// Session variable that differs between the compared profiles.
+/// A side where the variable was not changed reports its default value.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionVariableChange {
+    pub name: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// Aggregate report over a batch of analyzed profiles
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BatchReport {
+    pub total_files: usize,
+    pub analyzed_count: usize,
+    
+    /// Every analyzed profile, in file order
+    pub entries: Vec<BatchEntry>,
+    
+    /// Entries with the longest total time, slowest first
+    pub slowest_queries: Vec<BatchEntry>,
+    
+    /// Operators by how often they show up as hotspots, most frequent first
+    pub hotspot_operators: Vec<OperatorFrequency>,
+    
+    pub score_distribution: Vec<ScoreBucket>,
+    pub failures: Vec<BatchFailure>,
+}
+
+/// Result of one analyzed profile in a batch
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchEntry {
+    pub file: String,
+    pub query_id: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_time_ms: Option<f64>,
+    
+    pub performance_score: u32,
+    pub hotspot_count: usize,
+    
+    /// Operator of the most severe hotspot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_hotspot: Option<String>,
+    
+    /// Operator of every hotspot, most severe first
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub hotspot_operators: Vec<String>,
+}
+
+/// How often an operator was reported as a hotspot across a batch
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OperatorFrequency {
+    pub operator_name: String,
+    pub hotspot_count: usize,
+    
+    /// Number of profiles with at least one hotspot on this operator
+    pub query_count: usize,
+}
+
+/// Number of profiles per score category (Excellent, Good, ...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScoreBucket {
+    pub category: String,
+    pub count: usize,
+}
+
+/// Profile that could not be read or analyzed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchFailure {
+    pub file: String,
+    pub error: String,
+}
+
+/// Query listed by the FE query info API
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct FeQueryInfo {
+    pub query_id: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fe_node: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sql: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_type: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+    
+    /// Execution duration as reported by the FE (e.g. "1sec240ms")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
+/// Index entry of a stored analysis
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    pub query_id: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_db: Option<String>,
+    
+    pub start_time: String,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_time_ms: Option<f64>,
+    
+    pub query_state: String,
+    pub performance_score: u32,
+    pub hotspot_count: usize,
+    
+    /// When the analysis was stored (RFC 3339)
+    pub analyzed_at: String,
+}
+
+/// Filter for searching stored analyses; every field is optional
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HistoryFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_db: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_state: Option<String>,
+    
+    /// Earliest query start time, "YYYY-MM-DD HH:MM:SS" or "YYYY-MM-DD"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_from: Option<String>,
+    
+    /// Latest query start time, same formats as `start_from`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_to: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_score: Option<u32>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_score: Option<u32>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Stored analysis with its parsed profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub entry: HistoryEntry,
+    pub profile: Profile,
+    pub analysis: ProfileAnalysisResponse,
+}
+
+/// Kind of value replaced when redacting a profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RedactionKind {
+    User,
+    Catalog,
+    Database,
+    Table,
+    /// BE / FE host name or IP address
+    Host,
+    Port,
+    /// String literal of the SQL statement
+    Literal,
+}
+
+/// One original value and the alias that replaced it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RedactionEntry {
+    pub kind: RedactionKind,
+    pub original: String,
+    pub redacted: String,
+}
+
+/// Redacted profile text with the mapping used, so the owner can map results back
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RedactedProfile {
+    pub profile_text: String,
+    pub mapping: Vec<RedactionEntry>,
+}
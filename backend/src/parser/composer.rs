@@ -3,18 +3,33 @@
 
 use crate::models::*;
 use crate::parser::error::{ParseError, ParseResult};
-use crate::parser::engine::{SectionParser, FragmentParser, TreeBuilder};
+use crate::parser::engine::{SectionParser, FragmentParser, TreeBuilder, DetailProfileParser, PhysicalPlanParser, RuntimeFilterParser, NonPipelineParser, ProfileValidator};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Regex for the major.minor part of "Doris Version", e.g. "doris-2.0.4-rc06-2f2d0b5"
+static VERSION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d+)\.(\d+)\.\d+").unwrap()
+});
 
 /// ProfileComposer is responsible for parsing Doris profile text
 /// and composing it into a structured Profile object.
 pub struct ProfileComposer {
-    // Parser state if needed
+    /// Return partial results with `parse_warnings` instead of failing on missing sections
+    lenient: bool,
 }
 
 impl ProfileComposer {
     /// Create a new ProfileComposer instance
     pub fn new() -> Self {
-        Self {}
+        Self { lenient: false }
+    }
+    
+    /// Create a ProfileComposer that parses truncated or partly unknown profiles.
+    /// Missing sections are defaulted and everything skipped is reported in
+    /// `Profile::parse_warnings` with its line and column
+    pub fn lenient() -> Self {
+        Self { lenient: true }
     }
     
     /// Filter out DetailProfile section, keeping only the first 4 sections:
@@ -47,30 +62,146 @@ impl ProfileComposer {
         let filtered_text = Self::filter_detail_profile(profile_text);
         
         // Parse Summary section
-        let mut summary = SectionParser::parse_summary(&filtered_text)?;
+        let summary = SectionParser::parse_summary(&filtered_text);
+        let has_summary = summary.is_ok();
+        let mut summary = match summary {
+            Ok(summary) => summary,
+            Err(_) if self.lenient => ProfileSummary::default(),
+            Err(e) => return Err(e),
+        };
         
         // Parse ChangedSessionVariables (optional)
         if let Ok(variables) = SectionParser::parse_session_variables(&filtered_text) {
             summary.session_variables = variables;
         }
         
+        summary.format = Self::detect_format(&filtered_text, &summary);
+        let mut profile = match summary.format {
+            ProfileFormat::Pipeline => self.parse_pipeline(profile_text, &filtered_text, summary)?,
+            ProfileFormat::NonPipeline => self.parse_non_pipeline(&filtered_text, summary)?,
+        };
+        
+        if self.lenient {
+            if !has_summary && profile.execution_tree.is_none() {
+                return Err(ParseError::InvalidFormat("No Summary or execution profile found".to_string()));
+            }
+            profile.parse_warnings = Self::collect_warnings(profile_text, &profile);
+        }
+        
+        Ok(profile)
+    }
+    
+    /// Locate everything the lenient parse skipped or defaulted, in profile order
+    fn collect_warnings(profile_text: &str, profile: &Profile) -> Vec<ParseWarning> {
+        let mut warnings = ProfileValidator::check_sections(profile_text, profile.summary.format);
+        warnings.extend(ProfileValidator::check_lines(profile_text));
+        match profile.execution_tree {
+            Some(ref tree) => warnings.extend(ProfileValidator::check_tree(profile_text, tree)),
+            None => warnings.extend(ProfileValidator::check_operators(profile_text, profile.summary.format)),
+        }
+        
+        warnings.sort_by_key(|w| (w.line, w.column));
+        warnings
+    }
+    
+    /// Detect the profile format from the section layout, falling back to the
+    /// Doris version (the pipeline engine is the default since 2.1)
+    pub fn detect_format(profile_text: &str, summary: &ProfileSummary) -> ProfileFormat {
+        if profile_text.lines().any(|line| line.trim_start().starts_with("MergedProfile:")) {
+            return ProfileFormat::Pipeline;
+        }
+        if profile_text.lines().any(|line| line.trim_start().starts_with("Execution Profile ")) {
+            return ProfileFormat::NonPipeline;
+        }
+        
+        let version = VERSION_REGEX.captures(&summary.doris_version)
+            .and_then(|caps| Some((caps[1].parse::<u32>().ok()?, caps[2].parse::<u32>().ok()?)));
+        match version {
+            Some(version) if version < (2, 1) => ProfileFormat::NonPipeline,
+            _ => ProfileFormat::Pipeline,
+        }
+    }
+    
+    /// Parse a pipeline profile (Doris 2.1+): MergedProfile, DetailProfile and Appendix
+    fn parse_pipeline(&self, profile_text: &str, filtered_text: &str, summary: ProfileSummary) -> ParseResult<Profile> {
         // Extract MergedProfile section
-        let merged_profile = SectionParser::extract_merged_profile(&filtered_text)?;
+        let merged_profile = match SectionParser::extract_merged_profile(filtered_text) {
+            Ok(text) => text,
+            Err(_) if self.lenient => String::new(),
+            Err(e) => return Err(e),
+        };
         
         // Parse Fragments from MergedProfile
         let fragments = FragmentParser::extract_all_fragments(&merged_profile);
         
-        if fragments.is_empty() {
+        if fragments.is_empty() && !self.lenient {
             return Err(ParseError::InvalidFormat("No fragments found in profile".to_string()));
         }
         
-        // Build execution tree from fragments
-        let execution_tree = TreeBuilder::build_from_fragments(&fragments, &summary);
+        // Build execution tree from fragments (none in lenient mode without operators)
+        let mut execution_tree = TreeBuilder::build_from_fragments(&fragments, &summary);
+        
+        // Parse DetailProfile (optional) for per-backend / per-task data
+        let detail_profile = SectionParser::extract_detail_profile(profile_text)
+            .ok()
+            .map(|text| DetailProfileParser::parse(&text))
+            .filter(|detail| !detail.fragments.is_empty());
+        
+        // Parse Appendix PhysicalPlan (optional) and link it to the execution tree
+        let physical_plan = SectionParser::extract_physical_plan(profile_text)
+            .ok()
+            .map(|text| PhysicalPlanParser::parse(&text))
+            .filter(|plan| !plan.nodes.is_empty())
+            .map(|mut plan| {
+                if let Some(ref mut tree) = execution_tree {
+                    PhysicalPlanParser::link_execution_tree(&mut plan, tree);
+                }
+                plan
+            });
+        
+        // Link runtime filters to their publishing joins and target scans
+        let runtime_filters = execution_tree.as_ref()
+            .map(|tree| RuntimeFilterParser::build(tree, physical_plan.as_ref(), detail_profile.as_ref()))
+            .unwrap_or_default();
         
         Ok(Profile {
             summary,
             fragments,
-            execution_tree: Some(execution_tree),
+            execution_tree,
+            detail_profile,
+            physical_plan,
+            runtime_filters,
+            parse_warnings: Vec::new(),
+        })
+    }
+    
+    /// Parse a non-pipeline profile (Doris 1.2 / 2.0): per-instance Execution Profile
+    fn parse_non_pipeline(&self, filtered_text: &str, summary: ProfileSummary) -> ParseResult<Profile> {
+        let execution_profile = match SectionParser::extract_execution_profile(filtered_text) {
+            Ok(text) => text,
+            Err(_) if self.lenient => String::new(),
+            Err(e) => return Err(e),
+        };
+        let parsed = NonPipelineParser::parse(&execution_profile);
+        
+        if parsed.fragments.is_empty() && !self.lenient {
+            return Err(ParseError::InvalidFormat("No fragments found in profile".to_string()));
+        }
+        
+        let execution_tree = (!parsed.operators.is_empty())
+            .then(|| TreeBuilder::build_from_nested(&parsed.operators, &summary));
+        let runtime_filters = execution_tree.as_ref()
+            .map(|tree| RuntimeFilterParser::build(tree, None, None))
+            .unwrap_or_default();
+        
+        Ok(Profile {
+            summary,
+            fragments: parsed.fragments,
+            execution_tree,
+            detail_profile: None,
+            physical_plan: None,
+            runtime_filters,
+            parse_warnings: Vec::new(),
         })
     }
 }
@@ -207,4 +338,146 @@ MergedProfile:
         assert!(scan.time_percentage.is_some());
         assert!(scan.is_most_consuming || scan.time_percentage.unwrap() > 50.0);
     }
+    
+    #[test]
+    fn test_parse_load_profile() {
+        let profile_text = r#"Summary:
+   - Profile ID: 5a1c0e2b7f3d4e61-9b8a7c6d5e4f3a21
+   - Task Type: LOAD
+   - Start Time: 2025-12-16 10:02:11
+   - End Time: 2025-12-16 10:02:21
+   - Total: 10sec0ms
+   - Task State: OK
+   - User: root
+   - Default Db: tpcds
+   - Sql Statement: INSERT INTO store_sales_copy SELECT * FROM store_sales
+Execution Summary:
+   - Workload Group: normal
+ChangedSessionVariables:
+[]
+MergedProfile:
+     Fragments:
+       Fragment 0:
+         Pipeline 0(instance_num=4):
+           OLAP_TABLE_SINK_OPERATOR(id=-1):
+             CommonCounters:
+               - ExecTime: avg 7sec100ms, max 7sec600ms, min 6sec800ms
+               - InputRows: sum 2.88M (2880000), avg 720.0K (720000), max 730.0K (730000), min 710.0K (710000)
+             CustomCounters:
+               - NumberBatchAdded: sum 2.4K (2400), avg 600, max 620, min 580
+               - NumberNodeChannels: avg 3, max 3, min 3
+               - MaxAddBatchExecTime: avg 2sec10ms, max 2sec200ms, min 1sec800ms
+               - TotalAddBatchExecTime: avg 4sec500ms, max 5sec10ms, min 4sec20ms
+               - CloseWaitTime: avg 5sec800ms, max 6sec100ms, min 5sec500ms
+           OLAP_SCAN_OPERATOR(nereids_id=12. table_name=store_sales(store_sales))(id=0):
+             CommonCounters:
+               - ExecTime: avg 1sec200ms, max 1sec500ms, min 1sec20ms
+               - RowsProduced: sum 2.88M (2880000), avg 720.0K (720000), max 730.0K (730000), min 710.0K (710000)
+"#;
+        
+        let profile = ProfileComposer::new().parse(profile_text).unwrap();
+        assert_eq!(profile.summary.load_type, Some(LoadType::Insert));
+        
+        let tree = profile.execution_tree.as_ref().unwrap();
+        assert_eq!(tree.root.node_type, NodeType::OlapTableSink);
+        assert_eq!(tree.root.operator_name, "OLAP_TABLE_SINK_OPERATOR");
+        let scan = tree.nodes.iter().find(|n| n.node_type == NodeType::OlapScan).unwrap();
+        assert_eq!(scan.depth, 1);
+        
+        // Close wait dominates the load; the writers themselves and the batch sizes are fine
+        let hotspots = crate::diagnostic::LoadSink::analyze(&profile);
+        assert_eq!(hotspots.len(), 1);
+        assert_eq!(hotspots[0].severity, HotspotSeverity::High);
+        assert!(hotspots[0].description.contains("waited 6.10s (61.0% of the load)"));
+    }
+    
+    #[test]
+    fn test_parse_non_pipeline_profile() {
+        let profile_text = r#"Query:
+  Summary:
+     - Query ID: 5e1d8c2f9a7b4c3d-9e8f7a6b5c4d3e2f
+     - Start Time: 2023-06-01 12:00:00
+     - End Time: 2023-06-01 12:00:01
+     - Total: 1s502ms
+     - Query Type: Query
+     - Query State: EOF
+     - Doris Version: 1.2.4-rc01
+     - User: root
+     - Default Db: default_cluster:test
+     - Sql Statement: select count(*) from t
+  Execution Profile 5e1d8c2f9a7b4c3d-9e8f7a6b5c4d3e2f:(Active: 1s500ms, % non-child: 0.00%)
+    Fragment 0:
+      Instance 5e1d8c2f9a7b4c3d-9e8f7a6b5c4d3e30 (host=TNetworkAddress(hostname:10.0.0.1, port:9060)):(Active: 1s490ms, % non-child: 0.00%)
+        VResultSink:(Active: 20.000us, % non-child: 100.00%)
+           - NumSentRows: 1
+        VAGGREGATION_NODE (id=1):(Active: 1s480ms, % non-child: 2.00%)
+           - RowsReturned: 1
+          VOLAP_SCAN_NODE (id=0):(Active: 1s450ms, % non-child: 100.00%)
+             - RowsReturned: 1.000M (1000000)
+"#;
+        
+        let mut composer = ProfileComposer::new();
+        let profile = composer.parse(profile_text).expect("Parse failed");
+        
+        assert_eq!(profile.summary.format, ProfileFormat::NonPipeline);
+        assert_eq!(profile.summary.query_id, "5e1d8c2f9a7b4c3d-9e8f7a6b5c4d3e2f");
+        assert_eq!(profile.summary.query_state, "EOF");
+        
+        let tree = profile.execution_tree.unwrap();
+        assert_eq!(tree.nodes.len(), 3);
+        assert!(tree.root.operator_name.contains("RESULT_SINK"));
+        let scan = tree.nodes.iter().find(|n| n.operator_name == "OLAP_SCAN_OPERATOR").unwrap();
+        assert_eq!(scan.metrics.rows_returned, Some(1000000));
+        assert!(scan.is_most_consuming);
+    }
+    
+    #[test]
+    fn test_parse_truncated_profile_lenient() {
+        let profile_text = r#"Summary:
+   - Profile ID: test-123
+   - Total: 1sec
+MergedProfile:
+     Fragments:
+       Fragment 0:
+         Pipeline 0(instance_num=1):
+           RESULT_SINK_OPERATOR(id=2147483647):
+             CommonCounters:
+               - ExecTime: avg 95.241us, max 95.241us, min 95.241us
+             CustomCounters:
+           EXCHANGE_OPERATOR(id=3):
+"#;
+        
+        assert!(ProfileComposer::new().parse("   - Profile ID: x\n").is_err());
+        assert!(ProfileComposer::lenient().parse("no profile here").is_err());
+        
+        let profile = ProfileComposer::lenient().parse(profile_text).expect("Parse failed");
+        assert_eq!(profile.summary.query_id, "test-123");
+        assert!(profile.execution_tree.is_some());
+        assert_eq!(profile.parse_warnings.len(), 1, "{:?}", profile.parse_warnings);
+        assert_eq!(profile.parse_warnings[0].line, 12);
+        assert_eq!(profile.parse_warnings[0].section, "MergedProfile");
+        
+        // Without MergedProfile the summary is still returned
+        let profile = ProfileComposer::lenient()
+            .parse("Summary:\n   - Profile ID: test-123\n")
+            .expect("Parse failed");
+        assert!(profile.execution_tree.is_none());
+        assert!(profile.parse_warnings.iter().any(|w| w.section == "MergedProfile"));
+    }
+    
+    #[test]
+    fn test_detect_format_by_version() {
+        let mut summary = SectionParser::parse_summary("Summary:\n   - Profile ID: x\n").unwrap();
+        
+        summary.doris_version = "doris-2.0.4-rc06-2f2d0b5".to_string();
+        assert_eq!(ProfileComposer::detect_format("", &summary), ProfileFormat::NonPipeline);
+        summary.doris_version = "doris-2.1.7-rc03-443e87e203".to_string();
+        assert_eq!(ProfileComposer::detect_format("", &summary), ProfileFormat::Pipeline);
+        
+        // Section layout wins over the version
+        assert_eq!(ProfileComposer::detect_format("MergedProfile:\n", &summary), ProfileFormat::Pipeline);
+        summary.doris_version = "2c31ab4".to_string();
+        assert_eq!(ProfileComposer::detect_format("Execution Profile q:(Active: 1s, % non-child: 0.00%)", &summary), ProfileFormat::NonPipeline);
+        assert_eq!(ProfileComposer::detect_format("", &summary), ProfileFormat::Pipeline);
+    }
 }
@@ -0,0 +1,322 @@
+//! DetailProfile parser for Doris profile
+//! Parses the per-backend Fragment -> Pipeline(host) -> PipelineTask -> Operator structure
+
+use crate::models::*;
+use crate::parser::engine::operator_parser::ParsedOperator;
+use crate::parser::engine::{OperatorParser, ValueParser};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Regex for Fragment header: "Fragment 0:"
+static FRAGMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*Fragment\s+(\d+):").unwrap()
+});
+
+/// Regex for fragment level profile header:
+/// "FragmentLevelProfile:(host=TNetworkAddress(hostname:172.20.56.83, port:9050)):"
+static FRAGMENT_LEVEL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*FragmentLevelProfile:\(host=TNetworkAddress\(hostname:([^,]+),\s*port:(\d+)\)\)").unwrap()
+});
+
+/// Regex for per-host Pipeline header:
+/// "Pipeline 0(host=TNetworkAddress(hostname:172.20.56.83, port:9050)):"
+static HOST_PIPELINE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*Pipeline\s+(\d+)\(host=TNetworkAddress\(hostname:([^,]+),\s*port:(\d+)\)\)").unwrap()
+});
+
+/// Regex for PipelineTask header: "PipelineTask(index=0):"
+static PIPELINE_TASK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*PipelineTask\(index=(\d+)\)").unwrap()
+});
+
+/// Regex for metric lines: "- MetricName: value"
+static METRIC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*-\s+([^:]+):\s*(.*)$").unwrap()
+});
+
+pub struct DetailProfileParser;
+
+impl DetailProfileParser {
+    /// Parse the DetailProfile section text into per-backend fragments
+    pub fn parse(text: &str) -> DetailProfile {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut fragments: Vec<DetailFragment> = Vec::new();
+        
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            let trimmed = line.trim();
+            
+            if let Some(caps) = FRAGMENT_REGEX.captures(trimmed) {
+                fragments.push(DetailFragment {
+                    id: format!("Fragment {}", &caps[1]),
+                    backends: Vec::new(),
+                });
+                i += 1;
+                continue;
+            }
+            
+            let Some(fragment) = fragments.last_mut() else {
+                i += 1;
+                continue;
+            };
+            
+            if let Some(caps) = FRAGMENT_LEVEL_REGEX.captures(trimmed) {
+                let host = Self::format_host(&caps[1], &caps[2]);
+                let end = Self::find_block_end(&lines, i);
+                let counters = Self::parse_metric_items(&lines[i + 1..end]);
+                Self::backend_entry(fragment, &host).fragment_counters.extend(counters);
+                i = end;
+            } else if let Some(caps) = HOST_PIPELINE_REGEX.captures(trimmed) {
+                let host = Self::format_host(&caps[2], &caps[3]);
+                let end = Self::find_block_end(&lines, i);
+                let pipeline = BackendPipeline {
+                    id: format!("Pipeline {}", &caps[1]),
+                    tasks: Self::parse_tasks(&lines[i + 1..end]),
+                };
+                Self::backend_entry(fragment, &host).pipelines.push(pipeline);
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        
+        DetailProfile { fragments }
+    }
+    
+    /// Get (or create) the backend entry for a host within a fragment
+    fn backend_entry<'a>(fragment: &'a mut DetailFragment, host: &str) -> &'a mut BackendFragment {
+        let pos = match fragment.backends.iter().position(|b| b.host == host) {
+            Some(pos) => pos,
+            None => {
+                fragment.backends.push(BackendFragment {
+                    host: host.to_string(),
+                    fragment_counters: Vec::new(),
+                    pipelines: Vec::new(),
+                });
+                fragment.backends.len() - 1
+            }
+        };
+        &mut fragment.backends[pos]
+    }
+    
+    /// Parse all PipelineTask blocks of a per-host pipeline
+    fn parse_tasks(lines: &[&str]) -> Vec<PipelineTask> {
+        let mut tasks = Vec::new();
+        
+        let mut i = 0;
+        while i < lines.len() {
+            if let Some(caps) = PIPELINE_TASK_REGEX.captures(lines[i].trim()) {
+                let index: u32 = caps[1].parse().unwrap_or(0);
+                let end = Self::find_block_end(lines, i);
+                tasks.push(Self::parse_task(index, &lines[i + 1..end]));
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        
+        tasks
+    }
+    
+    /// Parse a single PipelineTask: task counters followed by its operators
+    fn parse_task(index: u32, lines: &[&str]) -> PipelineTask {
+        // Task-level counters come before the first operator header
+        let first_operator = lines.iter()
+            .position(|l| OperatorParser::is_operator_header(l.trim()))
+            .unwrap_or(lines.len());
+        
+        let task_counters = Self::parse_metric_items(&lines[..first_operator]);
+        let operators = OperatorParser::extract_parsed_operators(&lines[first_operator..].join("\n"))
+            .iter()
+            .map(Self::to_task_operator)
+            .collect();
+        
+        let find_time = |key: &str| {
+            OperatorParser::find_counter(&task_counters, key)
+                .and_then(|c| c.value)
+                .map(|t| t as u64)
+        };
+        
+        PipelineTask {
+            index,
+            execute_time: find_time("ExecuteTime"),
+            task_cpu_time: find_time("TaskCpuTime"),
+            wait_worker_time: find_time("WaitWorkerTime"),
+            task_counters,
+            operators,
+        }
+    }
+    
+    /// Convert a parsed operator into a TaskOperator with single-instance values
+    fn to_task_operator(parsed: &ParsedOperator) -> TaskOperator {
+        // Detail counters are per instance, so each holds a single value
+        let find_value = |key: &str| {
+            OperatorParser::find_counter(&parsed.common_counters, key)
+                .and_then(|c| c.value)
+                .map(|v| v.max(0.0) as u64)
+        };
+        
+        TaskOperator {
+            operator_name: parsed.name.clone(),
+            plan_node_id: parsed.id,
+            nereids_id: parsed.nereids_id,
+            dest_id: parsed.dest_id,
+            exec_time: find_value("ExecTime"),
+            rows_produced: find_value("RowsProduced"),
+            input_rows: find_value("InputRows"),
+            memory_peak: find_value("MemoryUsagePeak"),
+            common_counters: parsed.common_counters.clone(),
+            custom_counters: parsed.custom_counters.clone(),
+        }
+    }
+    
+    /// Parse "- Key: Value" lines into metric items, nesting by indentation
+    fn parse_metric_items(lines: &[&str]) -> Vec<MetricItem> {
+        let mut items = Vec::new();
+        
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            if let Some(caps) = METRIC_REGEX.captures(line) {
+                let end = Self::find_block_end(lines, i);
+                let value = caps[2].trim();
+                items.push(MetricItem {
+                    key: caps[1].trim().to_string(),
+                    value: value.to_string(),
+                    counter: ValueParser::parse_counter(value),
+                    children: Self::parse_metric_items(&lines[i + 1..end]),
+                });
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        
+        items
+    }
+    
+    /// Find the end of the block starting at `start`: the next non-empty line
+    /// that is not indented deeper than the block header
+    fn find_block_end(lines: &[&str], start: usize) -> usize {
+        let base_indent = Self::get_indent(lines[start]);
+        
+        lines.iter()
+            .enumerate()
+            .skip(start + 1)
+            .find(|(_, l)| !l.trim().is_empty() && Self::get_indent(l) <= base_indent)
+            .map(|(j, _)| j)
+            .unwrap_or(lines.len())
+    }
+    
+    /// Format a backend address as "host:port"
+    fn format_host(hostname: &str, port: &str) -> String {
+        format!("{}:{}", hostname.trim(), port)
+    }
+    
+    /// Get the indentation level of a line (number of leading spaces)
+    fn get_indent(line: &str) -> usize {
+        line.len() - line.trim_start().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const DETAIL_TEXT: &str = r#"(37f4f7ab99a741ed-8fd24882055ce279):
+  Fragments:
+    Fragment 0:
+      FragmentLevelProfile:(host=TNetworkAddress(hostname:172.20.56.83, port:9050)):
+         - BuildPipelinesTime: 51.87us
+         - PrepareTime: 305.199us
+      Pipeline 0(host=TNetworkAddress(hostname:172.20.56.83, port:9050)):
+        PipelineTask(index=0):
+           - TaskState: FINALIZED
+           - BlockedByDependency:
+           - ExecuteTime: 136.329us
+             - CloseTime: 5.139us
+             - SinkTime: 71.152us
+           - TaskCpuTime: 183.463us
+           - WaitWorkerTime: 18.605us
+          RESULT_SINK_OPERATOR(id=2147483647):
+            CommonCounters:
+               - ExecTime: 95.241us
+               - InputRows: 1
+            CustomCounters:
+               - BytesSent: 11.00 B
+    Fragment 1:
+      FragmentLevelProfile:(host=TNetworkAddress(hostname:172.20.56.85, port:9050)):
+         - PrepareTime: 5.767ms
+      Pipeline 0(host=TNetworkAddress(hostname:172.20.56.85, port:9050)):
+        PipelineTask(index=0):
+           - ExecuteTime: 132.96us
+          AGGREGATION_OPERATOR(nereids_id=1862)(id=24):
+            CommonCounters:
+               - ExecTime: 11.667us
+               - MemoryUsagePeak: 4.00 KB
+               - RowsProduced: 1
+        PipelineTask(index=8):
+           - ExecuteTime: 1sec240ms
+          AGGREGATION_OPERATOR(nereids_id=1862)(id=24):
+            CommonCounters:
+               - ExecTime: 807.567ms
+               - RowsProduced: 4.278K (4278)
+      Pipeline 0(host=TNetworkAddress(hostname:172.20.56.84, port:9050)):
+        PipelineTask(index=0):
+           - ExecuteTime: 98.1us
+          AGGREGATION_OPERATOR(nereids_id=1862)(id=24):
+            CommonCounters:
+               - ExecTime: 9.1us
+  LoadChannels:
+"#;
+    
+    #[test]
+    fn test_parse_fragments_and_backends() {
+        let detail = DetailProfileParser::parse(DETAIL_TEXT);
+        assert_eq!(detail.fragments.len(), 2);
+        assert_eq!(detail.fragments[0].id, "Fragment 0");
+        assert_eq!(detail.fragments[0].backends.len(), 1);
+        assert_eq!(detail.fragments[0].backends[0].host, "172.20.56.83:9050");
+        assert_eq!(detail.fragments[0].backends[0].fragment_counters.len(), 2);
+        
+        let hosts: Vec<&str> = detail.fragments[1].backends.iter().map(|b| b.host.as_str()).collect();
+        assert_eq!(hosts, vec!["172.20.56.85:9050", "172.20.56.84:9050"]);
+    }
+    
+    #[test]
+    fn test_parse_pipeline_tasks() {
+        let detail = DetailProfileParser::parse(DETAIL_TEXT);
+        let task = &detail.fragments[0].backends[0].pipelines[0].tasks[0];
+        assert_eq!(task.index, 0);
+        assert_eq!(task.execute_time, Some(136329));
+        assert_eq!(task.task_cpu_time, Some(183463));
+        assert_eq!(task.wait_worker_time, Some(18605));
+        
+        let execute_time = task.task_counters.iter().find(|m| m.key == "ExecuteTime").unwrap();
+        assert_eq!(execute_time.children.len(), 2);
+        
+        assert_eq!(task.operators.len(), 1);
+        assert_eq!(task.operators[0].operator_name, "RESULT_SINK_OPERATOR");
+        assert_eq!(task.operators[0].input_rows, Some(1));
+        assert_eq!(task.operators[0].custom_counters.len(), 1);
+    }
+    
+    #[test]
+    fn test_parse_task_operators() {
+        let detail = DetailProfileParser::parse(DETAIL_TEXT);
+        let tasks = &detail.fragments[1].backends[0].pipelines[0].tasks;
+        assert_eq!(tasks.len(), 2);
+        
+        let slow = &tasks[1];
+        assert_eq!(slow.index, 8);
+        assert_eq!(slow.execute_time, Some(1_240_000_000));
+        
+        let agg = &slow.operators[0];
+        assert_eq!(agg.plan_node_id, 24);
+        assert_eq!(agg.nereids_id, Some(1862));
+        assert_eq!(agg.exec_time, Some(807_567_000));
+        assert_eq!(agg.rows_produced, Some(4278));
+        assert_eq!(tasks[0].operators[0].memory_peak, Some(4096));
+    }
+}
@@ -3,7 +3,7 @@
 
 use crate::models::{Fragment, Pipeline};
 use crate::parser::error::ParseResult;
-use crate::parser::engine::OperatorParser;
+use crate::parser::engine::{OperatorParser, ValueParser};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
@@ -45,9 +45,7 @@ impl FragmentParser {
                 
                 // Find end of this fragment
                 let mut end_idx = lines.len();
-                for j in (i + 1)..lines.len() {
-                    let next_line = lines[j];
-                    
+                for (j, next_line) in lines.iter().enumerate().skip(i + 1) {
                     // Skip empty lines
                     if next_line.trim().is_empty() {
                         continue;
@@ -106,8 +104,7 @@ impl FragmentParser {
                 
                 // Find end of this pipeline
                 let mut end_idx = lines.len();
-                for j in (i + 1)..lines.len() {
-                    let next_line = lines[j];
+                for (j, next_line) in lines.iter().enumerate().skip(i + 1) {
                     let next_indent = Self::get_indent(next_line);
                     
                     // Pipeline ends when we hit another Pipeline or Fragment at same or less indent
@@ -135,7 +132,7 @@ impl FragmentParser {
     /// Parse a single pipeline
     fn parse_single_pipeline(text: &str, id: &str, instance_num: &str) -> ParseResult<Pipeline> {
         let mut metrics = HashMap::new();
-        metrics.insert("instance_num".to_string(), instance_num.to_string());
+        metrics.insert("instance_num".to_string(), ValueParser::parse_counter(instance_num));
         
         // Extract pipeline-level metrics (lines starting with "- " before operators)
         for line in text.lines() {
@@ -150,7 +147,7 @@ impl FragmentParser {
                 let key = caps.get(1).map(|m| m.as_str().trim()).unwrap_or("");
                 let value = caps.get(2).map(|m| m.as_str().trim()).unwrap_or("");
                 if !key.is_empty() {
-                    metrics.insert(key.to_string(), value.to_string());
+                    metrics.insert(key.to_string(), ValueParser::parse_counter(value));
                 }
             }
         }
@@ -3,10 +3,20 @@ pub mod section_parser;
 pub mod fragment_parser;
 pub mod operator_parser;
 pub mod tree_builder;
+pub mod detail_parser;
+pub mod plan_parser;
+pub mod runtime_filter_parser;
+pub mod non_pipeline_parser;
+pub mod profile_validator;
 
 pub use value_parser::ValueParser;
 pub use section_parser::SectionParser;
 pub use fragment_parser::FragmentParser;
 pub use operator_parser::OperatorParser;
 pub use tree_builder::TreeBuilder;
+pub use detail_parser::DetailProfileParser;
+pub use plan_parser::PhysicalPlanParser;
+pub use runtime_filter_parser::RuntimeFilterParser;
+pub use non_pipeline_parser::NonPipelineParser;
+pub use profile_validator::ProfileValidator;
 
@@ -0,0 +1,535 @@
+//! Parser for non-pipeline Doris profiles (1.2 / 2.0)
+//! Parses the "Execution Profile" section: Fragment -> Instance -> nested V*_NODE operators,
+//! merging the instances of each operator into aggregated counters like a MergedProfile
+
+use crate::models::*;
+use crate::parser::engine::operator_parser::ParsedOperator;
+use crate::parser::engine::tree_builder::NestedOperator;
+use crate::parser::engine::ValueParser;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Regex for Fragment header: "Fragment 0:"
+static FRAGMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Fragment\s+(\d+):").unwrap()
+});
+
+/// Regex for Instance header:
+/// "Instance 9f3c2a1b7d4e4f10-8a6b5c4d3e2f1a0a (host=TNetworkAddress(hostname:10.0.0.11, port:9060)):(Active: ...)"
+static INSTANCE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Instance\s+(\S+)\s*\(host=TNetworkAddress\(hostname:([^,]+),\s*port:(\d+)\)\)").unwrap()
+});
+
+/// Regex for exec node headers: "VHASH_JOIN_NODE (id=1):", "VNewOlapScanNode(lineitem) (id=0):"
+static NODE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([A-Za-z_]+)(?:\(([^)]*)\))?\s*\(id=(-?\d+)\)").unwrap()
+});
+
+/// Regex for data sink headers: "VDataStreamSender (dst_id=3, dst_fragments=[...]):", "VResultSink:"
+static SINK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(V?[A-Za-z]*(?:Sink|Sender))\b(?:\s*\((?:[^)]*?dst_id=(\d+))?)?").unwrap()
+});
+
+/// Regex for the timing suffix of a profile header: "(Active: 1s820ms, % non-child: 62.40%)"
+static ACTIVE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\(Active:\s*([^,]+),\s*%\s*non-child:\s*([\d.]+)%\)").unwrap()
+});
+
+/// Regex for metric lines: "- MetricName: value"
+static METRIC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*-\s+([^:]+):\s*(.*)$").unwrap()
+});
+
+/// Counters renamed to their pipeline equivalents and shown as CommonCounters
+const COMMON_COUNTERS: &[(&str, &str)] = &[
+    ("RowsReturned", "RowsProduced"),
+    ("PeakMemoryUsage", "MemoryUsagePeak"),
+];
+
+/// Operators and fragments of a non-pipeline profile
+#[derive(Debug, Clone, Default)]
+pub struct NonPipelineProfile {
+    pub fragments: Vec<Fragment>,
+    pub operators: Vec<NestedOperator>,
+}
+
+/// A header line with its counters and nested profiles
+#[derive(Debug)]
+struct ProfileBlock<'a> {
+    header: &'a str,
+    counters: Vec<MetricItem>,
+    children: Vec<ProfileBlock<'a>>,
+}
+
+/// Operator header fields
+#[derive(Debug, Clone, PartialEq)]
+struct NodeHeader {
+    name: String,
+    legacy_name: String,
+    id: i32,
+    dest_id: Option<i32>,
+    table_name: Option<String>,
+}
+
+/// Counters of one instance of an operator
+#[derive(Debug, Default)]
+struct InstanceCounters {
+    common: Vec<MetricItem>,
+    custom: Vec<MetricItem>,
+}
+
+/// An operator merged over all instances of its fragment
+#[derive(Debug)]
+struct MergedNode {
+    header: NodeHeader,
+    fragment_id: String,
+    instances: Vec<InstanceCounters>,
+    children: Vec<usize>,
+}
+
+pub struct NonPipelineParser;
+
+impl NonPipelineParser {
+    /// Parse the Execution Profile section text (after the "Execution Profile " marker)
+    pub fn parse(text: &str) -> NonPipelineProfile {
+        let lines: Vec<&str> = text.lines().skip(1).collect();
+        let mut fragments = Vec::new();
+        let mut nodes: Vec<MergedNode> = Vec::new();
+        let mut node_index: HashMap<(String, String), usize> = HashMap::new();
+        
+        for block in Self::parse_blocks(&lines) {
+            let Some(caps) = FRAGMENT_REGEX.captures(block.header) else {
+                continue;
+            };
+            let mut fragment = Fragment {
+                id: format!("Fragment {}", &caps[1]),
+                backend_addresses: Vec::new(),
+                instance_ids: Vec::new(),
+                pipelines: Vec::new(),
+            };
+            
+            for instance in &block.children {
+                let Some(caps) = INSTANCE_REGEX.captures(instance.header) else {
+                    continue;
+                };
+                fragment.instance_ids.push(caps[1].to_string());
+                let host = format!("{}:{}", caps[2].trim(), &caps[3]);
+                if !fragment.backend_addresses.contains(&host) {
+                    fragment.backend_addresses.push(host);
+                }
+                
+                // The data sink consumes the output of the plan root next to it
+                let mut sink = None;
+                for child in &instance.children {
+                    if Self::parse_node_header(child.header).is_some() {
+                        let idx = Self::visit(child, &fragment.id, &mut nodes, &mut node_index);
+                        if let Some(idx) = idx.filter(|_| sink.is_none()) {
+                            if nodes[idx].header.name.contains("SINK") {
+                                sink = Some(idx);
+                            }
+                        }
+                    }
+                }
+                for child in &instance.children {
+                    let Some(header) = Self::parse_node_header(child.header) else {
+                        continue;
+                    };
+                    if header.name.contains("SINK") {
+                        continue;
+                    }
+                    let key = (fragment.id.clone(), Self::node_key(&header));
+                    if let (Some(sink), Some(&root)) = (sink, node_index.get(&key)) {
+                        if !nodes[sink].children.contains(&root) {
+                            nodes[sink].children.push(root);
+                        }
+                    }
+                }
+            }
+            
+            fragments.push(fragment);
+        }
+        
+        let operators = nodes.into_iter()
+            .map(|node| NestedOperator {
+                parsed: Self::to_parsed_operator(&node),
+                fragment_id: node.fragment_id,
+                children: node.children,
+            })
+            .collect();
+        
+        NonPipelineProfile { fragments, operators }
+    }
+    
+    /// Record one instance of an operator block and its nested operators; returns its index
+    fn visit(
+        block: &ProfileBlock,
+        fragment_id: &str,
+        nodes: &mut Vec<MergedNode>,
+        node_index: &mut HashMap<(String, String), usize>,
+    ) -> Option<usize> {
+        let header = Self::parse_node_header(block.header)?;
+        let key = (fragment_id.to_string(), Self::node_key(&header));
+        let idx = *node_index.entry(key).or_insert_with(|| {
+            nodes.push(MergedNode {
+                header: header.clone(),
+                fragment_id: fragment_id.to_string(),
+                instances: Vec::new(),
+                children: Vec::new(),
+            });
+            nodes.len() - 1
+        });
+        
+        let mut counters = InstanceCounters::default();
+        if let Some(exec_time) = Self::self_time_ns(block.header) {
+            counters.common.push(Self::metric("ExecTime", CounterKind::Time, exec_time));
+        }
+        for item in &block.counters {
+            match COMMON_COUNTERS.iter().find(|(legacy, _)| *legacy == item.key) {
+                Some((_, name)) => counters.common.push(MetricItem {
+                    key: name.to_string(),
+                    ..item.clone()
+                }),
+                None => counters.custom.push(item.clone()),
+            }
+        }
+        
+        for child in &block.children {
+            match Self::visit(child, fragment_id, nodes, node_index) {
+                Some(child_idx) => {
+                    if !nodes[idx].children.contains(&child_idx) {
+                        nodes[idx].children.push(child_idx);
+                    }
+                }
+                // Nested profiles that are not operators (scanners, ...) become counter groups
+                None => counters.custom.push(Self::to_counter_group(child)),
+            }
+        }
+        
+        nodes[idx].instances.push(counters);
+        Some(idx)
+    }
+    
+    /// Operators are merged by plan node id, sinks (one per fragment) by name
+    fn node_key(header: &NodeHeader) -> String {
+        format!("{}#{}", header.name, header.id)
+    }
+    
+    /// Convert a merged operator into the parsed form shared with the pipeline path
+    fn to_parsed_operator(node: &MergedNode) -> ParsedOperator {
+        let common: Vec<&[MetricItem]> = node.instances.iter().map(|i| i.common.as_slice()).collect();
+        let custom: Vec<&[MetricItem]> = node.instances.iter().map(|i| i.custom.as_slice()).collect();
+        
+        ParsedOperator {
+            name: node.header.name.clone(),
+            id: node.header.id,
+            nereids_id: None,
+            dest_id: node.header.dest_id,
+            dest_ids: Vec::new(),
+            source_id: None,
+            exchange_type: None,
+            plan_info: vec![MetricItem {
+                key: "legacy_name".to_string(),
+                value: node.header.legacy_name.clone(),
+                counter: CounterValue::text(node.header.legacy_name.clone()),
+                children: Vec::new(),
+            }],
+            common_counters: Self::merge_instances(&common),
+            custom_counters: Self::merge_instances(&custom),
+            table_name: node.header.table_name.clone(),
+        }
+    }
+    
+    /// Merge the counters of all instances by key: numeric counters become
+    /// "sum, avg, max, min" aggregates (no sum for times), others keep the first value
+    fn merge_instances(instances: &[&[MetricItem]]) -> Vec<MetricItem> {
+        let mut keys: Vec<&str> = Vec::new();
+        for item in instances.iter().flat_map(|items| items.iter()) {
+            if !keys.contains(&item.key.as_str()) {
+                keys.push(&item.key);
+            }
+        }
+        
+        keys.into_iter()
+            .map(|key| {
+                let items: Vec<&MetricItem> = instances.iter()
+                    .filter_map(|items| items.iter().find(|item| item.key == key))
+                    .collect();
+                let children: Vec<&[MetricItem]> = items.iter().map(|item| item.children.as_slice()).collect();
+                let children = Self::merge_instances(&children);
+                
+                let kind = items[0].counter.kind;
+                let values: Option<Vec<f64>> = items.iter()
+                    .map(|item| item.counter.value.filter(|_| item.counter.kind == kind))
+                    .collect();
+                
+                match values.filter(|_| items[0].counter.is_numeric()) {
+                    Some(values) => Self::aggregate(key, kind, &values, children),
+                    None => MetricItem {
+                        children,
+                        ..items[0].clone()
+                    },
+                }
+            })
+            .collect()
+    }
+    
+    /// Build an aggregated counter from per-instance values
+    fn aggregate(key: &str, kind: CounterKind, values: &[f64], children: Vec<MetricItem>) -> MetricItem {
+        let sum: f64 = values.iter().sum();
+        let counter = CounterValue {
+            kind,
+            sum: (kind != CounterKind::Time).then_some(sum),
+            avg: Some(sum / values.len() as f64),
+            max: values.iter().copied().reduce(f64::max),
+            min: values.iter().copied().reduce(f64::min),
+            ..Default::default()
+        };
+        
+        let parts = [("sum", counter.sum), ("avg", counter.avg), ("max", counter.max), ("min", counter.min)];
+        let value = parts.iter()
+            .filter_map(|(name, v)| v.map(|v| format!("{} {}", name, ValueParser::format_counter(kind, v))))
+            .collect::<Vec<_>>()
+            .join(", ");
+        
+        MetricItem {
+            key: key.to_string(),
+            value,
+            counter,
+            children,
+        }
+    }
+    
+    /// A single-valued counter
+    fn metric(key: &str, kind: CounterKind, value: f64) -> MetricItem {
+        MetricItem {
+            key: key.to_string(),
+            value: ValueParser::format_counter(kind, value),
+            counter: CounterValue {
+                kind,
+                value: Some(value),
+                ..Default::default()
+            },
+            children: Vec::new(),
+        }
+    }
+    
+    /// Turn a nested non-operator profile into a counter with its counters as children
+    fn to_counter_group(block: &ProfileBlock) -> MetricItem {
+        let name = block.header.split(":(Active").next().unwrap_or(block.header);
+        let mut children = block.counters.clone();
+        children.extend(block.children.iter().map(Self::to_counter_group));
+        
+        MetricItem {
+            key: name.trim().trim_end_matches(':').to_string(),
+            value: String::new(),
+            counter: CounterValue::default(),
+            children,
+        }
+    }
+    
+    /// Time spent in the operator itself: Active time times the non-child share
+    fn self_time_ns(header: &str) -> Option<f64> {
+        let caps = ACTIVE_REGEX.captures(header)?;
+        let active = ValueParser::parse_counter(&caps[1]);
+        if active.kind != CounterKind::Time {
+            return None;
+        }
+        let non_child: f64 = caps[2].parse().ok()?;
+        active.value.map(|ns| (ns * non_child / 100.0).round())
+    }
+    
+    /// Parse an exec node or data sink header
+    fn parse_node_header(header: &str) -> Option<NodeHeader> {
+        if let Some(caps) = NODE_REGEX.captures(header) {
+            let legacy_name = caps[1].to_string();
+            return Some(NodeHeader {
+                name: Self::normalize_name(&legacy_name),
+                legacy_name,
+                id: caps[3].parse().ok()?,
+                dest_id: None,
+                table_name: caps.get(2).map(|m| m.as_str().trim().to_string()).filter(|t| !t.is_empty()),
+            });
+        }
+        
+        let caps = SINK_REGEX.captures(header)?;
+        let legacy_name = caps[1].to_string();
+        Some(NodeHeader {
+            name: Self::normalize_name(&legacy_name),
+            legacy_name,
+            id: -1,
+            dest_id: caps.get(2).and_then(|m| m.as_str().parse().ok()),
+            table_name: None,
+        })
+    }
+    
+    /// Map a non-pipeline node name to the pipeline operator name, so that diagnostics
+    /// and suggestions apply: "VOLAP_SCAN_NODE" / "VNewOlapScanNode" -> "OLAP_SCAN_OPERATOR"
+    fn normalize_name(name: &str) -> String {
+        let name = match name.strip_prefix('V') {
+            Some(rest) if rest.starts_with(|c: char| c.is_ascii_uppercase()) => rest,
+            _ => name,
+        };
+        
+        // CamelCase -> UPPER_SNAKE
+        let mut snake = String::new();
+        let mut prev_lower = false;
+        for c in name.chars() {
+            if c.is_ascii_uppercase() && prev_lower {
+                snake.push('_');
+            }
+            prev_lower = c.is_ascii_lowercase();
+            snake.push(c.to_ascii_uppercase());
+        }
+        let snake = snake.strip_prefix("NEW_").unwrap_or(&snake);
+        
+        match snake {
+            "DATA_STREAM_SENDER" => "DATA_STREAM_SINK_OPERATOR".to_string(),
+            "RESULT_SINK" | "MYSQL_RESULT_SINK" => "RESULT_SINK_OPERATOR".to_string(),
+            _ => {
+                let base = snake.strip_suffix("_NODE").unwrap_or(snake);
+                format!("{}_OPERATOR", base)
+            }
+        }
+    }
+    
+    /// Parse indented lines into blocks of header, counters and nested blocks
+    fn parse_blocks<'a>(lines: &[&'a str]) -> Vec<ProfileBlock<'a>> {
+        let mut blocks = Vec::new();
+        
+        let mut i = 0;
+        while i < lines.len() {
+            let trimmed = lines[i].trim();
+            let end = Self::find_block_end(lines, i);
+            if !trimmed.is_empty() && !trimmed.starts_with('-') {
+                let (counters, children) = Self::parse_body(&lines[i + 1..end]);
+                blocks.push(ProfileBlock {
+                    header: trimmed,
+                    counters,
+                    children,
+                });
+            }
+            i = end;
+        }
+        
+        blocks
+    }
+    
+    /// Split a block body into its counters (nested by indentation) and nested blocks
+    fn parse_body<'a>(lines: &[&'a str]) -> (Vec<MetricItem>, Vec<ProfileBlock<'a>>) {
+        let mut counters = Vec::new();
+        let mut header_lines = Vec::new();
+        
+        let mut i = 0;
+        while i < lines.len() {
+            let end = Self::find_block_end(lines, i);
+            if let Some(caps) = METRIC_REGEX.captures(lines[i]) {
+                let value = caps[2].trim();
+                let (children, _) = Self::parse_body(&lines[i + 1..end]);
+                counters.push(MetricItem {
+                    key: caps[1].trim().to_string(),
+                    value: value.to_string(),
+                    counter: ValueParser::parse_counter(value),
+                    children,
+                });
+            } else {
+                header_lines.extend_from_slice(&lines[i..end]);
+            }
+            i = end;
+        }
+        
+        (counters, Self::parse_blocks(&header_lines))
+    }
+    
+    /// Find the end of the block starting at `start`: the next non-empty line
+    /// that is not indented deeper than the block header
+    fn find_block_end(lines: &[&str], start: usize) -> usize {
+        let base_indent = Self::get_indent(lines[start]);
+        
+        lines.iter()
+            .enumerate()
+            .skip(start + 1)
+            .find(|(_, l)| !l.trim().is_empty() && Self::get_indent(l) <= base_indent)
+            .map(|(j, _)| j)
+            .unwrap_or(lines.len())
+    }
+    
+    /// Get the indentation level of a line (number of leading spaces)
+    fn get_indent(line: &str) -> usize {
+        line.len() - line.trim_start().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const EXECUTION_PROFILE: &str = r#"Execution Profile 1f2e3d4c5b6a7980-a1b2c3d4e5f60718:(Active: 1s500ms, % non-child: 0.00%)
+  Fragment 0:
+    Instance 1f2e3d4c5b6a7980-a1b2c3d4e5f60719 (host=TNetworkAddress(hostname:10.0.0.11, port:9060)):(Active: 1s490ms, % non-child: 0.00%)
+       - PeakMemoryUsage: 64.00 KB
+      PlanFragmentExecutor:
+         - OpenTime: 1s488ms
+      VResultSink:(Active: 20.000us, % non-child: 100.00%)
+         - NumSentRows: 2
+      VEXCHANGE_NODE (id=1):(Active: 1s480ms, % non-child: 10.00%)
+         - RowsReturned: 2
+  Fragment 1:
+    Instance 1f2e3d4c5b6a7980-a1b2c3d4e5f6071a (host=TNetworkAddress(hostname:10.0.0.11, port:9060)):(Active: 1s400ms, % non-child: 0.00%)
+      VDataStreamSender (dst_id=1, dst_fragments=[1f2e3d4c5b6a7980-a1b2c3d4e5f60719]):(Active: 10.000us, % non-child: 100.00%)
+         - BytesSent: 1.00 KB
+      VNewOlapScanNode(lineitem) (id=0):(Active: 1s, % non-child: 50.00%)
+         - RowsReturned: 1.5K (1500)
+         - PeakMemoryUsage: 2.00 MB
+        VScanner:
+           - ScannerGetBlockTime: 400.000ms
+    Instance 1f2e3d4c5b6a7980-a1b2c3d4e5f6071b (host=TNetworkAddress(hostname:10.0.0.12, port:9060)):(Active: 1s400ms, % non-child: 0.00%)
+      VDataStreamSender (dst_id=1, dst_fragments=[1f2e3d4c5b6a7980-a1b2c3d4e5f60719]):(Active: 30.000us, % non-child: 100.00%)
+         - BytesSent: 3.00 KB
+      VNewOlapScanNode(lineitem) (id=0):(Active: 200ms, % non-child: 50.00%)
+         - RowsReturned: 500
+         - PeakMemoryUsage: 1.00 MB
+        VScanner:
+           - ScannerGetBlockTime: 80.000ms
+"#;
+    
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(NonPipelineParser::normalize_name("VOLAP_SCAN_NODE"), "OLAP_SCAN_OPERATOR");
+        assert_eq!(NonPipelineParser::normalize_name("VNewOlapScanNode"), "OLAP_SCAN_OPERATOR");
+        assert_eq!(NonPipelineParser::normalize_name("VHASH_JOIN_NODE"), "HASH_JOIN_OPERATOR");
+        assert_eq!(NonPipelineParser::normalize_name("VAGGREGATION_NODE"), "AGGREGATION_OPERATOR");
+        assert_eq!(NonPipelineParser::normalize_name("VDataStreamSender"), "DATA_STREAM_SINK_OPERATOR");
+        assert_eq!(NonPipelineParser::normalize_name("VMysqlResultSink"), "RESULT_SINK_OPERATOR");
+        assert_eq!(NonPipelineParser::normalize_name("EXCHANGE_NODE"), "EXCHANGE_OPERATOR");
+    }
+    
+    #[test]
+    fn test_parse_execution_profile() {
+        let profile = NonPipelineParser::parse(EXECUTION_PROFILE);
+        
+        assert_eq!(profile.fragments.len(), 2);
+        assert_eq!(profile.fragments[1].instance_ids.len(), 2);
+        assert_eq!(profile.fragments[1].backend_addresses, vec!["10.0.0.11:9060", "10.0.0.12:9060"]);
+        
+        let names: Vec<&str> = profile.operators.iter().map(|op| op.parsed.name.as_str()).collect();
+        assert_eq!(names, vec!["RESULT_SINK_OPERATOR", "EXCHANGE_OPERATOR", "DATA_STREAM_SINK_OPERATOR", "OLAP_SCAN_OPERATOR"]);
+        assert_eq!(profile.operators[0].children, vec![1], "result sink consumes the plan root");
+        assert_eq!(profile.operators[2].children, vec![3]);
+        assert_eq!(profile.operators[2].parsed.dest_id, Some(1));
+        
+        // Both instances of the scan are merged
+        let scan = &profile.operators[3].parsed;
+        assert_eq!(scan.table_name.as_deref(), Some("lineitem"));
+        let exec_time = &scan.common_counters.iter().find(|c| c.key == "ExecTime").unwrap().counter;
+        assert_eq!(exec_time.avg, Some(300_000_000.0));
+        assert_eq!(exec_time.max, Some(500_000_000.0));
+        assert_eq!(exec_time.sum, None);
+        let rows = scan.common_counters.iter().find(|c| c.key == "RowsProduced").unwrap();
+        assert_eq!(rows.counter.sum, Some(2000.0));
+        assert_eq!(rows.value, "sum 2.000K (2000), avg 1.000K (1000), max 1.500K (1500), min 500");
+        
+        let scanner = scan.custom_counters.iter().find(|c| c.key == "VScanner").unwrap();
+        assert_eq!(scanner.children[0].counter.max, Some(400_000_000.0));
+    }
+}
@@ -1,7 +1,7 @@
 //! Operator parser for Doris profile
 //! Parses individual operators with their PlanInfo, CommonCounters, and CustomCounters
 
-use crate::models::Operator;
+use crate::models::{CounterValue, JoinDistribution, JoinType, Operator, OperatorAttributes};
 use crate::parser::engine::ValueParser;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -46,6 +46,11 @@ static MULTI_CAST_SOURCE_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"MULTI_CAST_DATA_STREAM_SOURCE_OPERATOR\(id=(-?\d+)\)").unwrap()
 });
 
+/// Regex for the join op of a join's PlanInfo: "INNER JOIN(BROADCAST)[]", "CROSS JOIN()"
+static JOIN_OP_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*([A-Z ]+?)\s*JOIN\s*\(([A-Z_]*)\)").unwrap()
+});
+
 /// Regex for metric lines: "- MetricName: value"
 static METRIC_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"^\s*-\s+([^:]+):\s*(.*)$").unwrap()
@@ -69,6 +74,10 @@ pub struct ParsedOperator {
     pub table_name: Option<String>,
 }
 
+/// Operator header fields:
+/// (name, id, nereids_id, dest_id, dest_ids, source_id, exchange_type, table_name)
+type OperatorHeader = (String, i32, Option<i32>, Option<i32>, Vec<i32>, Option<i32>, Option<String>, Option<String>);
+
 pub struct OperatorParser;
 
 impl OperatorParser {
@@ -119,8 +128,7 @@ impl OperatorParser {
                 
                 // Find end of this operator
                 let mut end_idx = lines.len();
-                for j in (i + 1)..lines.len() {
-                    let next_line = lines[j];
+                for (j, next_line) in lines.iter().enumerate().skip(i + 1) {
                     let next_indent = Self::get_indent(next_line);
                     let next_trimmed = next_line.trim();
                     
@@ -227,6 +235,7 @@ impl OperatorParser {
                             children.push(MetricItem {
                                 key: child_key.to_string(),
                                 value: child_value.to_string(),
+                                counter: ValueParser::parse_counter(child_value),
                                 children: Vec::new(),
                             });
                             j += 1;
@@ -241,6 +250,7 @@ impl OperatorParser {
                 let item = MetricItem {
                     key: key.to_string(),
                     value: value.to_string(),
+                    counter: ValueParser::parse_counter(value),
                     children,
                 };
                 
@@ -279,7 +289,7 @@ impl OperatorParser {
     
     /// Parse operator header line
     /// Returns: (name, id, nereids_id, dest_id, dest_ids, source_id, exchange_type, table_name)
-    fn parse_header(header: &str) -> Option<(String, i32, Option<i32>, Option<i32>, Vec<i32>, Option<i32>, Option<String>, Option<String>)> {
+    fn parse_header(header: &str) -> Option<OperatorHeader> {
         let trimmed = header.trim().trim_end_matches(':');
         
         // Check for MULTI_CAST_DATA_STREAM_SINK with multiple dest_ids
@@ -418,15 +428,15 @@ impl OperatorParser {
     }
     
     /// Merge all metrics into a single HashMap
-    fn merge_metrics(parsed: &ParsedOperator) -> HashMap<String, String> {
+    fn merge_metrics(parsed: &ParsedOperator) -> HashMap<String, CounterValue> {
         let mut metrics = HashMap::new();
         
         // Add ID info
         if let Some(nid) = parsed.nereids_id {
-            metrics.insert("nereids_id".to_string(), nid.to_string());
+            metrics.insert("nereids_id".to_string(), CounterValue::count(nid as f64));
         }
         if let Some(did) = parsed.dest_id {
-            metrics.insert("dest_id".to_string(), did.to_string());
+            metrics.insert("dest_id".to_string(), CounterValue::count(did as f64));
         }
         // Store dest_ids as a comma-separated string for MULTI_CAST_SINK
         if !parsed.dest_ids.is_empty() {
@@ -434,71 +444,114 @@ impl OperatorParser {
                 .map(|id| id.to_string())
                 .collect::<Vec<_>>()
                 .join(",");
-            metrics.insert("dest_ids".to_string(), dest_ids_str);
+            metrics.insert("dest_ids".to_string(), CounterValue::text(dest_ids_str));
         }
         // Store source_id for MULTI_CAST_SOURCE
         if let Some(sid) = parsed.source_id {
-            metrics.insert("source_id".to_string(), sid.to_string());
+            metrics.insert("source_id".to_string(), CounterValue::count(sid as f64));
         }
         if let Some(ref et) = parsed.exchange_type {
-            metrics.insert("exchange_type".to_string(), et.clone());
+            metrics.insert("exchange_type".to_string(), CounterValue::text(et.clone()));
         }
         if let Some(ref tn) = parsed.table_name {
-            metrics.insert("table_name".to_string(), tn.clone());
+            metrics.insert("table_name".to_string(), CounterValue::text(tn.clone()));
         }
         
         // Add plan info
         for item in &parsed.plan_info {
-            metrics.insert(format!("plan_{}", item.key), item.value.clone());
+            metrics.insert(format!("plan_{}", item.key), item.counter.clone());
         }
         
         // Add common counters (flattened - including children)
         for item in &parsed.common_counters {
-            metrics.insert(item.key.clone(), item.value.clone());
+            metrics.insert(item.key.clone(), item.counter.clone());
             for child in &item.children {
-                metrics.insert(format!("{}.{}", item.key, child.key), child.value.clone());
+                metrics.insert(format!("{}.{}", item.key, child.key), child.counter.clone());
             }
         }
         
         // Add custom counters (flattened - including children)
         for item in &parsed.custom_counters {
-            metrics.insert(item.key.clone(), item.value.clone());
+            metrics.insert(item.key.clone(), item.counter.clone());
             for child in &item.children {
-                metrics.insert(format!("{}.{}", item.key, child.key), child.value.clone());
+                metrics.insert(format!("{}.{}", item.key, child.key), child.counter.clone());
             }
         }
         
         metrics
     }
     
-    /// Find a metric by key in a Vec<MetricItem>
-    fn find_metric<'a>(items: &'a [MetricItem], key: &str) -> Option<&'a str> {
+    /// Collect the header and PlanInfo attributes of an operator
+    pub fn attributes(parsed: &ParsedOperator) -> OperatorAttributes {
+        let (join_type, join_distribution) = parsed.plan_info.iter()
+            .find(|item| item.key == "join op")
+            .map(|item| Self::parse_join_op(&item.value))
+            .unwrap_or_default();
+        
+        OperatorAttributes {
+            nereids_id: parsed.nereids_id,
+            dest_id: parsed.dest_id,
+            dest_ids: parsed.dest_ids.clone(),
+            source_id: parsed.source_id,
+            exchange_type: parsed.exchange_type.clone(),
+            join_type,
+            join_distribution,
+        }
+    }
+    
+    /// Parse a join op such as "LEFT SEMI JOIN(PARTITIONED)[]" into its type and distribution
+    pub fn parse_join_op(value: &str) -> (Option<JoinType>, Option<JoinDistribution>) {
+        let Some(caps) = JOIN_OP_REGEX.captures(value) else {
+            return (None, None);
+        };
+        let join_type = match caps.get(1).map_or("", |m| m.as_str()) {
+            "INNER" => Some(JoinType::Inner),
+            "LEFT OUTER" => Some(JoinType::LeftOuter),
+            "RIGHT OUTER" => Some(JoinType::RightOuter),
+            "FULL OUTER" => Some(JoinType::FullOuter),
+            "LEFT SEMI" => Some(JoinType::LeftSemi),
+            "RIGHT SEMI" => Some(JoinType::RightSemi),
+            "LEFT ANTI" => Some(JoinType::LeftAnti),
+            "RIGHT ANTI" => Some(JoinType::RightAnti),
+            "NULL AWARE LEFT ANTI" => Some(JoinType::NullAwareLeftAnti),
+            "CROSS" => Some(JoinType::Cross),
+            _ => None,
+        };
+        let distribution = match caps.get(2).map_or("", |m| m.as_str()) {
+            "BROADCAST" => Some(JoinDistribution::Broadcast),
+            "PARTITIONED" => Some(JoinDistribution::Partitioned),
+            "BUCKET_SHUFFLE" => Some(JoinDistribution::BucketShuffle),
+            "COLOCATE" => Some(JoinDistribution::Colocate),
+            _ => None,
+        };
+        (join_type, distribution)
+    }
+    
+    /// Find a counter by key in a Vec<MetricItem>
+    pub fn find_counter<'a>(items: &'a [MetricItem], key: &str) -> Option<&'a CounterValue> {
         items.iter()
             .find(|item| item.key == key)
-            .map(|item| item.value.as_str())
+            .map(|item| &item.counter)
     }
     
     /// Get the execution time in nanoseconds from an operator
     pub fn get_exec_time_ns(operator: &ParsedOperator) -> Option<i64> {
-        Self::find_metric(&operator.common_counters, "ExecTime")
-            .and_then(|v| ValueParser::parse_aggregated(v).avg)
+        Self::find_counter(&operator.common_counters, "ExecTime")
+            .and_then(CounterValue::average)
+            .map(|t| t as i64)
     }
     
     /// Get rows produced by an operator
     pub fn get_rows_produced(operator: &ParsedOperator) -> Option<i64> {
-        Self::find_metric(&operator.common_counters, "RowsProduced")
-            .and_then(|v| {
-                let agg = ValueParser::parse_aggregated(v);
-                agg.sum.or(agg.avg)
-            })
+        Self::find_counter(&operator.common_counters, "RowsProduced")
+            .and_then(CounterValue::total)
+            .map(|r| r as i64)
     }
     
     pub fn get_input_rows(operator: &ParsedOperator) -> Option<i64> {
-        Self::find_metric(&operator.common_counters, "InputRows")
-            .and_then(|v| {
-                let agg = ValueParser::parse_aggregated(v);
-                agg.sum.or(agg.avg)
-            })
+        Self::find_counter(&operator.common_counters, "InputRows")
+            .and_then(CounterValue::total)
+            .map(|r| r as i64)
     }
 }
 
@@ -520,12 +573,12 @@ mod tests {
     
     #[test]
     fn test_parse_header() {
-        let (name, id, nid, _did, _et, _tn) = OperatorParser::parse_header("SORT_OPERATOR(nereids_id=1966)(id=28):").unwrap();
+        let (name, id, nid, _did, _dids, _sid, _et, _tn) = OperatorParser::parse_header("SORT_OPERATOR(nereids_id=1966)(id=28):").unwrap();
         assert_eq!(name, "SORT_OPERATOR");
         assert_eq!(id, 28);
         assert_eq!(nid, Some(1966));
         
-        let (name, _id, _, did, _, _) = OperatorParser::parse_header("DATA_STREAM_SINK_OPERATOR(dest_id=25):").unwrap();
+        let (name, _id, _, did, _, _, _, _) = OperatorParser::parse_header("DATA_STREAM_SINK_OPERATOR(dest_id=25):").unwrap();
         assert_eq!(name, "DATA_STREAM_SINK_OPERATOR");
         assert_eq!(did, Some(25));
     }
@@ -533,20 +586,38 @@ mod tests {
     #[test]
     fn test_file_scan_formats() {
         // Format 1: FILE_SCAN_OPERATOR (id=4. nereids_id=1053. table name = warehouse):
-        let (name, id, nid, _, _, tn) = OperatorParser::parse_header("FILE_SCAN_OPERATOR (id=4. nereids_id=1053. table name = warehouse):").unwrap();
+        let (name, id, nid, _, _, _, _, tn) = OperatorParser::parse_header("FILE_SCAN_OPERATOR (id=4. nereids_id=1053. table name = warehouse):").unwrap();
         assert_eq!(name, "FILE_SCAN_OPERATOR");
         assert_eq!(id, 4);
         assert_eq!(nid, Some(1053));
         assert_eq!(tn, Some("warehouse".to_string()));
         
         // Format 2: FILE_SCAN_OPERATOR (nereids_id=1052. table_name=inventory)(id=6):
-        let (name, id, nid, _, _, tn) = OperatorParser::parse_header("FILE_SCAN_OPERATOR (nereids_id=1052. table_name=inventory)(id=6):").unwrap();
+        let (name, id, nid, _, _, _, _, tn) = OperatorParser::parse_header("FILE_SCAN_OPERATOR (nereids_id=1052. table_name=inventory)(id=6):").unwrap();
         assert_eq!(name, "FILE_SCAN_OPERATOR");
         assert_eq!(id, 6);
         assert_eq!(nid, Some(1052));
         assert_eq!(tn, Some("inventory".to_string()));
     }
     
+    #[test]
+    fn test_parse_join_op() {
+        assert_eq!(
+            OperatorParser::parse_join_op("INNER JOIN(BROADCAST)[]"),
+            (Some(JoinType::Inner), Some(JoinDistribution::Broadcast))
+        );
+        assert_eq!(
+            OperatorParser::parse_join_op("LEFT SEMI JOIN(BUCKET_SHUFFLE)[]"),
+            (Some(JoinType::LeftSemi), Some(JoinDistribution::BucketShuffle))
+        );
+        assert_eq!(
+            OperatorParser::parse_join_op("NULL AWARE LEFT ANTI JOIN(PARTITIONED)[]"),
+            (Some(JoinType::NullAwareLeftAnti), Some(JoinDistribution::Partitioned))
+        );
+        assert_eq!(OperatorParser::parse_join_op("CROSS JOIN()"), (Some(JoinType::Cross), None));
+        assert_eq!(OperatorParser::parse_join_op("unknown"), (None, None));
+    }
+    
     #[test]
     fn test_extract_operators() {
         let text = r#"Pipeline 0(instance_num=1):
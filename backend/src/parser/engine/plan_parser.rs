@@ -0,0 +1,289 @@
+//! PhysicalPlan parser for the Appendix section of Doris profiles
+//! Parses the "+--" / "|--" indented Nereids plan tree and links it to execution tree nodes
+
+use crate::models::*;
+use crate::parser::engine::ValueParser;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Regex for a plan node line:
+/// "PhysicalHashJoin[1854]@15 ( stats=720,000,376 actualRows=183750, type=INNER_JOIN )"
+/// "PhysicalFileScan[web_sales]@0 ( stats=720,000,376 actualRows=183750, ... )"
+/// "PhysicalResultSink[1970] ( outputExprs=[am_pm_ratio#128] )"
+static PLAN_NODE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(Physical[A-Za-z]+)\[([^\]]*)\](?:@(\d+))?\s*(?:\(\s*(.*?)\s*\))?\s*$").unwrap()
+});
+
+/// Regex for the tree marker of child lines: "     |  +--PhysicalProject..."
+static TREE_MARKER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([\s|]*)[+|]--(.*)$").unwrap()
+});
+
+pub struct PhysicalPlanParser;
+
+impl PhysicalPlanParser {
+    /// Parse the PhysicalPlan section text into a plan tree
+    pub fn parse(text: &str) -> PhysicalPlan {
+        let mut nodes: Vec<PhysicalPlanNode> = Vec::new();
+        // Stack of (marker column, node index) for the current path from root
+        let mut stack: Vec<(isize, usize)> = Vec::new();
+        
+        for line in text.lines() {
+            if line.trim().is_empty() {
+                if nodes.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            
+            let (column, content) = if let Some(caps) = TREE_MARKER_REGEX.captures(line) {
+                (caps[1].len() as isize, caps.get(2).map(|m| m.as_str()).unwrap_or(""))
+            } else if nodes.is_empty() {
+                (-1, line.trim())
+            } else {
+                // Lines after the tree (column statistics of scans) are not part of it
+                break;
+            };
+            
+            let Some(mut node) = Self::parse_node(content, nodes.len()) else {
+                if nodes.is_empty() {
+                    continue;
+                }
+                break;
+            };
+            
+            while stack.last().is_some_and(|&(col, _)| col >= column) {
+                stack.pop();
+            }
+            
+            let idx = nodes.len();
+            if let Some(&(_, parent_idx)) = stack.last() {
+                node.depth = nodes[parent_idx].depth + 1;
+                let node_id = node.id.clone();
+                nodes[parent_idx].children.push(node_id);
+            }
+            
+            nodes.push(node);
+            stack.push((column, idx));
+        }
+        
+        PhysicalPlan { nodes }
+    }
+    
+    /// Parse a single plan node line (without tree markers)
+    fn parse_node(content: &str, index: usize) -> Option<PhysicalPlanNode> {
+        let caps = PLAN_NODE_REGEX.captures(content.trim())?;
+        
+        let name = caps[1].to_string();
+        let bracket = caps[2].trim();
+        let group_id = caps.get(3).and_then(|m| m.as_str().parse().ok());
+        
+        // Scans carry the table name in brackets instead of the nereids id
+        let (nereids_id, table_name) = match bracket.parse::<i32>() {
+            Ok(id) => (Some(id), None),
+            Err(_) if !bracket.is_empty() => (None, Some(bracket.to_string())),
+            Err(_) => (None, None),
+        };
+        
+        let attributes = caps.get(4)
+            .map(|m| Self::parse_attributes(m.as_str()))
+            .unwrap_or_default();
+        
+        let find_attr = |key: &str| {
+            attributes.iter()
+                .find(|item| item.key == key)
+                .map(|item| item.value.as_str())
+        };
+        
+        let estimated_rows = find_attr("stats")
+            .and_then(|v| v.replace(',', "").parse::<f64>().ok());
+        let actual_rows = find_attr("actualRows")
+            .and_then(|v| v.replace(',', "").parse::<u64>().ok());
+        
+        Some(PhysicalPlanNode {
+            id: format!("plan-{}", index),
+            name,
+            nereids_id,
+            group_id,
+            table_name,
+            estimated_rows,
+            actual_rows,
+            attributes,
+            children: Vec::new(),
+            depth: 0,
+            execution_node_ids: Vec::new(),
+        })
+    }
+    
+    /// Parse "key=value, key=value" attributes, respecting nested brackets
+    /// The leading "stats=720,000,376 actualRows=183750" pair is separated by a space
+    fn parse_attributes(text: &str) -> Vec<MetricItem> {
+        let mut attributes = Vec::new();
+        
+        for part in Self::split_top_level(text) {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            
+            let pieces: Vec<&str> = if part.starts_with("stats=") {
+                part.split_whitespace().collect()
+            } else {
+                vec![part]
+            };
+            
+            for piece in pieces {
+                let (key, value) = match piece.split_once('=') {
+                    Some((k, v)) => (k.trim(), v.trim()),
+                    None => (piece, ""),
+                };
+                attributes.push(MetricItem {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    counter: ValueParser::parse_counter(value),
+                    children: Vec::new(),
+                });
+            }
+        }
+        
+        attributes
+    }
+    
+    /// Split on ", " separators that are not nested inside brackets or parentheses.
+    /// Thousands separators like "720,000,376" are not followed by a space and are kept.
+    fn split_top_level(text: &str) -> Vec<&str> {
+        let mut parts = Vec::new();
+        let mut depth: i32 = 0;
+        let mut start = 0;
+        let bytes = text.as_bytes();
+        
+        for (i, &b) in bytes.iter().enumerate() {
+            match b {
+                b'[' | b'(' | b'{' => depth += 1,
+                b']' | b')' | b'}' => depth -= 1,
+                b',' if depth == 0 && bytes.get(i + 1) == Some(&b' ') => {
+                    parts.push(&text[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        parts.push(&text[start..]);
+        
+        parts
+    }
+    
+    /// Link plan nodes to execution tree nodes.
+    /// Operators are matched by nereids_id; scans, whose plan nodes carry the table
+    /// name instead of an id, are matched by table name and actual row count.
+    pub fn link_execution_tree(plan: &mut PhysicalPlan, tree: &mut ExecutionTree) {
+        for node in tree.nodes.iter_mut() {
+            let mut plan_idx = node.attributes.nereids_id.and_then(|nid| {
+                plan.nodes.iter().position(|p| p.nereids_id == Some(nid))
+            });
+            
+            if plan_idx.is_none() {
+                if let Some(ref table) = node.table_name {
+                    plan_idx = Self::find_scan_node(plan, table, node.metrics.rows_returned);
+                }
+            }
+            
+            if let Some(idx) = plan_idx {
+                node.physical_plan_node_id = Some(plan.nodes[idx].id.clone());
+                plan.nodes[idx].execution_node_ids.push(node.id.clone());
+            }
+        }
+        
+        if let Some(root) = tree.nodes.iter().find(|n| n.id == tree.root.id) {
+            tree.root = root.clone();
+        }
+    }
+    
+    /// Find the plan scan node for a table, disambiguating repeated tables by actual rows
+    fn find_scan_node(plan: &PhysicalPlan, table: &str, rows: Option<u64>) -> Option<usize> {
+        // Execution tree table names may look like "date_dim(date_dim)"
+        let table = table.split('(').next().unwrap_or(table).trim();
+        
+        let candidates: Vec<usize> = plan.nodes.iter()
+            .enumerate()
+            .filter(|(_, p)| p.table_name.as_deref() == Some(table))
+            .map(|(i, _)| i)
+            .collect();
+        
+        if candidates.len() <= 1 {
+            return candidates.first().copied();
+        }
+        
+        // Prefer a plan node that is not linked yet, so repeated scans spread in plan order
+        let rows_match = |i: &usize| rows.is_some() && plan.nodes[*i].actual_rows == rows;
+        let unlinked = |i: &usize| plan.nodes[*i].execution_node_ids.is_empty();
+        
+        candidates.iter().copied().find(|i| rows_match(i) && unlinked(i))
+            .or_else(|| candidates.iter().copied().find(rows_match))
+            .or_else(|| candidates.iter().copied().find(unlinked))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const PLAN_TEXT: &str = r#"
+PhysicalResultSink[1970] ( outputExprs=[am_pm_ratio#128] )
+     +--PhysicalTopN[1966]@38 ( stats=1 actualRows=1, limit=100, offset=0, orderKeys=[am_pm_ratio#128 asc null first], phase=GATHER_SORT )
+        +--PhysicalHashJoin[1854]@15 ( stats=720,000,376 actualRows=183750, type=INNER_JOIN, hashCondition=[(ws_web_page_sk#12 = wp_web_page_sk#49)], otherCondition=[], markCondition=[] )
+           |--PhysicalFileScan[web_sales]@0 ( stats=720,000,376 actualRows=183750, qualified=iceberg.tpcds1000_parquet.web_sales, selected partitions num=unknown )
+           +--PhysicalDistribute[1850]@14 ( stats=750, distributionSpec=DistributionSpecReplicated )
+              +--PhysicalFilter[1842]@13 ( stats=750, predicates=AND[(wp_char_count#59 >= 5000),(wp_char_count#59 <= 5200)] )
+                 +--PhysicalFileScan[web_page]@12 ( stats=3,000 actualRows=67, qualified=iceberg.tpcds1000_parquet.web_page )
+     PhysicalFileScan[web_page]@12 ( stats=3,000 actualRows=67, qualified=iceberg.tpcds1000_parquet.web_page )
+       wp_web_page_sk#49 -> unknown(3000.0)
+"#;
+    
+    #[test]
+    fn test_parse_plan_tree() {
+        let plan = PhysicalPlanParser::parse(PLAN_TEXT);
+        assert_eq!(plan.nodes.len(), 7);
+        
+        let root = &plan.nodes[0];
+        assert_eq!(root.name, "PhysicalResultSink");
+        assert_eq!(root.nereids_id, Some(1970));
+        assert_eq!(root.children, vec!["plan-1".to_string()]);
+        
+        let join = &plan.nodes[2];
+        assert_eq!(join.name, "PhysicalHashJoin");
+        assert_eq!(join.depth, 2);
+        assert_eq!(join.children.len(), 2);
+        
+        let filter = &plan.nodes[5];
+        assert_eq!(filter.children, vec!["plan-6".to_string()]);
+        assert_eq!(filter.depth, 4);
+    }
+    
+    #[test]
+    fn test_parse_node_attributes() {
+        let plan = PhysicalPlanParser::parse(PLAN_TEXT);
+        
+        let join = &plan.nodes[2];
+        assert_eq!(join.nereids_id, Some(1854));
+        assert_eq!(join.group_id, Some(15));
+        assert_eq!(join.estimated_rows, Some(720_000_376.0));
+        assert_eq!(join.actual_rows, Some(183750));
+        let hash_condition = join.attributes.iter().find(|a| a.key == "hashCondition").unwrap();
+        assert_eq!(hash_condition.value, "[(ws_web_page_sk#12 = wp_web_page_sk#49)]");
+        
+        let scan = &plan.nodes[3];
+        assert_eq!(scan.table_name.as_deref(), Some("web_sales"));
+        assert_eq!(scan.nereids_id, None);
+        
+        let filter = &plan.nodes[5];
+        let predicates = filter.attributes.iter().find(|a| a.key == "predicates").unwrap();
+        assert_eq!(predicates.value, "AND[(wp_char_count#59 >= 5000),(wp_char_count#59 <= 5200)]");
+    }
+    
+    #[test]
+    fn test_split_top_level() {
+        let parts = PhysicalPlanParser::split_top_level("stats=3,600, predicates=(a = 1), projects=[x, y]");
+        assert_eq!(parts, vec!["stats=3,600", " predicates=(a = 1)", " projects=[x, y]"]);
+    }
+}
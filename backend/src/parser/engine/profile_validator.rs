@@ -0,0 +1,351 @@
+//! Profile validator for lenient parsing
+//! Reports located warnings for the parts of a profile that the parsers skip or default
+
+use crate::models::{ExecutionTree, ParseWarning, ProfileFormat};
+use crate::parser::engine::{OperatorParser, SectionParser, ValueParser};
+use crate::models::CounterKind;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{HashSet, VecDeque};
+
+/// Regex for metric lines: "- MetricName: value"
+static METRIC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*-\s+([^:]+):\s*(.*)$").unwrap()
+});
+
+/// Regex for the non-operator headers of a MergedProfile
+static STRUCTURE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:Fragments:|Fragment\s+\d+:|Pipeline\s+\d+\(instance_num=\d+\):|CommonCounters:|CustomCounters:|PlanInfo|RuntimeFilterInfo)").unwrap()
+});
+
+/// Regex for Fragment / Pipeline headers, used to locate tree nodes
+static FRAGMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Fragment\s+(\d+):").unwrap()
+});
+
+static PIPELINE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Pipeline\s+(\d+)\(").unwrap()
+});
+
+pub struct ProfileValidator;
+
+impl ProfileValidator {
+    /// Check every line of the execution sections: unknown operator headers
+    /// (MergedProfile only) and counter values that could not be parsed
+    pub fn check_lines(text: &str) -> Vec<ParseWarning> {
+        let mut warnings = Vec::new();
+        
+        for (idx, section, line) in Self::lines_with_sections(text) {
+            if section != "MergedProfile" && section != "Execution Profile" {
+                continue;
+            }
+            let trimmed = line.trim();
+            if trimmed.is_empty() || SectionParser::is_section_header(trimmed) {
+                continue;
+            }
+            let column = line.len() - line.trim_start().len() + 1;
+            
+            if let Some(caps) = METRIC_REGEX.captures(line) {
+                let value = caps.get(2).unwrap();
+                if Self::is_unparseable(value.as_str()) {
+                    warnings.push(Self::warning(idx, value.start() + 1, section, format!(
+                        "Could not parse value \"{}\" of counter {}; it is kept as text",
+                        value.as_str().trim(), caps[1].trim()
+                    )));
+                }
+            } else if section == "MergedProfile"
+                && !trimmed.starts_with('-')
+                && !STRUCTURE_REGEX.is_match(trimmed)
+                && !OperatorParser::is_operator_header(trimmed)
+            {
+                warnings.push(Self::warning(idx, column, section, format!(
+                    "Unknown operator header \"{}\"; its counters are skipped",
+                    trimmed
+                )));
+            }
+        }
+        
+        warnings
+    }
+    
+    /// Check that the sections needed for the format are present and not cut off
+    pub fn check_sections(text: &str, format: ProfileFormat) -> Vec<ParseWarning> {
+        let mut warnings = Vec::new();
+        let lines = Self::lines_with_sections(text);
+        let header_line = |name: &str| {
+            lines.iter()
+                .find(|(_, section, line)| *section == name && SectionParser::is_section_header(line.trim()))
+                .map(|(idx, _, _)| *idx)
+        };
+        
+        if header_line("Summary").is_none() {
+            warnings.push(Self::warning(0, 1, "Summary", "Summary section not found; query information is unavailable".to_string()));
+        }
+        
+        if let Some(idx) = header_line("ChangedSessionVariables") {
+            if SectionParser::parse_session_variables(text).is_err() {
+                warnings.push(Self::warning(idx, 1, "ChangedSessionVariables",
+                    "ChangedSessionVariables is not valid JSON; session variables are skipped".to_string()));
+            }
+        }
+        
+        let section = match format {
+            ProfileFormat::Pipeline => "MergedProfile",
+            ProfileFormat::NonPipeline => "Execution Profile",
+        };
+        let Some(start) = header_line(section) else {
+            warnings.push(Self::warning(lines.len().saturating_sub(1), 1, section, format!(
+                "{} section not found; no execution tree was built",
+                section
+            )));
+            return warnings;
+        };
+        
+        let body: Vec<(usize, &str)> = lines.iter()
+            .filter(|(idx, s, line)| *s == section && *idx > start && !line.trim().is_empty())
+            .map(|(idx, _, line)| (*idx, line.trim()))
+            .collect();
+        
+        if !body.iter().any(|(_, line)| FRAGMENT_REGEX.is_match(line)) {
+            warnings.push(Self::warning(start, 1, section, format!("No fragments found in {}", section)));
+            return warnings;
+        }
+        
+        // A profile cut off in the middle ends with a header that has no counters.
+        // Empty CustomCounters are normal, e.g. for result sinks
+        let last_header = body.iter().rev().find(|(_, line)| !line.starts_with('-'));
+        let has_counters_after = |header_idx: usize| body.iter().any(|(idx, line)| *idx > header_idx && line.starts_with('-'));
+        if let Some(&(idx, header)) = last_header {
+            if !header.starts_with("CustomCounters") && !has_counters_after(idx) {
+                let column = lines[idx].2.len() - lines[idx].2.trim_start().len() + 1;
+                warnings.push(Self::warning(idx, column, section, format!(
+                    "{} ends at \"{}\" without counters; the profile looks truncated",
+                    section, header
+                )));
+            }
+        }
+        
+        warnings
+    }
+    
+    /// Report an execution section whose fragments contain no operator, so that
+    /// no execution tree was built. Missing sections and fragments are reported
+    /// by `check_sections`
+    pub fn check_operators(text: &str, format: ProfileFormat) -> Vec<ParseWarning> {
+        let section = match format {
+            ProfileFormat::Pipeline => "MergedProfile",
+            ProfileFormat::NonPipeline => "Execution Profile",
+        };
+        Self::lines_with_sections(text).into_iter()
+            .rfind(|(_, s, line)| *s == section && FRAGMENT_REGEX.is_match(line.trim()))
+            .map(|(idx, _, line)| {
+                let column = line.len() - line.trim_start().len() + 1;
+                Self::warning(idx, column, section, format!(
+                    "No operators found in the fragments of {}; no execution tree was built",
+                    section
+                ))
+            })
+            .into_iter()
+            .collect()
+    }
+    
+    /// Report tree nodes that cannot be reached from the root, located at their operator header
+    pub fn check_tree(text: &str, tree: &ExecutionTree) -> Vec<ParseWarning> {
+        let mut reachable = HashSet::new();
+        let mut queue = VecDeque::from([tree.root.id.as_str()]);
+        while let Some(id) = queue.pop_front() {
+            if !reachable.insert(id) {
+                continue;
+            }
+            if let Some(node) = tree.nodes.iter().find(|n| n.id == id) {
+                queue.extend(node.children.iter().map(String::as_str));
+            }
+        }
+        
+        tree.nodes.iter()
+            .filter(|node| !reachable.contains(node.id.as_str()))
+            .map(|node| {
+                let (idx, column, section) = Self::locate_operator(
+                    text,
+                    node.fragment_id.as_deref(),
+                    node.pipeline_id.as_deref(),
+                    &node.operator_name,
+                    node.plan_node_id,
+                );
+                let plan_id = node.plan_node_id.map(|id| format!(" (id={})", id)).unwrap_or_default();
+                Self::warning(idx, column, section, format!(
+                    "{}{} in {} is not connected to the execution tree",
+                    node.operator_name, plan_id, node.fragment_id.as_deref().unwrap_or("unknown fragment")
+                ))
+            })
+            .collect()
+    }
+    
+    /// Find the header line of an operator; falls back to its fragment header, then the first line
+    fn locate_operator(
+        text: &str,
+        fragment_id: Option<&str>,
+        pipeline_id: Option<&str>,
+        operator_name: &str,
+        plan_node_id: Option<i32>,
+    ) -> (usize, usize, &'static str) {
+        let mut fragment = None;
+        let mut pipeline = None;
+        let mut fragment_line = None;
+        let id_marker = plan_node_id.map(|id| format!("id={}", id));
+        
+        for (idx, section, line) in Self::lines_with_sections(text) {
+            if section != "MergedProfile" && section != "Execution Profile" {
+                continue;
+            }
+            let trimmed = line.trim();
+            let column = line.len() - line.trim_start().len() + 1;
+            
+            if let Some(caps) = FRAGMENT_REGEX.captures(trimmed) {
+                fragment = Some(format!("Fragment {}", &caps[1]));
+                pipeline = None;
+                if fragment.as_deref() == fragment_id && fragment_line.is_none() {
+                    fragment_line = Some((idx, column, section));
+                }
+                continue;
+            }
+            if let Some(caps) = PIPELINE_REGEX.captures(trimmed) {
+                pipeline = Some(format!("Pipeline {}", &caps[1]));
+                continue;
+            }
+            
+            let in_place = fragment.as_deref() == fragment_id
+                && (pipeline_id.is_none() || pipeline.as_deref() == pipeline_id);
+            let same_id = id_marker.as_ref().map_or(true, |marker| trimmed.contains(marker.as_str()));
+            if in_place && same_id && trimmed.starts_with(operator_name) {
+                return (idx, column, section);
+            }
+        }
+        
+        fragment_line.unwrap_or((0, 1, "Profile"))
+    }
+    
+    /// A counter value that looks numeric but did not parse as time, bytes, count or ratio.
+    /// Rates ("1.2M /sec"), id lists ("680 873 681") and empty stats ("sum , avg , max , min")
+    /// are known text values
+    fn is_unparseable(value: &str) -> bool {
+        let value = value.trim();
+        let id_list = value.chars().all(|c| c.is_ascii_digit() || c == ' ' || c == ',');
+        let has_digit = value.chars().any(|c| c.is_ascii_digit());
+        let looks_numeric = value.starts_with(|c: char| c.is_ascii_digit())
+            || (value.starts_with('-') && value[1..].starts_with(|c: char| c.is_ascii_digit()))
+            || value.starts_with("sum ")
+            || value.starts_with("avg ");
+        
+        looks_numeric
+            && has_digit
+            && !id_list
+            && !value.ends_with("/sec")
+            && ValueParser::parse_counter(value).kind == CounterKind::Text
+    }
+    
+    /// Lines of the profile with their index and the top-level section they belong to
+    fn lines_with_sections(text: &str) -> Vec<(usize, &'static str, &str)> {
+        let mut section = "Profile";
+        
+        text.lines()
+            .enumerate()
+            .map(|(idx, line)| {
+                let trimmed = line.trim();
+                if SectionParser::is_section_header(trimmed) {
+                    section = Self::section_name(trimmed);
+                }
+                (idx, section, line)
+            })
+            .collect()
+    }
+    
+    /// Name of a top-level section from its header line
+    fn section_name(header: &str) -> &'static str {
+        [
+            "Execution Summary",
+            "Summary",
+            "ChangedSessionVariables",
+            "MergedProfile",
+            "DetailProfile",
+            "Appendix",
+            "Execution Profile",
+        ]
+        .into_iter()
+        .find(|name| header.starts_with(name))
+        .unwrap_or("Profile")
+    }
+    
+    /// Build a warning for a 0-based line index and 1-based column
+    fn warning(idx: usize, column: usize, section: &str, message: String) -> ParseWarning {
+        ParseWarning {
+            line: idx + 1,
+            column,
+            section: section.to_string(),
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const TRUNCATED_PROFILE: &str = r#"Summary:
+   - Profile ID: test-123
+   - Total: 1sec
+MergedProfile:
+     Fragments:
+       Fragment 0:
+         Pipeline 0(instance_num=1):
+           RESULT_SINK_OPERATOR(id=2147483647):
+             CommonCounters:
+               - ExecTime: avg 95.241us, max 95.241us, min 95.241us
+               - InputRows: 12 rowz
+             CustomCounters:
+           MYSTERY_OPERATOR[id=3]:
+             CommonCounters:
+               - ExecTime: avg 1.000ms, max 1.000ms, min 1.000ms
+       Fragment 1:
+         Pipeline 0(instance_num=1):
+           EXCHANGE_OPERATOR(id=3):
+"#;
+    
+    #[test]
+    fn test_check_lines() {
+        let warnings = ProfileValidator::check_lines(TRUNCATED_PROFILE);
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        
+        assert_eq!(warnings[0].line, 11);
+        assert_eq!(warnings[0].column, 29);
+        assert_eq!(warnings[0].section, "MergedProfile");
+        assert!(warnings[0].message.contains("InputRows"));
+        
+        assert_eq!((warnings[1].line, warnings[1].column), (13, 12));
+        assert!(warnings[1].message.contains("MYSTERY_OPERATOR[id=3]"));
+    }
+    
+    #[test]
+    fn test_check_sections() {
+        let warnings = ProfileValidator::check_sections(TRUNCATED_PROFILE, ProfileFormat::Pipeline);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert_eq!((warnings[0].line, warnings[0].column), (18, 12));
+        assert!(warnings[0].message.contains("truncated"));
+        
+        let warnings = ProfileValidator::check_sections("   - Profile ID: x\n", ProfileFormat::Pipeline);
+        let sections: Vec<&str> = warnings.iter().map(|w| w.section.as_str()).collect();
+        assert_eq!(sections, vec!["Summary", "MergedProfile"]);
+    }
+    
+    #[test]
+    fn test_is_unparseable() {
+        assert!(ProfileValidator::is_unparseable("sum 1 widgets"));
+        assert!(ProfileValidator::is_unparseable("12 widgets"));
+        assert!(!ProfileValidator::is_unparseable("1.2M /sec"));
+        assert!(!ProfileValidator::is_unparseable("N/A"));
+        assert!(!ProfileValidator::is_unparseable("avg 95.241us, max 95.241us, min 95.241us"));
+        assert!(!ProfileValidator::is_unparseable("BROADCAST"));
+        assert!(!ProfileValidator::is_unparseable("680 873 681 874"));
+        assert!(!ProfileValidator::is_unparseable("sum , avg , max , min"));
+    }
+}
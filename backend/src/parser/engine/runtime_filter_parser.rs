@@ -0,0 +1,389 @@
+//! Runtime filter parser
+//! Links RuntimeFilterInfo counters of scans to the joins that publish each filter
+
+use crate::models::*;
+use crate::parser::engine::ValueParser;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Regex for per-filter counters: "RF6 FilterRows", "RF10 WaitTime", "RF10 Info"
+static RF_COUNTER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^RF0*(\d+)\s+(\w+)$").unwrap()
+});
+
+/// Regex for scan PlanInfo: "runtime filters: RF006[min_max] -> ws_ship_hdemo_sk, ..."
+static RF_PLAN_INFO_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"RF0*(\d+)\[(\w+)\]\s*->\s*([^,]+)").unwrap()
+});
+
+/// Regex for join RFs in the physical plan: "RF10[wp_web_page_sk#49->[ws_web_page_sk#12](ndv/size = 750/1024)"
+static RF_PLAN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"RF0*(\d+)\[([^\[\]]+?)->\[([^\]]+)\]").unwrap()
+});
+
+/// Regex for consumer info: "..., reached_timeout: false, timeout_limit: 5000ms)"
+static RF_TIMEOUT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"reached_timeout:\s*(\w+)(?:,\s*timeout_limit:\s*([\w.]+))?").unwrap()
+});
+
+pub struct RuntimeFilterParser;
+
+impl RuntimeFilterParser {
+    /// Build runtime filters from scan counters, plan RFs and DetailProfile wait times
+    pub fn build(
+        tree: &ExecutionTree,
+        plan: Option<&PhysicalPlan>,
+        detail: Option<&DetailProfile>,
+    ) -> Vec<RuntimeFilter> {
+        let mut filters: BTreeMap<u32, RuntimeFilter> = BTreeMap::new();
+        
+        for node in &tree.nodes {
+            Self::collect_targets(node, &mut filters);
+        }
+        
+        if let Some(plan) = plan {
+            Self::link_builders(plan, tree, &mut filters);
+        }
+        
+        if let Some(detail) = detail {
+            Self::apply_detail(detail, tree, &mut filters);
+        }
+        
+        for target in filters.values_mut().flat_map(|f| f.targets.iter_mut()) {
+            Self::update_filter_rate(target);
+        }
+        
+        filters.into_values().collect()
+    }
+    
+    /// Collect targets from scan PlanInfo and RuntimeFilterInfo counters
+    fn collect_targets(node: &ExecutionTreeNode, filters: &mut BTreeMap<u32, RuntimeFilter>) {
+        if let Some(info) = node.unique_metrics.get("runtime filters").and_then(CounterValue::as_text) {
+            for caps in RF_PLAN_INFO_REGEX.captures_iter(info) {
+                let Ok(rf_id) = caps[1].parse::<u32>() else {
+                    continue;
+                };
+                let filter = Self::filter_entry(filters, rf_id);
+                filter.filter_type.get_or_insert_with(|| caps[2].to_string());
+                let target = Self::target_entry(filter, node);
+                target.target_expr.get_or_insert_with(|| caps[3].trim().to_string());
+            }
+        }
+        
+        let Some(rf_info) = Self::find_rf_info(&node.custom_counters) else {
+            return;
+        };
+        
+        for item in &rf_info.children {
+            let Some((rf_id, counter)) = Self::parse_rf_counter(&item.key) else {
+                continue;
+            };
+            let filter = Self::filter_entry(filters, rf_id);
+            let target = Self::target_entry(filter, node);
+            Self::apply_counter(target, &counter, item);
+        }
+    }
+    
+    /// Link each filter to the join that publishes it via the plan's "RFs" attribute
+    fn link_builders(plan: &PhysicalPlan, tree: &ExecutionTree, filters: &mut BTreeMap<u32, RuntimeFilter>) {
+        for plan_node in &plan.nodes {
+            let Some(rfs) = plan_node.attributes.iter().find(|a| a.key == "RFs") else {
+                continue;
+            };
+            
+            // The sink of the join carries BuildTime / PublishTime
+            let exec_nodes: Vec<&ExecutionTreeNode> = plan_node.execution_node_ids.iter()
+                .filter_map(|id| tree.nodes.iter().find(|n| &n.id == id))
+                .collect();
+            let builder_node = exec_nodes.iter()
+                .find(|n| matches!(n.node_type, NodeType::HashJoinBuild | NodeType::NestedLoopJoinBuild))
+                .or_else(|| exec_nodes.first());
+            
+            for caps in RF_PLAN_REGEX.captures_iter(&rfs.value) {
+                let Ok(rf_id) = caps[1].parse::<u32>() else {
+                    continue;
+                };
+                let filter = Self::filter_entry(filters, rf_id);
+                filter.source_expr = Some(caps[2].trim().to_string());
+                
+                for target in filter.targets.iter_mut() {
+                    target.target_expr.get_or_insert_with(|| caps[3].trim().to_string());
+                }
+                
+                if let Some(node) = builder_node {
+                    let rf_info = Self::find_rf_info(&node.custom_counters);
+                    let find_time = |key: &str| {
+                        rf_info
+                            .and_then(|info| info.children.iter().find(|c| c.key == key))
+                            .and_then(|c| Self::max_time(&c.counter))
+                    };
+                    
+                    filter.builder = Some(RuntimeFilterBuilder {
+                        node_id: node.id.clone(),
+                        operator_name: node.operator_name.clone(),
+                        nereids_id: plan_node.nereids_id,
+                        build_time: find_time("BuildTime"),
+                        publish_time: find_time("PublishTime"),
+                    });
+                }
+            }
+        }
+    }
+    
+    /// Merge per-instance wait times and timeouts from the DetailProfile
+    fn apply_detail(detail: &DetailProfile, tree: &ExecutionTree, filters: &mut BTreeMap<u32, RuntimeFilter>) {
+        let operators = detail.fragments.iter()
+            .flat_map(|f| f.backends.iter())
+            .flat_map(|b| b.pipelines.iter())
+            .flat_map(|p| p.tasks.iter())
+            .flat_map(|t| t.operators.iter());
+        
+        for op in operators {
+            let Some(rf_info) = Self::find_rf_info(&op.custom_counters) else {
+                continue;
+            };
+            let Some(node) = tree.nodes.iter().find(|n| {
+                n.plan_node_id == Some(op.plan_node_id) && n.operator_name == op.operator_name
+            }) else {
+                continue;
+            };
+            
+            for item in &rf_info.children {
+                let Some((rf_id, counter)) = Self::parse_rf_counter(&item.key) else {
+                    continue;
+                };
+                if counter != "WaitTime" && counter != "Info" {
+                    continue;
+                }
+                let filter = Self::filter_entry(filters, rf_id);
+                let target = Self::target_entry(filter, node);
+                Self::apply_counter(target, &counter, item);
+            }
+        }
+    }
+    
+    /// Apply one per-filter counter to a target
+    fn apply_counter(target: &mut RuntimeFilterTarget, counter: &str, item: &MetricItem) {
+        match counter {
+            "FilterRows" => target.filter_rows = Self::rows(&item.counter),
+            "InputRows" => target.input_rows = Self::rows(&item.counter),
+            "WaitTime" => {
+                if let Some(wait) = Self::max_time(&item.counter) {
+                    target.wait_time = Some(target.wait_time.map_or(wait, |w| w.max(wait)));
+                }
+            }
+            "Info" => {
+                if let Some(caps) = RF_TIMEOUT_REGEX.captures(&item.value) {
+                    target.reached_timeout |= &caps[1] == "true";
+                    if let Some(limit) = caps.get(2) {
+                        target.timeout_limit = ValueParser::parse_time_to_ns(limit.as_str())
+                            .map(|ns| ns as u64);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    /// Recompute the filter rate once both row counters are known
+    fn update_filter_rate(target: &mut RuntimeFilterTarget) {
+        target.filter_rate = match (target.filter_rows, target.input_rows) {
+            (Some(filtered), Some(input)) if input > 0 => Some(filtered as f64 / input as f64),
+            _ => None,
+        };
+    }
+    
+    /// Get or create the filter with the given id
+    fn filter_entry(filters: &mut BTreeMap<u32, RuntimeFilter>, rf_id: u32) -> &mut RuntimeFilter {
+        filters.entry(rf_id).or_insert_with(|| RuntimeFilter {
+            id: format!("RF{}", rf_id),
+            filter_type: None,
+            source_expr: None,
+            builder: None,
+            targets: Vec::new(),
+        })
+    }
+    
+    /// Get or create the target entry for an execution node
+    fn target_entry<'a>(filter: &'a mut RuntimeFilter, node: &ExecutionTreeNode) -> &'a mut RuntimeFilterTarget {
+        let idx = match filter.targets.iter().position(|t| t.node_id == node.id) {
+            Some(idx) => idx,
+            None => {
+                filter.targets.push(RuntimeFilterTarget {
+                    node_id: node.id.clone(),
+                    operator_name: node.operator_name.clone(),
+                    table_name: node.table_name.clone(),
+                    target_expr: None,
+                    input_rows: None,
+                    filter_rows: None,
+                    filter_rate: None,
+                    wait_time: None,
+                    timeout_limit: None,
+                    reached_timeout: false,
+                });
+                filter.targets.len() - 1
+            }
+        };
+        &mut filter.targets[idx]
+    }
+    
+    /// Find the RuntimeFilterInfo counter group
+    fn find_rf_info(counters: &[MetricItem]) -> Option<&MetricItem> {
+        counters.iter().find(|item| item.key == "RuntimeFilterInfo")
+    }
+    
+    /// Split "RF6 FilterRows" into (6, "FilterRows")
+    fn parse_rf_counter(key: &str) -> Option<(u32, String)> {
+        let caps = RF_COUNTER_REGEX.captures(key.trim())?;
+        Some((caps[1].parse().ok()?, caps[2].to_string()))
+    }
+    
+    /// Rows of a row counter: the sum if aggregated (merged profile), else the single value (detail)
+    fn rows(counter: &CounterValue) -> Option<u64> {
+        counter.total().map(|v| v.max(0.0) as u64)
+    }
+    
+    /// Time of a time counter, taking the max across instances if aggregated
+    fn max_time(counter: &CounterValue) -> Option<u64> {
+        counter.maximum().map(|v| v.max(0.0) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn metric(key: &str, value: &str, children: Vec<MetricItem>) -> MetricItem {
+        MetricItem {
+            key: key.to_string(),
+            value: value.to_string(),
+            counter: ValueParser::parse_counter(value),
+            children,
+        }
+    }
+    
+    fn create_node(id: &str, operator_name: &str, plan_node_id: i32, custom_counters: Vec<MetricItem>) -> ExecutionTreeNode {
+        ExecutionTreeNode {
+            id: id.to_string(),
+            operator_name: operator_name.to_string(),
+            plan_node_id: Some(plan_node_id),
+            custom_counters,
+            ..Default::default()
+        }
+    }
+    
+    fn create_tree() -> ExecutionTree {
+        let mut scan = create_node("scan", "FILE_SCAN_OPERATOR", 20, vec![
+            metric("RuntimeFilterInfo", "sum , avg , max , min", vec![
+                metric("RF10 FilterRows", "sum 5.718K (5718), avg 119, max 178, min 79", vec![]),
+                metric("RF10 InputRows", "sum 315.763K (315763), avg 6.578K (6578), max 9.079K (9079), min 5.312K (5312)", vec![]),
+                metric("RF9 FilterRows", "sum 0, avg 0, max 0, min 0", vec![]),
+                metric("RF9 InputRows", "sum 2.221724M (2221724), avg 46.285K (46285), max 65.15K (65150), min 35.284K (35284)", vec![]),
+            ]),
+        ]);
+        scan.unique_metrics.insert(
+            "runtime filters".to_string(),
+            CounterValue::text("RF009[in_or_bloom] -> ws_sold_time_sk, RF010[min_max] -> ws_web_page_sk"),
+        );
+        scan.table_name = Some("web_sales".to_string());
+        
+        let sink = create_node("sink", "HASH_JOIN_SINK_OPERATOR", 23, vec![
+            metric("RuntimeFilterInfo", "sum , avg , max , min", vec![
+                metric("BuildTime", "avg 633ns, max 15.656us, min 0ns", vec![]),
+                metric("PublishTime", "avg 4.69us, max 7.161us, min 2.476us", vec![]),
+            ]),
+        ]);
+        
+        ExecutionTree { root: sink.clone(), nodes: vec![sink, scan] }
+    }
+    
+    #[test]
+    fn test_collect_targets() {
+        let filters = RuntimeFilterParser::build(&create_tree(), None, None);
+        assert_eq!(filters.len(), 2);
+        
+        let rf9 = &filters[0];
+        assert_eq!(rf9.id, "RF9");
+        assert_eq!(rf9.filter_type.as_deref(), Some("in_or_bloom"));
+        assert_eq!(rf9.targets.len(), 1);
+        assert_eq!(rf9.targets[0].filter_rows, Some(0));
+        assert_eq!(rf9.targets[0].input_rows, Some(2221724));
+        assert_eq!(rf9.targets[0].filter_rate, Some(0.0));
+        assert_eq!(rf9.targets[0].target_expr.as_deref(), Some("ws_sold_time_sk"));
+        
+        let rf10 = &filters[1];
+        assert_eq!(rf10.targets[0].filter_rows, Some(5718));
+        assert!(rf10.builder.is_none());
+    }
+    
+    #[test]
+    fn test_link_builder_from_plan() {
+        let tree = create_tree();
+        let plan = PhysicalPlan {
+            nodes: vec![PhysicalPlanNode {
+                id: "plan-0".to_string(),
+                name: "PhysicalHashJoin".to_string(),
+                nereids_id: Some(1854),
+                group_id: None,
+                table_name: None,
+                estimated_rows: None,
+                actual_rows: None,
+                attributes: vec![metric(
+                    "RFs",
+                    "[RF10[wp_web_page_sk#49->[ws_web_page_sk#12](ndv/size = 750/1024) , RF11[wp_web_page_sk#49->[ws_web_page_sk#12](ndv/size = 750/1024) ]",
+                    vec![],
+                )],
+                children: vec![],
+                depth: 0,
+                execution_node_ids: vec!["sink".to_string()],
+            }],
+        };
+        
+        let filters = RuntimeFilterParser::build(&tree, Some(&plan), None);
+        let rf10 = filters.iter().find(|f| f.id == "RF10").unwrap();
+        assert_eq!(rf10.source_expr.as_deref(), Some("wp_web_page_sk#49"));
+        
+        let builder = rf10.builder.as_ref().unwrap();
+        assert_eq!(builder.node_id, "sink");
+        assert_eq!(builder.nereids_id, Some(1854));
+        assert_eq!(builder.build_time, Some(15_656));
+        assert_eq!(builder.publish_time, Some(7_161));
+        
+        // RF11 is published but not applied by any scan in this tree
+        let rf11 = filters.iter().find(|f| f.id == "RF11").unwrap();
+        assert!(rf11.targets.is_empty());
+    }
+    
+    #[test]
+    fn test_apply_detail_timeout() {
+        let mut target = RuntimeFilterTarget {
+            node_id: "scan".to_string(),
+            operator_name: "FILE_SCAN_OPERATOR".to_string(),
+            table_name: None,
+            target_expr: None,
+            input_rows: None,
+            filter_rows: None,
+            filter_rate: None,
+            wait_time: None,
+            timeout_limit: None,
+            reached_timeout: false,
+        };
+        
+        RuntimeFilterParser::apply_counter(&mut target, "WaitTime", &metric("RF10 WaitTime", "42.0ms", vec![]));
+        RuntimeFilterParser::apply_counter(&mut target, "WaitTime", &metric("RF10 WaitTime", "94.0ms", vec![]));
+        RuntimeFilterParser::apply_counter(
+            &mut target,
+            "Info",
+            &metric(
+                "RF10 Info",
+                "Consumer: ([id: 10, state: [NOT_READY], type: MINMAX_FILTER], mode: LOCAL, state: NOT_READY, reached_timeout: true, timeout_limit: 5000ms)",
+                vec![],
+            ),
+        );
+        
+        assert_eq!(target.wait_time, Some(94_000_000));
+        assert!(target.reached_timeout);
+        assert_eq!(target.timeout_limit, Some(5_000_000_000));
+    }
+}
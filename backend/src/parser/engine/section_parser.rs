@@ -1,7 +1,7 @@
 //! Section parser for extracting major sections from Doris profile
-//! Handles Summary, ChangedSessionVariables, and MergedProfile sections
+//! Handles Summary, ChangedSessionVariables, MergedProfile and (non-pipeline) Execution Profile sections
 
-use crate::models::ProfileSummary;
+use crate::models::{BackendSchedule, CounterKind, FePhase, LoadType, ProfileFormat, ProfileSummary, ScheduleRpc};
 use crate::parser::error::{ParseError, ParseResult};
 use crate::parser::engine::ValueParser;
 use once_cell::sync::Lazy;
@@ -69,8 +69,10 @@ impl SectionParser {
         
         // Parse Execution Summary separately
         let mut execution_summary = HashMap::new();
+        let mut fe_phases = Vec::new();
         if let Ok(exec_block) = Self::extract_section(text, "Execution Summary:") {
             let exec_lines: Vec<&str> = exec_block.lines().collect();
+            fe_phases = Self::parse_fe_phases(&exec_lines);
             let mut i = 0;
             
             while i < exec_lines.len() {
@@ -86,28 +88,198 @@ impl SectionParser {
             }
         }
         
+        let backend_schedules = Self::parse_backend_schedules(&execution_summary);
+        
         let total_time = fields.get("Total").cloned().unwrap_or_default();
         let total_time_ms = Self::parse_total_time_ms(&total_time);
         
+        // Doris 1.2 names some fields "Query ..." instead of "Profile ..." / "Task ..."
+        let field = |key: &str, legacy_key: &str| {
+            fields.get(key).or_else(|| fields.get(legacy_key)).cloned()
+        };
+        
+        let query_type = field("Task Type", "Query Type");
+        let sql_statement = fields.get("Sql Statement").cloned().unwrap_or_default();
+        let load_type = Self::detect_load_type(query_type.as_deref(), &sql_statement);
+        
+        // Only some FE versions report the query peak, under slightly different names
+        let query_peak_memory = fields.iter()
+            .chain(execution_summary.iter())
+            .find(|(key, _)| key.to_lowercase().contains("peak memory"))
+            .and_then(|(_, value)| ValueParser::parse_memory_to_bytes(value));
+        
         Ok(ProfileSummary {
-            query_id: fields.get("Profile ID").cloned().unwrap_or_default(),
+            query_id: field("Profile ID", "Query ID").unwrap_or_default(),
             start_time: fields.get("Start Time").cloned().unwrap_or_default(),
             end_time: fields.get("End Time").cloned().unwrap_or_default(),
             total_time,
-            query_state: fields.get("Task State").cloned().unwrap_or_default(),
+            query_state: field("Task State", "Query State").unwrap_or_default(),
             doris_version: fields.get("Doris Version").cloned().unwrap_or_default(),
-            sql_statement: fields.get("Sql Statement").cloned().unwrap_or_default(),
-            query_type: fields.get("Task Type").cloned(),
+            sql_statement,
+            query_type,
+            load_type,
             user: fields.get("User").cloned(),
             default_db: fields.get("Default Db").cloned(),
             default_catalog: fields.get("Default Catalog").cloned(),
             session_variables: Vec::new(),
             execution_summary,
+            fe_phases,
+            backend_schedules,
             total_time_ms,
-            query_peak_memory: None,
+            query_peak_memory,
+            format: ProfileFormat::default(),
         })
     }
     
+    /// Tell loads from queries: INSERT and "LOAD LABEL" statements, and LOAD tasks
+    /// without a statement, which are Stream Loads
+    fn detect_load_type(task_type: Option<&str>, sql: &str) -> Option<LoadType> {
+        let sql = sql.trim_start().to_uppercase();
+        if sql.starts_with("INSERT") {
+            Some(LoadType::Insert)
+        } else if sql.starts_with("LOAD LABEL") {
+            Some(LoadType::BrokerLoad)
+        } else if task_type.is_some_and(|t| t.eq_ignore_ascii_case("LOAD")) {
+            Some(LoadType::StreamLoad)
+        } else {
+            None
+        }
+    }
+    
+    /// Build the FE phase tree from the Execution Summary lines by indentation
+    fn parse_fe_phases(lines: &[&str]) -> Vec<FePhase> {
+        let mut roots = Vec::new();
+        let mut stack: Vec<(usize, FePhase)> = Vec::new();
+        let mut plan_child_indent = None;
+        
+        for line in lines {
+            let Some(cap) = EXEC_SUMMARY_LINE_REGEX.captures(line) else {
+                continue;
+            };
+            let name = cap[1].trim().to_string();
+            let value = cap[2].trim().to_string();
+            let mut indent = line.len() - line.trim_start().len();
+            
+            // Doris prints "Nereids Pre Rewrite By Mv Time" one level too shallow,
+            // although it and the Nereids phases after it belong to Plan Time
+            if name.starts_with("Nereids ") {
+                if let (Some(child_indent), Some((root_indent, root))) = (plan_child_indent, stack.first()) {
+                    if root.name == "Plan Time" && indent <= *root_indent {
+                        indent = child_indent;
+                    }
+                }
+            }
+            
+            while stack.last().is_some_and(|(top, _)| *top >= indent) {
+                let (_, phase) = stack.pop().unwrap();
+                match stack.last_mut() {
+                    Some((_, parent)) => parent.children.push(phase),
+                    None => roots.push(phase),
+                }
+            }
+            
+            match stack.first() {
+                None => plan_child_indent = None,
+                Some((_, root)) if stack.len() == 1 && root.name == "Plan Time" => {
+                    plan_child_indent.get_or_insert(indent);
+                }
+                _ => {}
+            }
+            
+            let counter = ValueParser::parse_counter(&value);
+            let duration_ms = counter.value
+                .filter(|_| counter.kind == CounterKind::Time)
+                .map(|ns| ns / 1_000_000.0);
+            stack.push((indent, FePhase { name, value, duration_ms, children: Vec::new() }));
+        }
+        
+        while let Some((_, phase)) = stack.pop() {
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(phase),
+                None => roots.push(phase),
+            }
+        }
+        
+        roots
+    }
+    
+    /// Merge the per-BE Execution Summary counters into one entry per host:
+    /// "Instances Num Per BE: 172.20.56.83:8060:129,172.20.56.85:8060:32",
+    /// "Splits Assignment Weight: {"172.20.56.84:9050":19704}" and
+    /// "Schedule Time Of BE: {"phase1":{"172.20.56.84: 8060":{"RPC Work Time":"15ms",...}}}"
+    fn parse_backend_schedules(execution_summary: &HashMap<String, String>) -> Vec<BackendSchedule> {
+        let mut schedules: Vec<BackendSchedule> = Vec::new();
+        
+        if let Some(value) = execution_summary.get("Instances Num Per BE") {
+            for item in value.split(',') {
+                let Some((address, count)) = item.trim().rsplit_once(':') else {
+                    continue;
+                };
+                if let Ok(count) = count.trim().parse::<u64>() {
+                    Self::backend_entry(&mut schedules, address).instances_num = Some(count);
+                }
+            }
+        }
+        
+        let weights = execution_summary.get("Splits Assignment Weight")
+            .and_then(|value| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(value).ok());
+        for (address, weight) in weights.iter().flatten() {
+            if let Some(weight) = weight.as_u64() {
+                Self::backend_entry(&mut schedules, address).split_weight = Some(weight);
+            }
+        }
+        
+        let phases = execution_summary.get("Schedule Time Of BE")
+            .and_then(|value| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(value).ok());
+        for (phase, backends) in phases.iter().flatten() {
+            let Some(backends) = backends.as_object() else {
+                continue;
+            };
+            for (address, timings) in backends {
+                let rpc = Some(Self::parse_schedule_rpc(timings));
+                match phase.as_str() {
+                    "phase1" => Self::backend_entry(&mut schedules, address).rpc_phase1 = rpc,
+                    "phase2" => Self::backend_entry(&mut schedules, address).rpc_phase2 = rpc,
+                    _ => {}
+                }
+            }
+        }
+        
+        schedules.sort_by(|a, b| a.host.cmp(&b.host));
+        schedules
+    }
+    
+    /// Find or add the entry for the host of "host:port"
+    fn backend_entry<'a>(schedules: &'a mut Vec<BackendSchedule>, address: &str) -> &'a mut BackendSchedule {
+        let host = address.split(':').next().unwrap_or(address).trim();
+        let idx = match schedules.iter().position(|s| s.host == host) {
+            Some(idx) => idx,
+            None => {
+                schedules.push(BackendSchedule { host: host.to_string(), ..Default::default() });
+                schedules.len() - 1
+            }
+        };
+        &mut schedules[idx]
+    }
+    
+    /// Parse the RPC timings of one BE; latencies can be negative when FE and BE clocks differ
+    fn parse_schedule_rpc(timings: &serde_json::Value) -> ScheduleRpc {
+        let time_ms = |key: &str| {
+            let value = timings.get(key)?.as_str()?.trim();
+            match value.strip_prefix('-') {
+                Some(abs) => ValueParser::parse_time_to_ms(abs).map(|ms| -ms),
+                None => ValueParser::parse_time_to_ms(value),
+            }
+        };
+        
+        ScheduleRpc {
+            work_time_ms: time_ms("RPC Work Time"),
+            queue_time_ms: time_ms("RPC Work Queue Time"),
+            latency_to_be_ms: time_ms("RPC Latency From FE To BE"),
+            latency_to_fe_ms: time_ms("RPC Latency From BE To FE"),
+        }
+    }
+    
     /// Parse the ChangedSessionVariables section as JSON
     pub fn parse_session_variables(text: &str) -> ParseResult<Vec<crate::models::SessionVariable>> {
         let start_marker = "ChangedSessionVariables:";
@@ -148,6 +320,21 @@ impl SectionParser {
         Self::extract_section(text, "MergedProfile:")
     }
     
+    /// Extract the "Execution Profile <query_id>:" section of non-pipeline profiles
+    pub fn extract_execution_profile(text: &str) -> ParseResult<String> {
+        Self::extract_section(text, "Execution Profile ")
+    }
+    
+    /// Extract the DetailProfile section (per-backend, per-task counters)
+    pub fn extract_detail_profile(text: &str) -> ParseResult<String> {
+        Self::extract_section(text, "DetailProfile(")
+    }
+    
+    /// Extract the PhysicalPlan tree from the Appendix section
+    pub fn extract_physical_plan(text: &str) -> ParseResult<String> {
+        Self::extract_section(text, "PhysicalPlan:")
+    }
+    
     /// Extract a section from text starting with the given marker
     pub fn extract_section(text: &str, marker: &str) -> ParseResult<String> {
         let start_pos = text.find(marker)
@@ -182,10 +369,10 @@ impl SectionParser {
                 && Self::is_section_header(trimmed) 
             {
                 // Calculate position of this line
-                let mut pos = 0;
-                for j in 0..i {
-                    pos += lines[j].len() + 1; // +1 for newline
-                }
+                let pos: usize = lines.iter()
+                    .take(i)
+                    .map(|l| l.len() + 1) // +1 for newline
+                    .sum();
                 return pos;
             }
         }
@@ -194,13 +381,16 @@ impl SectionParser {
     }
     
     /// Check if a line is a top-level section header
-    fn is_section_header(line: &str) -> bool {
+    pub(crate) fn is_section_header(line: &str) -> bool {
         // These are top-level sections (not indented subsections)
         let headers = [
             "Summary:",
             "Execution Summary:",
             "ChangedSessionVariables:",
             "MergedProfile:",
+            "DetailProfile(",
+            "Appendix:",
+            "Execution Profile ",
             // Note: "Fragments:" is NOT a top-level section - it's inside MergedProfile
         ];
         
@@ -236,6 +426,97 @@ Execution Summary:
         assert_eq!(summary.query_state, "OK");
         assert_eq!(summary.total_time, "1sec240ms");
         assert!(summary.total_time_ms.is_some());
+        assert_eq!(summary.load_type, None);
+        assert_eq!(summary.query_peak_memory, None);
+        
+        let with_peak = profile_text.replace("   - User: root", "   - User: root\n   - Peak Memory: 1.50 GB");
+        let summary = SectionParser::parse_summary(&with_peak).unwrap();
+        assert_eq!(summary.query_peak_memory, Some(1610612736));
+    }
+    
+    #[test]
+    fn test_detect_load_type() {
+        assert_eq!(SectionParser::detect_load_type(Some("LOAD"), "insert into t select * from s"), Some(LoadType::Insert));
+        assert_eq!(SectionParser::detect_load_type(Some("QUERY"), "INSERT OVERWRITE TABLE t SELECT 1"), Some(LoadType::Insert));
+        assert_eq!(
+            SectionParser::detect_load_type(Some("LOAD"), "LOAD LABEL db.l1 (DATA INFILE(\"s3://b/f.csv\") INTO TABLE t) WITH S3 (...)"),
+            Some(LoadType::BrokerLoad)
+        );
+        assert_eq!(SectionParser::detect_load_type(Some("LOAD"), ""), Some(LoadType::StreamLoad));
+        assert_eq!(SectionParser::detect_load_type(Some("QUERY"), "SELECT 1"), None);
+        assert_eq!(SectionParser::detect_load_type(None, "SELECT 1"), None);
+    }
+    
+    #[test]
+    fn test_parse_fe_phases() {
+        let profile_text = r#"Summary:
+   - Profile ID: 37f4f7ab99a741ed-8fd24882055ce279
+Execution Summary:
+   - Workload Group: normal
+   - Plan Time: 368ms
+     - Nereids Analysis Time: 4ms
+     - Nereids Rewrite Time: 17ms
+       - Nereids Fold Const By BE Time: 0ms
+   - Nereids Pre Rewrite By Mv Time: N/A
+     - Nereids Optimize Time: N/A
+     - Nereids Translate Time: 337ms
+       - Finalize Scan Node Time: 47ms
+         - Get Splits Time: 43ms
+   - Schedule Time: 33ms
+     - Fragment RPC Phase1 Time: 20ms
+     - Fragment Compressed Size: 542.34 KB
+"#;
+        
+        let summary = SectionParser::parse_summary(profile_text).unwrap();
+        let names: Vec<&str> = summary.fe_phases.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Workload Group", "Plan Time", "Schedule Time"]);
+        
+        let plan = &summary.fe_phases[1];
+        assert_eq!(plan.duration_ms, Some(368.0));
+        let plan_children: Vec<&str> = plan.children.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(plan_children, vec![
+            "Nereids Analysis Time",
+            "Nereids Rewrite Time",
+            "Nereids Pre Rewrite By Mv Time",
+            "Nereids Optimize Time",
+            "Nereids Translate Time",
+        ]);
+        assert_eq!(plan.slowest_child().unwrap().name, "Nereids Translate Time");
+        
+        let splits = FePhase::find(&summary.fe_phases, "Get Splits Time").unwrap();
+        assert_eq!(splits.duration_ms, Some(43.0));
+        assert_eq!(FePhase::find(&summary.fe_phases, "Nereids Optimize Time").unwrap().duration_ms, None);
+        assert_eq!(FePhase::find(&summary.fe_phases, "Fragment Compressed Size").unwrap().duration_ms, None);
+        assert_eq!(summary.fe_phases[2].children.len(), 2);
+        
+        // The flat map is kept for existing consumers
+        assert_eq!(summary.execution_summary.get("Get Splits Time").map(String::as_str), Some("43ms"));
+    }
+    
+    #[test]
+    fn test_parse_backend_schedules() {
+        let profile_text = r#"Summary:
+   - Profile ID: 37f4f7ab99a741ed-8fd24882055ce279
+Execution Summary:
+   - Schedule Time Of BE: {"phase1":{"172.20.56.84: 8060":{"RPC Work Time":"15ms","RPC Latency From FE To BE":"1ms","RPC Work Queue Time":"0ms","RPC Latency From BE To FE":"3ms"},"172.20.56.83: 8060":{"RPC Work Time":"17ms","RPC Latency From FE To BE":"3ms","RPC Work Queue Time":"0ms","RPC Latency From BE To FE":"-1ms"}}}
+   - Instances Num Per BE: 172.20.56.83:8060:129,172.20.56.84:8060:33
+   - Splits Assignment Weight: {"172.20.56.84:9050":19704,"172.20.56.83:9050":21600}
+"#;
+        
+        let summary = SectionParser::parse_summary(profile_text).unwrap();
+        assert_eq!(summary.backend_schedules.len(), 2);
+        
+        let be = &summary.backend_schedules[0];
+        assert_eq!(be.host, "172.20.56.83");
+        assert_eq!(be.instances_num, Some(129));
+        assert_eq!(be.split_weight, Some(21600));
+        let rpc = be.rpc_phase1.as_ref().unwrap();
+        assert_eq!(rpc.work_time_ms, Some(17.0));
+        assert_eq!(rpc.latency_to_fe_ms, Some(-1.0));
+        assert_eq!(rpc.latency_ms(), 3.0);
+        assert!(be.rpc_phase2.is_none());
+        
+        assert_eq!(summary.backend_schedules[1].instances_num, Some(33));
     }
     
     #[test]
@@ -273,5 +554,29 @@ MergedProfile:
         assert!(merged.contains("Fragment 0:"), "Should contain Fragment 0, got: {}", merged);
         assert!(merged.contains("Pipeline 0"), "Should contain Pipeline 0");
     }
+    
+    #[test]
+    fn test_extract_detail_profile() {
+        let text = r#"MergedProfile:
+     Fragments:
+       Fragment 0:
+DetailProfile(test-123):
+  Fragments:
+    Fragment 0:
+      Pipeline 0(host=TNetworkAddress(hostname:127.0.0.1, port:9050)):
+  LoadChannels:
+
+Appendix:
+
+PhysicalPlan:
+"#;
+        
+        let merged = SectionParser::extract_merged_profile(text).unwrap();
+        assert!(!merged.contains("DetailProfile"));
+        
+        let detail = SectionParser::extract_detail_profile(text).unwrap();
+        assert!(detail.contains("Pipeline 0(host="));
+        assert!(!detail.contains("PhysicalPlan"));
+    }
 }
 
@@ -8,14 +8,24 @@ use crate::parser::engine::OperatorParser;
 use crate::constants::thresholds;
 use std::collections::HashMap;
 
+/// Operator of a non-pipeline profile, with its children given by the profile nesting
+#[derive(Debug, Clone)]
+pub struct NestedOperator {
+    pub parsed: ParsedOperator,
+    pub fragment_id: String,
+    /// Indexes of the child operators in the same list
+    pub children: Vec<usize>,
+}
+
 pub struct TreeBuilder;
 
 impl TreeBuilder {
-    /// Build ExecutionTree from fragments
+    /// Build ExecutionTree from fragments; None when no operator was parsed,
+    /// e.g. for a profile cut off right after its Fragment / Pipeline headers
     pub fn build_from_fragments(
         fragments: &[Fragment],
         summary: &ProfileSummary,
-    ) -> ExecutionTree {
+    ) -> Option<ExecutionTree> {
         let mut nodes = Vec::new();
         let mut node_map: HashMap<String, usize> = HashMap::new();
         
@@ -28,7 +38,7 @@ impl TreeBuilder {
                     let node = Self::create_tree_node(
                         &parsed_op,
                         &fragment.id,
-                        &pipeline.id,
+                        Some(&pipeline.id),
                     );
                     
                     let node_id = node.id.clone();
@@ -38,15 +48,66 @@ impl TreeBuilder {
             }
         }
         
+        if nodes.is_empty() {
+            return None;
+        }
+        
+        Self::share_join_attributes(&mut nodes);
+        
         // Second pass: establish connections
         Self::connect_nodes(&mut nodes, &node_map);
         
         // Third pass: calculate metrics and identify hotspots
         Self::calculate_metrics(&mut nodes, summary);
         
-        // Find root node (usually RESULT_SINK)
+        // Find root node (RESULT_SINK, or the table sink of a load)
         let root = Self::find_root_node(&nodes);
         
+        Some(ExecutionTree { root, nodes })
+    }
+    
+    /// Build ExecutionTree from operators whose children are already known,
+    /// as in non-pipeline profiles where operators are nested by indentation
+    pub fn build_from_nested(
+        operators: &[NestedOperator],
+        summary: &ProfileSummary,
+    ) -> ExecutionTree {
+        let mut nodes: Vec<ExecutionTreeNode> = operators.iter()
+            .map(|op| Self::create_tree_node(&op.parsed, &op.fragment_id, None))
+            .collect();
+        
+        for (idx, op) in operators.iter().enumerate() {
+            nodes[idx].children = op.children.iter()
+                .map(|&child| nodes[child].id.clone())
+                .collect();
+        }
+        
+        // An EXCHANGE receives from the DATA_STREAM_SINK whose dest_id is its plan node id
+        let mut sink_nodes_by_dest: HashMap<i32, usize> = HashMap::new();
+        for (idx, op) in operators.iter().enumerate() {
+            if let Some(dest_id) = op.parsed.dest_id {
+                sink_nodes_by_dest.insert(dest_id, idx);
+            }
+        }
+        for idx in 0..nodes.len() {
+            if nodes[idx].node_type != NodeType::Exchange {
+                continue;
+            }
+            let sink = nodes[idx].plan_node_id.and_then(|id| sink_nodes_by_dest.get(&id));
+            if let Some(&sink_idx) = sink {
+                let sink_id = nodes[sink_idx].id.clone();
+                nodes[idx].children.push(sink_id);
+            }
+        }
+        
+        let node_map: HashMap<String, usize> = nodes.iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.clone(), i))
+            .collect();
+        Self::update_depths(&mut nodes, &node_map);
+        Self::calculate_metrics(&mut nodes, summary);
+        
+        let root = Self::find_root_node(&nodes);
         ExecutionTree { root, nodes }
     }
     
@@ -54,7 +115,7 @@ impl TreeBuilder {
     fn create_tree_node(
         parsed: &ParsedOperator,
         fragment_id: &str,
-        pipeline_id: &str,
+        pipeline_id: Option<&str>,
     ) -> ExecutionTreeNode {
         let node_type = Self::determine_node_type(&parsed.name);
         
@@ -62,12 +123,9 @@ impl TreeBuilder {
         let exec_time = OperatorParser::get_exec_time_ns(parsed);
         let rows = OperatorParser::get_rows_produced(parsed);
         let input_rows = OperatorParser::get_input_rows(parsed);
-        let memory = parsed.common_counters.iter()
-            .find(|item| item.key == "MemoryUsagePeak")
-            .and_then(|item| {
-                let agg = ValueParser::parse_aggregated(&item.value);
-                agg.sum.or(agg.avg).map(|v| v as u64)
-            });
+        let memory = OperatorParser::find_counter(&parsed.common_counters, "MemoryUsagePeak")
+            .and_then(CounterValue::total)
+            .map(|v| v as u64);
         
         // Extract exec time stats (avg, max, min)
         let exec_time_item = parsed.common_counters.iter()
@@ -76,23 +134,20 @@ impl TreeBuilder {
         let exec_time_raw = exec_time_item
             .and_then(|item| ValueParser::extract_first_value(&item.value));
         
-        let exec_time_agg = exec_time_item
-            .map(|item| ValueParser::parse_aggregated(&item.value));
-        
-        let exec_max_time = exec_time_agg.as_ref()
-            .and_then(|agg| agg.max)
+        let exec_max_time = exec_time_item
+            .and_then(|item| item.counter.max)
             .map(|t| t as u64);
         
-        let exec_min_time = exec_time_agg.as_ref()
-            .and_then(|agg| agg.min)
+        let exec_min_time = exec_time_item
+            .and_then(|item| item.counter.min)
             .map(|t| t as u64);
         
         // Format max/min time for display
         let exec_max_time_raw = exec_max_time
-            .map(|ns| Self::format_time_ns(ns));
+            .map(Self::format_time_ns);
         
         let exec_min_time_raw = exec_min_time
-            .map(|ns| Self::format_time_ns(ns));
+            .map(Self::format_time_ns);
         
         let metrics = OperatorMetrics {
             operator_total_time: exec_time.map(|t| t as u64),
@@ -111,36 +166,21 @@ impl TreeBuilder {
         // Build unique metrics from plan_info and custom_counters
         let mut unique_metrics = HashMap::new();
         for item in &parsed.plan_info {
-            unique_metrics.insert(item.key.clone(), item.value.clone());
+            unique_metrics.insert(item.key.clone(), item.counter.clone());
         }
         if let Some(ref tn) = parsed.table_name {
-            unique_metrics.insert("table_name".to_string(), tn.clone());
-        }
-        // Store nereids_id and dest_id for connection logic
-        if let Some(nid) = parsed.nereids_id {
-            unique_metrics.insert("nereids_id".to_string(), nid.to_string());
-        }
-        if let Some(did) = parsed.dest_id {
-            unique_metrics.insert("dest_id".to_string(), did.to_string());
-        }
-        // Store dest_ids for MULTI_CAST_SINK
-        if !parsed.dest_ids.is_empty() {
-            let dest_ids_str = parsed.dest_ids.iter()
-                .map(|id| id.to_string())
-                .collect::<Vec<_>>()
-                .join(",");
-            unique_metrics.insert("dest_ids".to_string(), dest_ids_str);
-        }
-        // Store source_id for MULTI_CAST_SOURCE
-        if let Some(sid) = parsed.source_id {
-            unique_metrics.insert("source_id".to_string(), sid.to_string());
+            unique_metrics.insert("table_name".to_string(), CounterValue::text(tn.clone()));
         }
         
         // Generate a unique node ID
+        let prefix = match pipeline_id {
+            Some(pipeline_id) => format!("{}-{}", fragment_id, pipeline_id),
+            None => fragment_id.to_string(),
+        };
         let node_id = if let Some(did) = parsed.dest_id {
-            format!("{}-{}-dest{}", fragment_id, pipeline_id, did)
+            format!("{}-dest{}", prefix, did)
         } else {
-            format!("{}-{}-id{}", fragment_id, pipeline_id, parsed.id)
+            format!("{}-id{}", prefix, parsed.id)
         };
         
         ExecutionTreeNode {
@@ -149,13 +189,14 @@ impl TreeBuilder {
             node_type,
             plan_node_id: Some(parsed.id),
             parent_plan_node_id: None,
+            attributes: OperatorParser::attributes(parsed),
             metrics,
             children: Vec::new(),
             depth: 0,
             is_hotspot: false,
             hotspot_severity: HotspotSeverity::None,
             fragment_id: Some(fragment_id.to_string()),
-            pipeline_id: Some(pipeline_id.to_string()),
+            pipeline_id: pipeline_id.map(str::to_string),
             table_name: parsed.table_name.clone(),
             time_percentage: None,
             is_most_consuming: false,
@@ -164,6 +205,25 @@ impl TreeBuilder {
             plan_info: parsed.plan_info.clone(),
             common_counters: parsed.common_counters.clone(),
             custom_counters: parsed.custom_counters.clone(),
+            physical_plan_node_id: None,
+        }
+    }
+    
+    /// Only the probe side of a join prints the join op in its PlanInfo;
+    /// copy the join type to the build sink with the same plan node id
+    fn share_join_attributes(nodes: &mut [ExecutionTreeNode]) {
+        let probes: HashMap<(Option<String>, Option<i32>), OperatorAttributes> = nodes.iter()
+            .filter(|n| matches!(n.node_type, NodeType::HashJoin | NodeType::NestedLoopJoin))
+            .map(|n| ((n.fragment_id.clone(), n.plan_node_id), n.attributes.clone()))
+            .collect();
+        
+        for node in nodes.iter_mut()
+            .filter(|n| matches!(n.node_type, NodeType::HashJoinBuild | NodeType::NestedLoopJoinBuild))
+        {
+            if let Some(probe) = probes.get(&(node.fragment_id.clone(), node.plan_node_id)) {
+                node.attributes.join_type = probe.join_type;
+                node.attributes.join_distribution = probe.join_distribution;
+            }
         }
     }
     
@@ -185,52 +245,46 @@ impl TreeBuilder {
                     .push(idx);
                 
                 // Track EXCHANGE operators by their plan_node_id (non-LOCAL)
-                if node.operator_name.contains("EXCHANGE_OPERATOR") 
-                    && !node.operator_name.contains("SINK") 
-                    && !node.operator_name.contains("LOCAL") {
+                if node.node_type == NodeType::Exchange {
                     if let Some(plan_id) = node.plan_node_id {
                         exchange_nodes.insert(plan_id, idx);
                     }
                 }
                 
                 // Track LOCAL_EXCHANGE operators by (fragment, plan_node_id)
-                if node.operator_name.contains("LOCAL_EXCHANGE_OPERATOR") && !node.operator_name.contains("SINK") {
+                if node.node_type == NodeType::LocalExchange {
                     if let Some(plan_id) = node.plan_node_id {
                         local_exchange_nodes.insert((fid.clone(), plan_id), idx);
                     }
                 }
                 
                 // Track LOCAL_EXCHANGE_SINK operators by (fragment, plan_node_id)
-                if node.operator_name.contains("LOCAL_EXCHANGE_SINK") {
+                if node.node_type == NodeType::LocalExchangeSink {
                     if let Some(plan_id) = node.plan_node_id {
                         local_exchange_sink_nodes.insert((fid.clone(), plan_id), idx);
                     }
                 }
                 
                 // Track DATA_STREAM_SINK operators by their dest_id
-                if node.operator_name.contains("DATA_STREAM_SINK") {
-                    if let Some(dest_str) = node.unique_metrics.get("dest_id") {
-                        if let Ok(dest_id) = dest_str.parse::<i32>() {
-                            sink_nodes_by_dest.insert(dest_id, idx);
-                        }
+                if node.node_type == NodeType::DataStreamSink {
+                    if let Some(dest_id) = node.attributes.dest_id {
+                        sink_nodes_by_dest.insert(dest_id, idx);
                     }
                 }
                 
                 // Track operators by (fragment, nereids_id)
-                if let Some(nereids_str) = node.unique_metrics.get("nereids_id") {
-                    if let Ok(nereids_id) = nereids_str.parse::<i32>() {
-                        operators_by_nereids
-                            .entry((fid.clone(), nereids_id))
-                            .or_default()
-                            .push(idx);
-                    }
+                if let Some(nereids_id) = node.attributes.nereids_id {
+                    operators_by_nereids
+                        .entry((fid.clone(), nereids_id))
+                        .or_default()
+                        .push(idx);
                 }
             }
         }
         
         // 1. Connect operators within the same pipeline (sequential chain)
         // First operator's child is the second operator, etc.
-        for ((fid, pid), pipeline_node_indices) in &nodes_by_fragment_pipeline {
+        for pipeline_node_indices in nodes_by_fragment_pipeline.values() {
             let indices: Vec<usize> = pipeline_node_indices.clone();
             
             for i in 0..indices.len() {
@@ -259,7 +313,7 @@ impl TreeBuilder {
                 };
                 
                 // Check if this is a UNION operator (can have multiple SINKs)
-                let is_union = node.operator_name.contains("UNION_OPERATOR");
+                let is_union = node.node_type == NodeType::Union;
                 
                 if let Some(fid) = &node.fragment_id {
                     // Find SINK with matching name AND plan_node_id in a different pipeline
@@ -283,20 +337,18 @@ impl TreeBuilder {
                 // Also try nereids_id matching for cases where name doesn't match exactly
                 // (Skip for UNION since we already handled it above with plan_node_id)
                 if !is_union {
-                    if let Some(nereids_str) = node.unique_metrics.get("nereids_id") {
-                        if let Ok(nereids_id) = nereids_str.parse::<i32>() {
-                            if let Some(fid) = &node.fragment_id {
-                                if let Some(matching_nodes) = operators_by_nereids.get(&(fid.clone(), nereids_id)) {
-                                    for &match_idx in matching_nodes {
-                                        if match_idx != idx 
-                                            && nodes[match_idx].operator_name.contains("SINK")
-                                            && !nodes[match_idx].operator_name.contains("DATA_STREAM_SINK")
-                                            && !nodes[match_idx].operator_name.contains("RESULT_SINK")
-                                            && nodes[match_idx].pipeline_id != node.pipeline_id {
-                                            // Record connection
-                                            sink_connections.push((idx, nodes[match_idx].id.clone()));
-                                            break;
-                                        }
+                    if let Some(nereids_id) = node.attributes.nereids_id {
+                        if let Some(fid) = &node.fragment_id {
+                            if let Some(matching_nodes) = operators_by_nereids.get(&(fid.clone(), nereids_id)) {
+                                for &match_idx in matching_nodes {
+                                    if match_idx != idx 
+                                        && nodes[match_idx].operator_name.contains("SINK")
+                                        && !nodes[match_idx].node_type.is_exchange()
+                                        && !nodes[match_idx].node_type.is_final_sink()
+                                        && nodes[match_idx].pipeline_id != node.pipeline_id {
+                                        // Record connection
+                                        sink_connections.push((idx, nodes[match_idx].id.clone()));
+                                        break;
                                     }
                                 }
                             }
@@ -347,21 +399,18 @@ impl TreeBuilder {
         
         // Track multi-cast sinks and sources
         for (idx, node) in nodes.iter().enumerate() {
-            if node.operator_name.contains("MULTI_CAST_DATA_STREAM_SINK") {
+            if node.node_type == NodeType::MultiCastSink {
                 if let Some(fid) = &node.fragment_id {
-                    // Extract all dest_ids from unique_metrics
-                    let dest_ids = Self::extract_dest_ids_from_metrics(&node.unique_metrics);
+                    let dest_ids = &node.attributes.dest_ids;
                     if !dest_ids.is_empty() {
-                        multi_cast_sinks.insert(fid.clone(), (idx, dest_ids));
+                        multi_cast_sinks.insert(fid.clone(), (idx, dest_ids.clone()));
                     }
                 }
             }
-            if node.operator_name.contains("MULTI_CAST_DATA_STREAM_SOURCE") {
+            if node.node_type == NodeType::MultiCastSource {
                 if let Some(fid) = &node.fragment_id {
-                    if let Some(source_str) = node.unique_metrics.get("source_id") {
-                        if let Ok(source_id) = source_str.parse::<i32>() {
-                            multi_cast_sources.insert((fid.clone(), source_id), idx);
-                        }
+                    if let Some(source_id) = node.attributes.source_id {
+                        multi_cast_sources.insert((fid.clone(), source_id), idx);
                     }
                 }
             }
@@ -394,12 +443,11 @@ impl TreeBuilder {
         
         for (idx, node) in nodes.iter().enumerate() {
             if let (Some(fid), Some(plan_id)) = (&node.fragment_id, node.plan_node_id) {
-                if node.operator_name.contains("INTERSECT_OPERATOR") 
-                    || node.operator_name.contains("EXCEPT_OPERATOR") {
+                if matches!(node.node_type, NodeType::Intersect | NodeType::Except) {
                     set_operators.insert((fid.clone(), plan_id), idx);
-                } else if node.operator_name == "SET_SINK_OPERATOR" {
+                } else if node.node_type == NodeType::SetSink {
                     set_sink_operators.insert((fid.clone(), plan_id), idx);
-                } else if node.operator_name == "SET_PROBE_SINK_OPERATOR" {
+                } else if node.node_type == NodeType::SetProbeSink {
                     set_probe_sink_operators.push((fid.clone(), plan_id, idx));
                 }
             }
@@ -432,19 +480,6 @@ impl TreeBuilder {
         Self::check_multiple_parents(nodes);
     }
     
-    /// Extract dest_ids from node metrics
-    fn extract_dest_ids_from_metrics(metrics: &HashMap<String, String>) -> Vec<i32> {
-        if let Some(dest_ids_str) = metrics.get("dest_ids") {
-            dest_ids_str.split(',')
-                .filter_map(|s| s.trim().parse::<i32>().ok())
-                .collect()
-        } else if let Some(dest_id_str) = metrics.get("dest_id") {
-            vec![dest_id_str.parse::<i32>().unwrap_or(0)]
-        } else {
-            vec![]
-        }
-    }
-    
     /// Format time in nanoseconds to human-readable string
     fn format_time_ns(ns: u64) -> String {
         if ns == 0 {
@@ -513,9 +548,9 @@ impl TreeBuilder {
             .map(|(i, n)| (n.id.clone(), i))
             .collect();
         
-        // Find root (RESULT_SINK)
+        // Find root (RESULT_SINK or a table sink)
         let root_idx = nodes.iter()
-            .position(|n| n.operator_name.contains("RESULT_SINK"))
+            .position(|n| n.node_type.is_final_sink())
             .unwrap_or(0);
         
         // BFS to update depths (use VecDeque for proper FIFO queue)
@@ -596,11 +631,11 @@ impl TreeBuilder {
         }
     }
     
-    /// Find the root node (typically RESULT_SINK)
+    /// Find the root node: RESULT_SINK for queries, the table sink for loads
     fn find_root_node(nodes: &[ExecutionTreeNode]) -> ExecutionTreeNode {
-        // Look for RESULT_SINK first
+        // Look for the final sink first
         for node in nodes {
-            if node.operator_name.contains("RESULT_SINK") {
+            if node.node_type.is_final_sink() {
                 return node.clone();
             }
         }
@@ -620,6 +655,7 @@ impl TreeBuilder {
             node_type: NodeType::Unknown,
             plan_node_id: None,
             parent_plan_node_id: None,
+            attributes: OperatorAttributes::default(),
             metrics: OperatorMetrics::default(),
             children: Vec::new(),
             depth: 0,
@@ -635,118 +671,73 @@ impl TreeBuilder {
             plan_info: Vec::new(),
             common_counters: Vec::new(),
             custom_counters: Vec::new(),
+            physical_plan_node_id: None,
         })
     }
     
     /// Determine node type from operator name
     fn determine_node_type(name: &str) -> NodeType {
         let upper = name.to_uppercase();
-        
-        // Check for MULTI_CAST operators first (before generic checks)
-        if upper.contains("MULTI_CAST_DATA_STREAM_SINK") {
-            return NodeType::MultiCastSink;
-        }
-        if upper.contains("MULTI_CAST_DATA_STREAM_SOURCE") {
-            return NodeType::MultiCastSource;
-        }
-        
-        // Check for SET operators
-        if upper.contains("SET_SINK_OPERATOR") {
-            return NodeType::SetSink;
-        }
-        if upper.contains("SET_PROBE_SINK_OPERATOR") {
-            return NodeType::SetProbeSink;
-        }
-        if upper.contains("INTERSECT_OPERATOR") {
-            return NodeType::Intersect;
-        }
-        if upper.contains("EXCEPT_OPERATOR") {
-            return NodeType::Except;
-        }
-        
-        if upper.contains("SCAN") {
-            if upper.contains("OLAP") {
-                NodeType::OlapScan
-            } else {
-                NodeType::OlapScan // FILE_SCAN treated as OlapScan for now
-            }
-        } else if upper.contains("EXCHANGE") {
-            NodeType::Exchange
-        } else if upper.contains("HASH_JOIN") {
-            NodeType::HashJoin
-        } else if upper.contains("AGGREGATE") || upper.contains("AGGREGATION") {
-            NodeType::Aggregate
-        } else if upper.contains("SORT") {
-            NodeType::Sort
-        } else if upper.contains("LIMIT") {
-            NodeType::Limit
-        } else if upper.contains("PROJECT") {
-            NodeType::Project
-        } else if upper.contains("FILTER") {
-            NodeType::Filter
-        } else if upper.contains("UNION") {
-            NodeType::Union
-        } else if upper.contains("RESULT_SINK") {
-            NodeType::ResultSink
-        } else if upper.contains("DATA_STREAM_SINK") || upper.contains("STREAM_SINK") {
-            NodeType::DataStreamSink
-        } else {
-            NodeType::Unknown
-        }
-    }
-    
-    /// Reconstruct pipeline text from Pipeline struct (for re-parsing)
-    fn reconstruct_pipeline_text(pipeline: &Pipeline) -> String {
-        let mut text = format!("{}:\n", pipeline.id);
-        
-        for (k, v) in &pipeline.metrics {
-            text.push_str(&format!("   - {}: {}\n", k, v));
+        let base = upper.strip_suffix("_OPERATOR").unwrap_or(&upper);
+        // Spilling variants behave like the operator they wrap
+        let base = base.strip_prefix("PARTITIONED_")
+            .or_else(|| base.strip_prefix("SPILL_"))
+            .unwrap_or(base);
+        
+        match base {
+            "OLAP_SCAN" => NodeType::OlapScan,
+            "FILE_SCAN" => NodeType::FileScan,
+            // External databases, including the legacy MySQL / ODBC scans
+            "JDBC_SCAN" | "MYSQL_SCAN" | "ODBC_SCAN" => NodeType::JdbcScan,
+            "ES_SCAN" | "ES_HTTP_SCAN" => NodeType::EsScan,
+            "SCHEMA_SCAN" => NodeType::SchemaScan,
+            "META_SCAN" => NodeType::MetaScan,
+            "GROUP_COMMIT_SCAN" => NodeType::GroupCommitScan,
+            "DATA_GEN_SCAN" | "DATA_GEN_FUNCTION_SCAN" => NodeType::DataGenScan,
+            "EMPTY_SET" | "EMPTYSET" => NodeType::EmptySet,
+            "EXCHANGE" => NodeType::Exchange,
+            "LOCAL_EXCHANGE" => NodeType::LocalExchange,
+            "LOCAL_EXCHANGE_SINK" => NodeType::LocalExchangeSink,
+            "HASH_JOIN" => NodeType::HashJoin,
+            "HASH_JOIN_SINK" => NodeType::HashJoinBuild,
+            // Doris 2.1 prints nested loop joins as CROSS_JOIN
+            "NESTED_LOOP_JOIN" | "NESTED_LOOP_JOIN_PROBE" | "CROSS_JOIN" => NodeType::NestedLoopJoin,
+            "NESTED_LOOP_JOIN_BUILD_SINK" | "NESTED_LOOP_JOIN_SINK" | "CROSS_JOIN_SINK" => NodeType::NestedLoopJoinBuild,
+            "AGGREGATION" | "AGGREGATE" => NodeType::Aggregate,
+            "AGGREGATION_SINK" => NodeType::AggregateSink,
+            "STREAMING_AGGREGATION" => NodeType::StreamingAggregate,
+            "DISTINCT_STREAMING_AGGREGATION" => NodeType::DistinctStreamingAggregate,
+            "SORT" | "TOP_N" => NodeType::Sort,
+            "SORT_SINK" => NodeType::SortSink,
+            "LOCAL_MERGE_SORT_SOURCE" | "LOCAL_MERGE_SORT" => NodeType::LocalMergeSort,
+            "PARTITION_SORT" => NodeType::PartitionSort,
+            "PARTITION_SORT_SINK" => NodeType::PartitionSortSink,
+            "ANALYTIC_EVAL" => NodeType::AnalyticEval,
+            "ANALYTIC_EVAL_SINK" => NodeType::AnalyticEvalSink,
+            "TABLE_FUNCTION" => NodeType::TableFunction,
+            "REPEAT" => NodeType::Repeat,
+            "ASSERT_NUM_ROWS" => NodeType::AssertNumRows,
+            "CACHE_SOURCE" => NodeType::CacheSource,
+            "LIMIT" => NodeType::Limit,
+            "PROJECT" => NodeType::Project,
+            "SELECT" | "FILTER" => NodeType::Filter,
+            "UNION" => NodeType::Union,
+            "UNION_SINK" => NodeType::UnionSink,
+            "SET_SINK" => NodeType::SetSink,
+            "SET_PROBE_SINK" => NodeType::SetProbeSink,
+            "INTERSECT" => NodeType::Intersect,
+            "EXCEPT" => NodeType::Except,
+            "RESULT_SINK" => NodeType::ResultSink,
+            "DATA_STREAM_SINK" => NodeType::DataStreamSink,
+            "MULTI_CAST_DATA_STREAM_SINK" => NodeType::MultiCastSink,
+            "MULTI_CAST_DATA_STREAM_SOURCE" => NodeType::MultiCastSource,
+            "RESULT_FILE_SINK" => NodeType::ResultFileSink,
+            // Sinks ending a load or export: "OLAP_TABLE_SINK_V2", "GROUP_COMMIT_BLOCK_SINK",
+            // "HIVE_TABLE_SINK", "ICEBERG_TABLE_SINK", ...
+            _ if base.starts_with("OLAP_TABLE_SINK") || base.starts_with("GROUP_COMMIT") => NodeType::OlapTableSink,
+            _ if base.ends_with("TABLE_SINK") => NodeType::ExternalTableSink,
+            _ => NodeType::Unknown,
         }
-        
-        for op in &pipeline.operators {
-            // Reconstruct operator header based on type
-            if op.name.contains("DATA_STREAM_SINK") {
-                // DATA_STREAM_SINK_OPERATOR(dest_id=X):
-                if let Some(dest_id) = op.metrics.get("dest_id") {
-                    text.push_str(&format!("   {}(dest_id={}):\n", op.name, dest_id));
-                } else {
-                    text.push_str(&format!("   {}:\n", op.name));
-                }
-            } else if op.name.contains("LOCAL_EXCHANGE") {
-                // LOCAL_EXCHANGE_OPERATOR(type)(id=X):
-                if let Some(exchange_type) = op.metrics.get("exchange_type") {
-                    text.push_str(&format!("   {}({})(id={}):\n", op.name, exchange_type, op.id));
-                } else {
-                    text.push_str(&format!("   {}(id={}):\n", op.name, op.id));
-                }
-            } else {
-                // Standard format: OPERATOR(nereids_id=X)(id=Y):
-                // Or alternate format: OPERATOR (id=X. nereids_id=Y. ...)
-                if let Some(nereids_id) = op.metrics.get("nereids_id") {
-                    // Check if there's table_name
-                    if let Some(table_name) = op.metrics.get("table_name") {
-                        text.push_str(&format!("   {}(nereids_id={})(id={}, table name = {}):\n", 
-                            op.name, nereids_id, op.id, table_name));
-                    } else {
-                        text.push_str(&format!("   {}(nereids_id={})(id={}):\n", 
-                            op.name, nereids_id, op.id));
-                    }
-                } else {
-                    text.push_str(&format!("   {}(id={}):\n", op.name, op.id));
-                }
-            }
-            
-            text.push_str("     CommonCounters:\n");
-            for (k, v) in &op.metrics {
-                // Skip metadata fields that were already in the header
-                if k == "dest_id" || k == "nereids_id" || k == "exchange_type" || k == "table_name" {
-                    continue;
-                }
-                text.push_str(&format!("        - {}: {}\n", k, v));
-            }
-        }
-        
-        text
     }
 }
 
@@ -757,11 +748,36 @@ mod tests {
     #[test]
     fn test_determine_node_type() {
         assert_eq!(TreeBuilder::determine_node_type("OLAP_SCAN_OPERATOR"), NodeType::OlapScan);
-        assert_eq!(TreeBuilder::determine_node_type("FILE_SCAN_OPERATOR"), NodeType::OlapScan);
+        assert_eq!(TreeBuilder::determine_node_type("FILE_SCAN_OPERATOR"), NodeType::FileScan);
+        assert_eq!(TreeBuilder::determine_node_type("JDBC_SCAN_OPERATOR"), NodeType::JdbcScan);
+        assert_eq!(TreeBuilder::determine_node_type("SCHEMA_SCAN_OPERATOR"), NodeType::SchemaScan);
         assert_eq!(TreeBuilder::determine_node_type("HASH_JOIN_OPERATOR"), NodeType::HashJoin);
+        assert_eq!(TreeBuilder::determine_node_type("HASH_JOIN_SINK_OPERATOR"), NodeType::HashJoinBuild);
+        assert_eq!(TreeBuilder::determine_node_type("PARTITIONED_HASH_JOIN_SINK_OPERATOR"), NodeType::HashJoinBuild);
+        assert_eq!(TreeBuilder::determine_node_type("NESTED_LOOP_JOIN_PROBE_OPERATOR"), NodeType::NestedLoopJoin);
+        assert_eq!(TreeBuilder::determine_node_type("CROSS_JOIN_SINK_OPERATOR"), NodeType::NestedLoopJoinBuild);
         assert_eq!(TreeBuilder::determine_node_type("AGGREGATION_OPERATOR"), NodeType::Aggregate);
+        assert_eq!(TreeBuilder::determine_node_type("STREAMING_AGGREGATION_OPERATOR"), NodeType::StreamingAggregate);
+        assert_eq!(
+            TreeBuilder::determine_node_type("DISTINCT_STREAMING_AGGREGATION_OPERATOR"),
+            NodeType::DistinctStreamingAggregate
+        );
         assert_eq!(TreeBuilder::determine_node_type("SORT_OPERATOR"), NodeType::Sort);
+        assert_eq!(TreeBuilder::determine_node_type("LOCAL_MERGE_SORT_SOURCE_OPERATOR"), NodeType::LocalMergeSort);
+        assert_eq!(TreeBuilder::determine_node_type("PARTITION_SORT_SINK_OPERATOR"), NodeType::PartitionSortSink);
+        assert_eq!(TreeBuilder::determine_node_type("ANALYTIC_EVAL_OPERATOR"), NodeType::AnalyticEval);
+        assert_eq!(TreeBuilder::determine_node_type("TABLE_FUNCTION_OPERATOR"), NodeType::TableFunction);
+        assert_eq!(TreeBuilder::determine_node_type("REPEAT_OPERATOR"), NodeType::Repeat);
+        assert_eq!(TreeBuilder::determine_node_type("ASSERT_NUM_ROWS_OPERATOR"), NodeType::AssertNumRows);
+        assert_eq!(TreeBuilder::determine_node_type("SELECT_OPERATOR"), NodeType::Filter);
         assert_eq!(TreeBuilder::determine_node_type("EXCHANGE_OPERATOR"), NodeType::Exchange);
+        assert_eq!(TreeBuilder::determine_node_type("LOCAL_EXCHANGE_SINK_OPERATOR"), NodeType::LocalExchangeSink);
+        assert_eq!(TreeBuilder::determine_node_type("DATA_STREAM_SINK_OPERATOR"), NodeType::DataStreamSink);
         assert_eq!(TreeBuilder::determine_node_type("RESULT_SINK_OPERATOR"), NodeType::ResultSink);
+        assert_eq!(TreeBuilder::determine_node_type("OLAP_TABLE_SINK_V2_OPERATOR"), NodeType::OlapTableSink);
+        assert_eq!(TreeBuilder::determine_node_type("GROUP_COMMIT_BLOCK_SINK_OPERATOR"), NodeType::OlapTableSink);
+        assert_eq!(TreeBuilder::determine_node_type("ICEBERG_TABLE_SINK_OPERATOR"), NodeType::ExternalTableSink);
+        assert_eq!(TreeBuilder::determine_node_type("RESULT_FILE_SINK_OPERATOR"), NodeType::ResultFileSink);
+        assert_eq!(TreeBuilder::determine_node_type("SOME_NEW_OPERATOR"), NodeType::Unknown);
     }
 }
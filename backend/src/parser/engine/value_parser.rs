@@ -1,6 +1,7 @@
 //! Value parser for Doris profile metrics
 //! Handles parsing of time, memory, count, and aggregated value formats
 
+use crate::models::{CounterKind, CounterValue};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -19,20 +20,30 @@ static COUNT_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(\d+(?:\.\d+)?)\s*([KMB])?\s*(?:\((\d+)\))?").unwrap()
 });
 
-/// Regex for parsing aggregated metrics like "avg 95.241us, max 95.241us, min 95.241us"
-static AGGREGATED_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(sum|avg|max|min)\s+([^,]+)").unwrap()
+/// Regex for time values made of unit parts like "1hour2min", "1sec240ms", "835.207ms",
+/// or the short units of non-pipeline profiles like "1m2s", "2s912ms"
+static TIME_VALUE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\d+(?:\.\d+)?(?:hour|min|sec|ms|us|ns|h|m|s))+$").unwrap()
 });
 
-/// Parsed aggregated metric with sum/avg/max/min values
-#[derive(Debug, Clone, Default)]
-pub struct AggregatedValue {
-    pub sum: Option<i64>,
-    pub avg: Option<i64>,
-    pub max: Option<i64>,
-    pub min: Option<i64>,
-    pub raw: String,
-}
+static TIME_PART_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d+(?:\.\d+)?)(hour|min|sec|ms|us|ns|h|m|s)").unwrap()
+});
+
+/// Regex for byte values; Doris always prints a space before the unit
+static BYTES_VALUE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d+(?:\.\d+)?)\s+(B|KB|MB|GB|TB|PB)$").unwrap()
+});
+
+/// Regex for count values like "1", "-2", "183.75K (183750)"
+static COUNT_VALUE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(-?\d+(?:\.\d+)?)([KMB])?(?:\s*\((-?\d+)\))?$").unwrap()
+});
+
+/// Regex for ratio values like "12.5%"
+static RATIO_VALUE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(-?\d+(?:\.\d+)?)\s*%$").unwrap()
+});
 
 /// Value parser utility
 pub struct ValueParser;
@@ -150,38 +161,173 @@ impl ValueParser {
         trimmed.parse::<i64>().ok()
     }
     
-    /// Parse an aggregated metric string
-    /// Format: "avg 95.241us, max 95.241us, min 95.241us" or "sum 1, avg 1, max 1, min 1"
-    pub fn parse_aggregated(value_str: &str) -> AggregatedValue {
-        let mut result = AggregatedValue {
-            raw: value_str.to_string(),
-            ..Default::default()
-        };
-        
-        for caps in AGGREGATED_REGEX.captures_iter(value_str) {
-            let stat_type = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-            let value_part = caps.get(2).map(|m| m.as_str().trim()).unwrap_or("");
-            
-            // Try to parse as time first, then as count
-            let parsed_value = Self::parse_time_to_ns(value_part)
-                .or_else(|| Self::parse_count(value_part));
+    /// Parse a metric value into a typed counter
+    /// Supports single values ("835.207ms", "2.24 MB", "183.75K (183750)", "12.5%", "N/A")
+    /// and aggregated values ("sum 1, avg 1, max 1, min 1"); anything else is text
+    pub fn parse_counter(value_str: &str) -> CounterValue {
+        let trimmed = value_str.trim();
+        
+        if trimmed == "N/A" {
+            return CounterValue {
+                kind: CounterKind::NotAvailable,
+                ..Default::default()
+            };
+        }
+        
+        if let Some(counter) = Self::parse_aggregated_counter(trimmed) {
+            return counter;
+        }
+        
+        match Self::parse_scalar(trimmed) {
+            Some((kind, value)) => CounterValue {
+                kind,
+                value: Some(value),
+                ..Default::default()
+            },
+            None if trimmed.is_empty() => CounterValue::default(),
+            None => CounterValue::text(trimmed),
+        }
+    }
+    
+    /// Parse "sum X, avg X, max X, min X"; the kind comes from the first numeric part
+    fn parse_aggregated_counter(value_str: &str) -> Option<CounterValue> {
+        let mut counter: Option<CounterValue> = None;
+        
+        for part in value_str.split(", ") {
+            let (stat, value) = part.split_once(' ')?;
+            if !matches!(stat, "sum" | "avg" | "max" | "min") {
+                return None;
+            }
             
-            match stat_type {
-                "sum" => result.sum = parsed_value,
-                "avg" => result.avg = parsed_value,
-                "max" => result.max = parsed_value,
-                "min" => result.min = parsed_value,
-                _ => {}
+            let Some((kind, value)) = Self::parse_scalar(value.trim()) else {
+                continue;
+            };
+            let counter = counter.get_or_insert_with(|| CounterValue {
+                kind,
+                ..Default::default()
+            });
+            if counter.kind != kind {
+                continue;
+            }
+            match stat {
+                "sum" => counter.sum = Some(value),
+                "avg" => counter.avg = Some(value),
+                "max" => counter.max = Some(value),
+                _ => counter.min = Some(value),
+            }
+        }
+        
+        counter
+    }
+    
+    /// Parse a single value into its kind and normalized number
+    fn parse_scalar(value_str: &str) -> Option<(CounterKind, f64)> {
+        if TIME_VALUE_REGEX.is_match(value_str) {
+            let ns: f64 = TIME_PART_REGEX.captures_iter(value_str)
+                .map(|caps| {
+                    let value: f64 = caps[1].parse().unwrap_or(0.0);
+                    let multiplier = match &caps[2] {
+                        "hour" | "h" => 3_600_000_000_000.0,
+                        "min" | "m" => 60_000_000_000.0,
+                        "sec" | "s" => 1_000_000_000.0,
+                        "ms" => 1_000_000.0,
+                        "us" => 1_000.0,
+                        _ => 1.0,
+                    };
+                    value * multiplier
+                })
+                .sum();
+            return Some((CounterKind::Time, ns.round()));
+        }
+        
+        // Zero bytes are printed without a unit ("0.00 ")
+        if value_str == "0.00" {
+            return Some((CounterKind::Bytes, 0.0));
+        }
+        
+        if let Some(caps) = BYTES_VALUE_REGEX.captures(value_str) {
+            let value: f64 = caps[1].parse().ok()?;
+            let exponent = match &caps[2] {
+                "B" => 0,
+                "KB" => 1,
+                "MB" => 2,
+                "GB" => 3,
+                "TB" => 4,
+                _ => 5,
+            };
+            return Some((CounterKind::Bytes, (value * 1024f64.powi(exponent)).trunc()));
+        }
+        
+        if let Some(caps) = RATIO_VALUE_REGEX.captures(value_str) {
+            let value: f64 = caps[1].parse().ok()?;
+            return Some((CounterKind::Ratio, value / 100.0));
+        }
+        
+        if let Some(caps) = COUNT_VALUE_REGEX.captures(value_str) {
+            // Prefer the exact value in parentheses
+            if let Some(exact) = caps.get(3).and_then(|m| m.as_str().parse::<f64>().ok()) {
+                return Some((CounterKind::Count, exact));
             }
+            let value: f64 = caps[1].parse().ok()?;
+            let multiplier = match caps.get(2).map(|m| m.as_str()) {
+                Some("K") => 1_000.0,
+                Some("M") => 1_000_000.0,
+                Some("B") => 1_000_000_000.0,
+                _ => return Some((CounterKind::Count, value)),
+            };
+            return Some((CounterKind::Count, (value * multiplier).round()));
         }
         
-        result
+        None
+    }
+    
+    /// Format a normalized value the way Doris profiles print it, so that
+    /// `parse_counter` reads it back
+    pub fn format_counter(kind: CounterKind, value: f64) -> String {
+        match kind {
+            CounterKind::Time => {
+                if value >= 1_000_000_000.0 {
+                    let secs = (value / 1_000_000_000.0).floor();
+                    let ms = ((value - secs * 1_000_000_000.0) / 1_000_000.0).round();
+                    format!("{}sec{}ms", secs, ms)
+                } else if value >= 1_000_000.0 {
+                    format!("{:.3}ms", value / 1_000_000.0)
+                } else if value >= 1_000.0 {
+                    format!("{:.3}us", value / 1_000.0)
+                } else {
+                    format!("{}ns", value.round())
+                }
+            }
+            CounterKind::Bytes => Self::format_bytes(value),
+            CounterKind::Ratio => format!("{:.2}%", value * 100.0),
+            _ => {
+                if value.fract() != 0.0 {
+                    return format!("{:.3}", value);
+                }
+                let (scaled, suffix) = if value.abs() >= 1_000_000_000.0 {
+                    (value / 1_000_000_000.0, "B")
+                } else if value.abs() >= 1_000_000.0 {
+                    (value / 1_000_000.0, "M")
+                } else if value.abs() >= 1_000.0 {
+                    (value / 1_000.0, "K")
+                } else {
+                    return format!("{}", value);
+                };
+                format!("{:.3}{} ({})", scaled, suffix, value)
+            }
+        }
     }
     
-    /// Parse aggregated metric and return avg value in milliseconds (for time metrics)
-    pub fn parse_aggregated_time_ms(value_str: &str) -> Option<f64> {
-        let agg = Self::parse_aggregated(value_str);
-        agg.avg.map(|ns| ns as f64 / 1_000_000.0)
+    /// Format a byte count as "12.00 MB"
+    pub fn format_bytes(bytes: f64) -> String {
+        let units = ["B", "KB", "MB", "GB", "TB"];
+        let mut scaled = bytes;
+        let mut unit = 0;
+        while scaled >= 1024.0 && unit < units.len() - 1 {
+            scaled /= 1024.0;
+            unit += 1;
+        }
+        format!("{:.2} {}", scaled, units[unit])
     }
     
     /// Extract the first numeric value from a metric string
@@ -234,15 +380,59 @@ mod tests {
     }
     
     #[test]
-    fn test_parse_aggregated() {
-        let agg = ValueParser::parse_aggregated("avg 95.241us, max 95.241us, min 95.241us");
-        assert_eq!(agg.avg, Some(95241));
-        assert_eq!(agg.max, Some(95241));
-        assert_eq!(agg.min, Some(95241));
-        
-        let agg2 = ValueParser::parse_aggregated("sum 1, avg 1, max 1, min 1");
-        assert_eq!(agg2.sum, Some(1));
-        assert_eq!(agg2.avg, Some(1));
+    fn test_parse_counter() {
+        let time = ValueParser::parse_counter("avg 95.241us, max 1sec240ms, min 0ns");
+        assert_eq!(time.kind, CounterKind::Time);
+        assert_eq!(time.avg, Some(95241.0));
+        assert_eq!(time.max, Some(1240000000.0));
+        assert_eq!(time.min, Some(0.0));
+        assert_eq!(time.sum, None);
+        
+        let rows = ValueParser::parse_counter("sum 183.75K (183750), avg 1.298K (1298), max 1, min 0");
+        assert_eq!(rows.kind, CounterKind::Count);
+        assert_eq!(rows.total(), Some(183750.0));
+        assert_eq!(rows.average(), Some(1298.0));
+        
+        let memory = ValueParser::parse_counter("sum 2.24 MB, avg 4.00 KB, max 0.00 , min 128.00 B");
+        assert_eq!(memory.kind, CounterKind::Bytes);
+        assert_eq!(memory.sum, Some(2348810.0));
+        assert_eq!(memory.max, Some(0.0));
+        assert_eq!(memory.min, Some(128.0));
+        
+        assert_eq!(ValueParser::parse_counter("1min2sec").value, Some(62_000_000_000.0));
+        assert_eq!(ValueParser::parse_counter("1m2s37ms").value, Some(62_037_000_000.0));
+        assert_eq!(ValueParser::parse_counter("0.00 ").kind, CounterKind::Bytes);
+        assert_eq!(ValueParser::parse_counter("-2").as_i32(), Some(-2));
+        assert_eq!(ValueParser::parse_counter("1.5B").value, Some(1_500_000_000.0));
+        assert_eq!(ValueParser::parse_counter("12.5%").value, Some(0.125));
+        assert_eq!(ValueParser::parse_counter("N/A").kind, CounterKind::NotAvailable);
+        assert_eq!(ValueParser::parse_counter("INNER_JOIN"), CounterValue::text("INNER_JOIN"));
+        assert_eq!(ValueParser::parse_counter("sum 0.0 /sec, avg 0.0 /sec").kind, CounterKind::Text);
+        assert!(!ValueParser::parse_counter("").is_numeric());
+    }
+    
+    #[test]
+    fn test_counter_json() {
+        let counter = ValueParser::parse_counter("avg 1ms, max 2ms, min 500us");
+        let json = serde_json::to_value(&counter).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "kind": "Time",
+            "avg": 1000000.0,
+            "max": 2000000.0,
+            "min": 500000.0,
+        }));
+    }
+    
+    #[test]
+    fn test_format_counter() {
+        assert_eq!(ValueParser::format_counter(CounterKind::Time, 1_240_000_000.0), "1sec240ms");
+        assert_eq!(ValueParser::format_counter(CounterKind::Time, 835_207_000.0), "835.207ms");
+        assert_eq!(ValueParser::format_counter(CounterKind::Bytes, 12.0 * 1024.0 * 1024.0), "12.00 MB");
+        assert_eq!(ValueParser::format_counter(CounterKind::Count, 1234.0), "1.234K (1234)");
+        for (kind, value) in [(CounterKind::Time, 18_605.0), (CounterKind::Bytes, 4096.0), (CounterKind::Count, 5_000_000.0)] {
+            let counter = ValueParser::parse_counter(&ValueParser::format_counter(kind, value));
+            assert_eq!((counter.kind, counter.value), (kind, Some(value)));
+        }
     }
 }
 
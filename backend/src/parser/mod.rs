@@ -7,5 +7,5 @@ mod test_real_profile;
 
 pub use error::{ParseError, ParseResult};
 pub use composer::ProfileComposer;
-pub use engine::{ValueParser, SectionParser, FragmentParser, OperatorParser, TreeBuilder};
+pub use engine::{ValueParser, SectionParser, FragmentParser, OperatorParser, TreeBuilder, DetailProfileParser, PhysicalPlanParser, RuntimeFilterParser, NonPipelineParser, ProfileValidator};
 
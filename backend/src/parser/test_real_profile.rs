@@ -4,10 +4,10 @@
 mod tests {
     use crate::parser::ProfileComposer;
     use std::fs;
-
+    
     #[test]
     fn test_parse_real_profile() {
-        let profile_text = fs::read_to_string("../test/test-profile.txt")
+        let profile_text = fs::read_to_string("../test/test-profile-external-2.txt")
             .expect("Failed to read test profile");
         
         let mut composer = ProfileComposer::new();
@@ -56,5 +56,103 @@ mod tests {
             );
         }
     }
+    
+    #[test]
+    fn test_parse_real_detail_profile() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        
+        let mut composer = ProfileComposer::new();
+        let profile = composer.parse(&profile_text).expect("Parse failed");
+        
+        let detail = profile.detail_profile.expect("Should have detail profile");
+        assert_eq!(detail.fragments.len(), profile.fragments.len());
+        
+        // Fragment 1 runs on all three backends with 48 tasks per pipeline in total
+        let frag1 = detail.fragments.iter().find(|f| f.id == "Fragment 1").unwrap();
+        assert_eq!(frag1.backends.len(), 3);
+        let pipeline0_tasks: usize = frag1.backends.iter()
+            .flat_map(|b| b.pipelines.iter().filter(|p| p.id == "Pipeline 0"))
+            .map(|p| p.tasks.len())
+            .sum();
+        assert_eq!(pipeline0_tasks, 48);
+        
+        for backend in &frag1.backends {
+            println!("  {} {}: {} pipelines", frag1.id, backend.host, backend.pipelines.len());
+        }
+    }
+    
+    #[test]
+    fn test_parse_real_physical_plan() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        
+        let mut composer = ProfileComposer::new();
+        let profile = composer.parse(&profile_text).expect("Parse failed");
+        
+        let plan = profile.physical_plan.expect("Should have physical plan");
+        assert_eq!(plan.nodes[0].name, "PhysicalResultSink");
+        
+        // Operators are linked by nereids_id
+        let join = plan.nodes.iter().find(|n| n.nereids_id == Some(1854)).unwrap();
+        assert_eq!(join.name, "PhysicalHashJoin");
+        assert!(!join.execution_node_ids.is_empty());
+        
+        // web_sales is scanned twice; each scan links to a different plan node
+        let web_sales: Vec<_> = plan.nodes.iter()
+            .filter(|n| n.table_name.as_deref() == Some("web_sales"))
+            .collect();
+        assert_eq!(web_sales.len(), 2);
+        for scan in &web_sales {
+            assert_eq!(scan.execution_node_ids.len(), 1, "{} should link to one scan", scan.id);
+        }
+        assert_ne!(web_sales[0].execution_node_ids, web_sales[1].execution_node_ids);
+        
+        // Every scan in the plan maps to exactly one scan operator
+        for scan in plan.nodes.iter().filter(|n| n.name == "PhysicalFileScan") {
+            assert_eq!(scan.execution_node_ids.len(), 1, "{:?} should link to one scan", scan.table_name);
+        }
+        
+        let tree = profile.execution_tree.unwrap();
+        for node in &plan.nodes {
+            println!("  {}{} -> {:?}", "  ".repeat(node.depth), node.name, node.execution_node_ids);
+        }
+        let linked = tree.nodes.iter().filter(|n| n.physical_plan_node_id.is_some()).count();
+        println!("  Linked {}/{} execution nodes", linked, tree.nodes.len());
+    }
+    
+    #[test]
+    fn test_parse_real_runtime_filters() {
+        let profile_text = fs::read_to_string("../test/test-profile-external-full.txt")
+            .expect("Failed to read test profile");
+        
+        let mut composer = ProfileComposer::new();
+        let profile = composer.parse(&profile_text).expect("Parse failed");
+        
+        assert_eq!(profile.runtime_filters.len(), 12);
+        
+        // RF10 is published by join 1854 and applied on the web_sales scan
+        let rf10 = profile.runtime_filters.iter().find(|f| f.id == "RF10").unwrap();
+        assert_eq!(rf10.filter_type.as_deref(), Some("min_max"));
+        let builder = rf10.builder.as_ref().expect("RF10 should have a builder");
+        assert_eq!(builder.nereids_id, Some(1854));
+        assert_eq!(builder.operator_name, "HASH_JOIN_SINK_OPERATOR");
+        
+        let target = &rf10.targets[0];
+        assert_eq!(target.table_name.as_deref(), Some("web_sales"));
+        assert_eq!(target.input_rows, Some(315763));
+        assert_eq!(target.filter_rows, Some(5718));
+        assert_eq!(target.wait_time, Some(42_000_000));
+        assert!(!target.reached_timeout);
+        
+        // RF9 filters nothing and is flagged by the diagnostic
+        let rf9 = profile.runtime_filters.iter().find(|f| f.id == "RF9").unwrap();
+        assert_eq!(rf9.targets[0].filter_rows, Some(0));
+        
+        for filter in &profile.runtime_filters {
+            for t in &filter.targets {
+                println!("  {} -> {}: filter rate {:?}, wait {:?}", filter.id, t.node_id, t.filter_rate, t.wait_time);
+            }
+        }
+    }
 }
-
@@ -0,0 +1,3 @@
+pub mod redactor;
+
+pub use redactor::*;
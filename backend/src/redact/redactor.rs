@@ -0,0 +1,846 @@
+//! Profile redaction
+//! Replaces user names, SQL literals, BE addresses and catalog/db/table names with stable
+//! aliases, so a profile can be shared publicly and the redacted copy still analyzes the same.
+//! Column names and plan expressions are kept, they are needed to read the plan; the constants
+//! of plan predicates get the same aliases as the SQL literals.
+//! In the SQL statement every reference after FROM, JOIN, INTO, UPDATE and TABLE is aliased as a
+//! table, also views and CTEs missing from the plan; elsewhere in the statement only names the
+//! profile shows as a table, database or catalog are replaced
+
+use crate::models::*;
+use crate::parser::SectionParser;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex, RegexSet};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Regex for the Summary lines holding identifying values: "- User: root"
+static SUMMARY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\s*-\s+(User|Default Db|Default Catalog|Sql Statement):\s*)(.*)$").unwrap()
+});
+
+/// Regex for the Summary line after a multi-line SQL statement: "- Distributed Plan: N/A"
+static SUMMARY_KEY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*-\s+[A-Z][\w ]*:").unwrap()
+});
+
+/// Regex for BE addresses in DetailProfile and non-pipeline instance headers:
+/// "TNetworkAddress(hostname:172.20.56.83, port:9050)"
+static NETWORK_ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"TNetworkAddress\(hostname:([^,]+),(\s*)port:(\d+)\)").unwrap()
+});
+
+/// Regexes for table references; group 2 is the reference, groups 1 and 3 are kept.
+/// "table name = web_sales", "table_name=date_dim(date_dim)", "VNewOlapScanNode(lineitem) (id=0)",
+/// "PhysicalFileScan[web_sales]", "qualified=iceberg.tpcds.web_sales", "- table: tpcds.tiny.item",
+/// and the partitions of an OLAP scan, named after the table when it is not partitioned:
+/// "partitions=1/1 (store_sales)"
+const TABLE_PATTERNS: &[&str] = &[
+    r"(table name = )([^)]+)()",
+    r"(table_name=)([\w$-]+(?:\([\w$-]+\))?)()",
+    r"^(\s*\w*(?i:scan)\w*\()([\w.$-]+)(\)\s*\(id=)",
+    r"(Physical\w*Scan\w*\[)([^\]]+)(\])",
+    r"(qualified=)([\w.$-]+)()",
+    r"^(\s*-\s+(?i:table):\s*)([\w.$-]+(?:\([\w$-]+\))?)()",
+    r"(partitions=\d+/\d+ \()([\w$,-]+)(\))",
+];
+
+static TABLE_REGEXES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    TABLE_PATTERNS.iter().map(|pattern| Regex::new(pattern).unwrap()).collect()
+});
+
+/// The table regexes in one pass, so most lines are skipped after a single match
+static TABLE_REGEX_SET: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new(TABLE_PATTERNS).unwrap()
+});
+
+/// Regex for SQL tokens: string literals (also unterminated ones of a truncated statement),
+/// quoted identifiers, numbers and identifiers
+static SQL_TOKEN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"'(?:[^'\\]|\\.|'')*(?:'|$)|"(?:[^"\\]|\\.|"")*(?:"|$)|`[^`]*`|\b\d+(?:\.\d+)?\b|[A-Za-z_][A-Za-z0-9_$]*"#).unwrap()
+});
+
+/// SQL keywords followed by a table reference
+const SQL_TABLE_KEYWORDS: &[&str] = &["FROM", "JOIN", "INTO", "UPDATE", "TABLE"];
+
+/// Words after a table keyword that are no table: "INTO OUTFILE", "FROM LATERAL"
+const SQL_NOT_TABLES: &[&str] = &["OUTFILE", "LATERAL", "TABLE", "SELECT", "VALUES"];
+
+/// SQL keywords ending a FROM list
+const SQL_CLAUSE_KEYWORDS: &[&str] = &[
+    "WHERE", "GROUP", "ORDER", "HAVING", "LIMIT", "ON", "USING", "UNION", "INTERSECT", "EXCEPT", "MINUS",
+    "SELECT", "SET", "VALUES", "WINDOW", "QUALIFY", "LEFT", "RIGHT", "INNER", "FULL", "CROSS",
+];
+
+/// SQL keywords that may be directly followed by a subquery or expression in parentheses
+const SQL_SUBQUERY_KEYWORDS: &[&str] = &[
+    "IN", "EXISTS", "FROM", "JOIN", "AS", "AND", "OR", "NOT", "ON", "WHERE", "SELECT", "UNION",
+    "ALL", "ANY", "SOME", "VALUES", "LATERAL", "WHEN", "THEN", "ELSE", "WITH", "INTO", "TABLE",
+];
+
+/// Regex for string literals, as in plan expressions: "d_date < '1999-06-22'"
+static STRING_LITERAL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"'(?:[^'\\]|\\.|'')*(?:'|$)").unwrap()
+});
+
+/// Regex for numbers in plan expressions: compared, computed, or listed as function
+/// arguments or IN values: "(t_hour >= 12)", "CAST((2.000000 / 3.0) AS double)", "k IN (1, 2)".
+/// Group 2 is checked to be a number, "1.5K" is not one. Attributes ("cardinality=300000",
+/// "stats=3,600", "hotValues=(0:0.8)"), ids ("#110", "[1800]") and sizes after a name
+/// ("bloom(1048576)") have no space, "=" or an identifier before the number and are kept
+static PLAN_NUMBER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\s(?:<=>|<=|>=|<>|!=|=|<|>|[-+*/%])\s*|(?:^|[^\w\]=])\(|,\s+)(-?\d[\w.]*)").unwrap()
+});
+
+/// Regex for plain numbers
+static NUMBER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^-?\d+(?:\.\d+)?$").unwrap()
+});
+
+/// Redactor rewrites profile text and parsed profiles with the same aliases.
+///
+/// Aliases are derived from a salted hash of the original value, so the same table or
+/// BE gets the same alias in every profile redacted with the same salt, and two redacted
+/// profiles can still be compared. Hosts become 10.x.y.z addresses and ports stay numeric,
+/// so the address counters parse as before
+pub struct Redactor {
+    salt: String,
+    aliases: HashMap<(RedactionKind, String), String>,
+    /// Originals by alias, to keep aliases unique
+    originals: HashMap<(RedactionKind, String), String>,
+    /// Host names that are not IP addresses, matched wherever they appear
+    hostnames: Vec<String>,
+    /// Shared rather than cloned per line, a cloned Regex starts with empty caches
+    host_regex: Arc<Regex>,
+}
+
+impl Redactor {
+    /// Create a Redactor without salt
+    pub fn new() -> Self {
+        Self::with_salt("")
+    }
+
+    /// Create a Redactor whose aliases are derived with `salt`; without one, aliases of
+    /// well-known names (e.g. TPC-DS tables) can be reversed by hashing candidates
+    pub fn with_salt(salt: &str) -> Self {
+        Self {
+            salt: salt.to_string(),
+            aliases: HashMap::new(),
+            originals: HashMap::new(),
+            hostnames: Vec::new(),
+            host_regex: Arc::new(Self::build_host_regex(&[])),
+        }
+    }
+
+    /// Redact raw profile text line by line, keeping its layout
+    pub fn redact_text(&mut self, text: &str) -> String {
+        for caps in NETWORK_ADDRESS_REGEX.captures_iter(text) {
+            self.register_host(caps[1].trim());
+        }
+        // BE host names may only appear in the Execution Summary, e.g. without a DetailProfile
+        if text.contains("Instances Num Per BE") || text.contains("Splits Assignment Weight") || text.contains("Schedule Time Of BE") {
+            if let Ok(summary) = SectionParser::parse_summary(text) {
+                for backend in &summary.backend_schedules {
+                    self.register_host(&backend.host);
+                }
+            }
+        }
+
+        let mut lines = Vec::new();
+        // First and last line of each SQL statement; it continues up to the next
+        // Summary key or section header
+        let mut statements: Vec<(usize, usize)> = Vec::new();
+        let mut in_sql = false;
+        // Plan expressions: the Appendix, and PlanInfo up to the next line indented no deeper
+        let mut in_appendix = false;
+        let mut plan_info_indent = None;
+        for (idx, line) in text.lines().enumerate() {
+            if in_sql && !SUMMARY_KEY_REGEX.is_match(line) && !SectionParser::is_section_header(line.trim()) {
+                if let Some(statement) = statements.last_mut() {
+                    statement.1 = idx;
+                }
+                lines.push(line.to_string());
+                continue;
+            }
+            in_sql = false;
+
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+            if SectionParser::is_section_header(trimmed) {
+                in_appendix = trimmed.starts_with("Appendix");
+            }
+            if plan_info_indent.is_some_and(|plan_info| indent <= plan_info && !trimmed.is_empty()) {
+                plan_info_indent = None;
+            }
+
+            match SUMMARY_REGEX.captures(line) {
+                Some(caps) if &caps[2] == "Sql Statement" => {
+                    statements.push((idx, idx));
+                    in_sql = true;
+                    lines.push(line.to_string());
+                }
+                Some(caps) => {
+                    let value = self.redact_summary_value(&caps[2], &caps[3]);
+                    lines.push(format!("{}{}", &caps[1], value));
+                }
+                None if in_appendix || plan_info_indent.is_some() => lines.push(self.redact_plan_line(line)),
+                None => {
+                    if trimmed.trim_start_matches("- ") == "PlanInfo" {
+                        plan_info_indent = Some(indent);
+                    }
+                    lines.push(self.redact_line(line));
+                }
+            }
+        }
+
+        // The statement comes first but is redacted last, once every table of the profile is known.
+        // It is redacted as a whole, so literals spanning lines are found, and keeps its lines
+        for (first, last) in statements {
+            let Some(caps) = SUMMARY_REGEX.captures(&lines[first]) else {
+                continue;
+            };
+            let prefix = caps[1].to_string();
+            let sql = std::iter::once(&caps[3])
+                .chain(lines[first + 1..=last].iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let redacted = self.redact_sql(&sql);
+            for (idx, line) in (first..=last).zip(redacted.split('\n')) {
+                lines[idx] = line.to_string();
+            }
+            lines[first] = format!("{}{}", prefix, lines[first]);
+        }
+
+        let mut redacted = lines.join("\n");
+        if text.ends_with('\n') {
+            redacted.push('\n');
+        }
+        redacted
+    }
+
+    /// Redact a parsed profile in place with the same aliases as `redact_text`
+    pub fn redact_profile(&mut self, profile: &mut Profile) {
+        let summary = &profile.summary;
+        let addresses = summary.backend_schedules.iter().map(|b| b.host.clone())
+            .chain(profile.fragments.iter().flat_map(|f| f.backend_addresses.iter().map(|a| Self::split_port(a).0.to_string())))
+            .chain(profile.detail_profile.iter().flat_map(|d| &d.fragments).flat_map(|f| &f.backends).map(|b| Self::split_port(&b.host).0.to_string()))
+            .collect::<Vec<_>>();
+        for host in addresses {
+            self.register_host(&host);
+        }
+
+        for fragment in &mut profile.fragments {
+            for address in &mut fragment.backend_addresses {
+                *address = self.redact_line(address);
+            }
+            for pipeline in &mut fragment.pipelines {
+                pipeline.raw_text = self.redact_text(&pipeline.raw_text);
+                self.redact_counter_map(&mut pipeline.metrics);
+                for operator in &mut pipeline.operators {
+                    operator.name = self.redact_line(&operator.name);
+                    self.redact_counter_map(&mut operator.metrics);
+                }
+            }
+        }
+
+        if let Some(ref mut tree) = profile.execution_tree {
+            self.redact_tree_node(&mut tree.root);
+            for node in &mut tree.nodes {
+                self.redact_tree_node(node);
+            }
+        }
+
+        if let Some(ref mut detail) = profile.detail_profile {
+            for backend in detail.fragments.iter_mut().flat_map(|f| &mut f.backends) {
+                backend.host = self.redact_line(&backend.host);
+                self.redact_metrics(&mut backend.fragment_counters);
+                for task in backend.pipelines.iter_mut().flat_map(|p| &mut p.tasks) {
+                    self.redact_metrics(&mut task.task_counters);
+                    for operator in &mut task.operators {
+                        self.redact_metrics(&mut operator.common_counters);
+                        self.redact_metrics(&mut operator.custom_counters);
+                    }
+                }
+            }
+        }
+
+        if let Some(ref mut plan) = profile.physical_plan {
+            for node in &mut plan.nodes {
+                node.table_name = node.table_name.as_deref().map(|t| self.redact_table_ref(t));
+                self.redact_plan_metrics(&mut node.attributes);
+            }
+        }
+
+        for target in profile.runtime_filters.iter_mut().flat_map(|rf| &mut rf.targets) {
+            target.table_name = target.table_name.as_deref().map(|t| self.redact_table_ref(t));
+        }
+
+        for warning in &mut profile.parse_warnings {
+            warning.message = self.redact_line(&warning.message);
+        }
+
+        let summary = &mut profile.summary;
+        summary.user = summary.user.as_deref().map(|v| self.redact_summary_value("User", v));
+        summary.default_db = summary.default_db.as_deref().map(|v| self.redact_summary_value("Default Db", v));
+        summary.default_catalog = summary.default_catalog.as_deref().map(|v| self.redact_summary_value("Default Catalog", v));
+        for value in summary.execution_summary.values_mut() {
+            *value = self.redact_line(value);
+        }
+        for backend in &mut summary.backend_schedules {
+            backend.host = self.alias(RedactionKind::Host, &backend.host);
+        }
+        summary.sql_statement = self.redact_sql(&summary.sql_statement);
+    }
+
+    /// Every replacement made so far, by kind and original value
+    pub fn mapping(&self) -> Vec<RedactionEntry> {
+        let mut mapping: Vec<RedactionEntry> = self.aliases.iter()
+            .map(|((kind, original), redacted)| RedactionEntry {
+                kind: *kind,
+                original: original.clone(),
+                redacted: redacted.clone(),
+            })
+            .collect();
+        mapping.sort_by(|a, b| (a.kind, &a.original).cmp(&(b.kind, &b.original)));
+        mapping
+    }
+
+    /// Redact the SQL statement: string literals are hashed, numbers become "?", the
+    /// references after FROM, JOIN, INTO, UPDATE and TABLE are aliased as tables (also
+    /// views and CTEs the plan does not show) and other identifiers that are known table,
+    /// database or catalog names get their alias. Column names are kept
+    pub fn redact_sql(&mut self, sql: &str) -> String {
+        let tokens: Vec<regex::Match> = SQL_TOKEN_REGEX.find_iter(sql).collect();
+        let mut redacted = String::with_capacity(sql.len());
+        let mut end = 0;
+        // Open parentheses, true for function calls such as extract(year FROM d_date)
+        let mut calls: Vec<bool> = Vec::new();
+        let mut expect_table = false;
+        // Parenthesis depth of a FROM list, whose comma-separated entries are tables
+        let mut table_list: Option<usize> = None;
+        let mut idx = 0;
+        while idx < tokens.len() {
+            let token = tokens[idx];
+            let gap = &sql[end..token.start()];
+            for (pos, c) in gap.char_indices() {
+                match c {
+                    '(' => {
+                        expect_table = false;
+                        calls.push(pos == 0 && idx > 0 && Self::is_sql_call(tokens[idx - 1].as_str()));
+                    }
+                    ')' => {
+                        calls.pop();
+                        if table_list.is_some_and(|depth| calls.len() < depth) {
+                            table_list = None;
+                        }
+                    }
+                    ',' if table_list == Some(calls.len()) => expect_table = true,
+                    _ => {}
+                }
+            }
+            redacted.push_str(gap);
+            end = token.end();
+            idx += 1;
+
+            let text = token.as_str();
+            let upper = text.to_ascii_uppercase();
+            if expect_table && Self::is_sql_identifier(text) && !SQL_NOT_TABLES.contains(&upper.as_str()) {
+                expect_table = false;
+                // "catalog.db.table"; a name directly followed by "(" is a table function
+                let mut parts = vec![text];
+                while idx < tokens.len() && &sql[end..tokens[idx].start()] == "." && Self::is_sql_identifier(tokens[idx].as_str()) {
+                    parts.push(tokens[idx].as_str());
+                    end = tokens[idx].end();
+                    idx += 1;
+                }
+                if sql[end..].starts_with('(') {
+                    redacted.push_str(&parts.join("."));
+                } else {
+                    redacted.push_str(&self.redact_sql_reference(&parts));
+                }
+                continue;
+            }
+
+            if SQL_TABLE_KEYWORDS.contains(&upper.as_str()) && calls.last() != Some(&true) {
+                expect_table = true;
+                if upper == "FROM" {
+                    table_list = Some(calls.len());
+                }
+            } else if SQL_CLAUSE_KEYWORDS.contains(&upper.as_str()) {
+                expect_table = false;
+                table_list = None;
+            }
+
+            let replacement = match text.chars().next() {
+                Some('\'' | '"') => self.redact_literal(text),
+                Some('`') => {
+                    let inner = &text[1..text.len() - 1];
+                    format!("`{}`", self.known_name(inner).unwrap_or_else(|| inner.to_string()))
+                }
+                Some(c) if c.is_ascii_digit() => "?".to_string(),
+                _ => self.known_name(text).unwrap_or_else(|| text.to_string()),
+            };
+            redacted.push_str(&replacement);
+        }
+        redacted.push_str(&sql[end..]);
+        redacted
+    }
+
+    /// Alias the parts of a SQL table reference "table", "db.table" or "catalog.db.table",
+    /// keeping backquotes; a name already seen in the profile keeps its alias
+    fn redact_sql_reference(&mut self, parts: &[&str]) -> String {
+        let kinds = [RedactionKind::Table, RedactionKind::Database, RedactionKind::Catalog];
+        parts.iter()
+            .enumerate()
+            .map(|(idx, part)| {
+                let (quote, name) = match part.strip_prefix('`').and_then(|p| p.strip_suffix('`')) {
+                    Some(inner) => ("`", inner),
+                    None => ("", *part),
+                };
+                let Some(kind) = kinds.get(parts.len() - 1 - idx) else {
+                    return part.to_string();
+                };
+                let known = self.aliases.get(&(*kind, name.to_string()))
+                    .or_else(|| self.aliases.get(&(*kind, name.to_lowercase())))
+                    .cloned();
+                let alias = known.unwrap_or_else(|| self.alias(*kind, name));
+                format!("{}{}{}", quote, alias, quote)
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Alias of a quoted string literal, also an unterminated one. A literal spanning lines
+    /// is aliased line by line, so the text keeps its lines
+    fn redact_literal(&mut self, token: &str) -> String {
+        let quote = &token[..1];
+        let inner = token[1..].strip_suffix(quote).unwrap_or(&token[1..]);
+        let inner = inner.split('\n')
+            .map(|part| self.alias(RedactionKind::Literal, part))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("{0}{1}{0}", quote, inner)
+    }
+
+    /// Redact a line of PlanInfo or the PhysicalPlan: numbers of expressions become "?"
+    /// like in the SQL statement, then string literals, tables and addresses are redacted
+    fn redact_plan_line(&mut self, line: &str) -> String {
+        self.redact_line(&Self::replace_plan_numbers(line))
+    }
+
+    fn replace_plan_numbers(text: &str) -> String {
+        PLAN_NUMBER_REGEX.replace_all(text, |caps: &Captures| {
+            // Runtime filter sizes are statistics: "(ndv/size = 750/1024)"
+            let is_statistic = text[..caps.get(0).unwrap().start()].ends_with("ndv/size");
+            if NUMBER_REGEX.is_match(&caps[2]) && !is_statistic {
+                format!("{}?", &caps[1])
+            } else {
+                caps[0].to_string()
+            }
+        }).into_owned()
+    }
+
+    /// Redact string literals, table references and addresses in a single line or value.
+    /// Most lines are counters holding none of them, so each replacement is only run
+    /// when a cheap check finds a candidate
+    fn redact_line(&mut self, line: &str) -> String {
+        let mut line = line.to_string();
+        if line.contains('\'') {
+            line = STRING_LITERAL_REGEX.replace_all(&line, |caps: &Captures| self.redact_literal(&caps[0])).into_owned();
+        }
+        for idx in TABLE_REGEX_SET.matches(&line).iter() {
+            line = TABLE_REGEXES[idx].replace_all(&line, |caps: &Captures| {
+                let references = caps[2].split(',')
+                    .map(|reference| self.redact_table_ref(reference))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("{}{}{}", &caps[1], references, &caps[3])
+            }).into_owned();
+        }
+
+        // Hosts are IPv4 addresses, registered host names or TNetworkAddress
+        let has_host = line.contains('.')
+            || line.contains("TNetworkAddress")
+            || self.hostnames.iter().any(|host| line.contains(host.as_str()));
+        if !has_host {
+            return line;
+        }
+        let host_regex = Arc::clone(&self.host_regex);
+        host_regex.replace_all(&line, |caps: &Captures| {
+            if let Some(hostname) = caps.get(1) {
+                return format!(
+                    "TNetworkAddress(hostname:{},{}port:{})",
+                    self.alias(RedactionKind::Host, hostname.as_str().trim()), &caps[2], self.alias(RedactionKind::Port, &caps[3])
+                );
+            }
+
+            let host = &caps[4];
+            if !Self::is_host(host) {
+                return caps[0].to_string();
+            }
+            let host = self.alias(RedactionKind::Host, host);
+            match caps.get(6) {
+                Some(port) => format!("{}:{}{}", host, &caps[5], self.alias(RedactionKind::Port, port.as_str())),
+                None => host,
+            }
+        }).into_owned()
+    }
+
+    /// Redact "table", "table(alias)", "db.table" or "catalog.db.table"
+    fn redact_table_ref(&mut self, reference: &str) -> String {
+        let reference = reference.trim();
+        if let Some((name, alias)) = reference.strip_suffix(')').and_then(|r| r.split_once('(')) {
+            return format!("{}({})", self.redact_table_ref(name), self.alias(RedactionKind::Table, alias));
+        }
+
+        let parts: Vec<&str> = reference.split('.').collect();
+        let kinds = [RedactionKind::Table, RedactionKind::Database, RedactionKind::Catalog];
+        let mut redacted: Vec<String> = parts.iter().rev()
+            .enumerate()
+            .map(|(idx, part)| match kinds.get(idx) {
+                Some(kind) => self.alias(*kind, part),
+                None => part.to_string(),
+            })
+            .collect();
+        redacted.reverse();
+        redacted.join(".")
+    }
+
+    /// Redact a Summary value by its key. Default Db may carry a cluster prefix
+    /// ("default_cluster:tpch"), which is kept
+    fn redact_summary_value(&mut self, key: &str, value: &str) -> String {
+        let value = value.trim();
+        if value.is_empty() || value == "N/A" {
+            return value.to_string();
+        }
+
+        match key {
+            "User" => self.alias(RedactionKind::User, value),
+            "Default Catalog" => self.alias(RedactionKind::Catalog, value),
+            "Default Db" => match value.rsplit_once(':') {
+                Some((cluster, db)) => format!("{}:{}", cluster, self.alias(RedactionKind::Database, db)),
+                None => self.alias(RedactionKind::Database, value),
+            },
+            _ => self.redact_line(value),
+        }
+    }
+
+    fn redact_tree_node(&mut self, node: &mut ExecutionTreeNode) {
+        node.table_name = node.table_name.as_deref().map(|t| self.redact_table_ref(t));
+        self.redact_counter_map(&mut node.unique_metrics);
+        self.redact_plan_metrics(&mut node.plan_info);
+        self.redact_metrics(&mut node.common_counters);
+        self.redact_metrics(&mut node.custom_counters);
+    }
+
+    fn redact_metrics(&mut self, items: &mut [MetricItem]) {
+        for item in items {
+            if Self::is_table_key(&item.key) {
+                item.value = self.redact_table_ref(&item.value);
+                item.counter.text = item.counter.text.as_deref().map(|t| self.redact_table_ref(t));
+            } else {
+                item.value = self.redact_line(&item.value);
+                item.counter.text = item.counter.text.as_deref().map(|t| self.redact_line(t));
+            }
+            self.redact_metrics(&mut item.children);
+        }
+    }
+
+    /// Redact PlanInfo or PhysicalPlan attributes, including the numbers of their expressions
+    fn redact_plan_metrics(&mut self, items: &mut [MetricItem]) {
+        Self::redact_plan_numbers(items);
+        self.redact_metrics(items);
+    }
+
+    fn redact_plan_numbers(items: &mut [MetricItem]) {
+        for item in items {
+            if !Self::is_table_key(&item.key) {
+                item.value = Self::replace_plan_numbers(&item.value);
+                item.counter.text = item.counter.text.as_deref().map(Self::replace_plan_numbers);
+            }
+            Self::redact_plan_numbers(&mut item.children);
+        }
+    }
+
+    fn redact_counter_map(&mut self, counters: &mut HashMap<String, CounterValue>) {
+        for (key, counter) in counters.iter_mut() {
+            counter.text = match counter.text.as_deref() {
+                Some(text) if Self::is_table_key(key) => Some(self.redact_table_ref(text)),
+                Some(text) => Some(self.redact_line(text)),
+                None => None,
+            };
+        }
+    }
+
+    /// An unquoted or backquoted identifier token of a SQL statement
+    fn is_sql_identifier(token: &str) -> bool {
+        token.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '`')
+    }
+
+    /// Whether "(" right after this token opens a function call rather than a subquery
+    fn is_sql_call(token: &str) -> bool {
+        Self::is_sql_identifier(token) && !SQL_SUBQUERY_KEYWORDS.contains(&token.to_ascii_uppercase().as_str())
+    }
+
+    /// Alias of a table, database or catalog name used in the SQL statement
+    fn known_name(&self, name: &str) -> Option<String> {
+        let lower = name.to_lowercase();
+        [RedactionKind::Table, RedactionKind::Database, RedactionKind::Catalog].iter()
+            .find_map(|kind| {
+                self.aliases.get(&(*kind, name.to_string()))
+                    .or_else(|| self.aliases.get(&(*kind, lower.clone())))
+            })
+            .cloned()
+    }
+
+    /// Alias of a value, created on first use. A hash collision with another value of the
+    /// same kind is resolved by hashing again with the next attempt number
+    fn alias(&mut self, kind: RedactionKind, original: &str) -> String {
+        let key = (kind, original.to_string());
+        if let Some(alias) = self.aliases.get(&key) {
+            return alias.clone();
+        }
+
+        let mut attempt = 0;
+        let alias = loop {
+            let hash = self.hash(original, attempt);
+            let alias = match kind {
+                RedactionKind::User => format!("user_{:08x}", hash as u32),
+                RedactionKind::Catalog => format!("catalog_{:08x}", hash as u32),
+                RedactionKind::Database => format!("db_{:08x}", hash as u32),
+                RedactionKind::Table => format!("tbl_{:08x}", hash as u32),
+                RedactionKind::Literal => format!("s_{:08x}", hash as u32),
+                RedactionKind::Host => format!("10.{}.{}.{}", (hash >> 16) & 0xff, (hash >> 8) & 0xff, 1 + hash % 254),
+                RedactionKind::Port => (10000 + hash % 50000).to_string(),
+            };
+            if !self.originals.contains_key(&(kind, alias.clone())) {
+                break alias;
+            }
+            attempt += 1;
+        };
+
+        self.originals.insert((kind, alias.clone()), original.to_string());
+        self.aliases.insert(key, alias.clone());
+        alias
+    }
+
+    /// FNV-1a over salt, value and attempt; stable across Rust versions and platforms
+    fn hash(&self, original: &str, attempt: u32) -> u64 {
+        let bytes = self.salt.bytes()
+            .chain([0])
+            .chain(original.bytes())
+            .chain(attempt.to_le_bytes());
+        bytes.fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+    }
+
+    /// Remember a BE host name so it is also replaced outside of TNetworkAddress
+    fn register_host(&mut self, host: &str) {
+        if host.is_empty() || host.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            return;
+        }
+        if !self.hostnames.iter().any(|h| h == host) {
+            self.hostnames.push(host.to_string());
+            self.host_regex = Arc::new(Self::build_host_regex(&self.hostnames));
+        }
+    }
+
+    /// Regex for TNetworkAddress (groups 1-3) or an IPv4 address / known host name
+    /// with an optional port (groups 4-6): "172.20.56.84:8060", "172.20.56.84: 8060"
+    fn build_host_regex(hostnames: &[String]) -> Regex {
+        let mut hostnames: Vec<String> = hostnames.iter().map(|h| regex::escape(h)).collect();
+        // Longest first, so "be-1.example" does not stop at "be-1"
+        hostnames.sort_by_key(|h| std::cmp::Reverse(h.len()));
+        let hosts = std::iter::once(r"\d{1,3}(?:\.\d{1,3}){3}".to_string())
+            .chain(hostnames)
+            .collect::<Vec<_>>()
+            .join("|");
+
+        Regex::new(&format!(
+            r"{}|\b({})\b(?::(\s?)(\d{{2,5}})\b)?",
+            NETWORK_ADDRESS_REGEX.as_str(), hosts
+        )).unwrap()
+    }
+
+    /// A known host name or an IPv4 address with valid octets
+    fn is_host(value: &str) -> bool {
+        let octets: Vec<&str> = value.split('.').collect();
+        let is_ip = octets.len() == 4 && octets.iter().all(|o| o.parse::<u8>().is_ok());
+        is_ip || !value.chars().all(|c| c.is_ascii_digit() || c == '.')
+    }
+
+    /// Split "host:port" into host and port
+    fn split_port(address: &str) -> (&str, Option<&str>) {
+        match address.rsplit_once(':') {
+            Some((host, port)) if port.trim().chars().all(|c| c.is_ascii_digit()) => (host.trim(), Some(port.trim())),
+            _ => (address.trim(), None),
+        }
+    }
+
+    fn is_table_key(key: &str) -> bool {
+        key.eq_ignore_ascii_case("table") || key == "table_name" || key == "qualified"
+    }
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = r#"Summary:
+   - Profile ID: test-123
+   - User: alice
+   - Default Catalog: iceberg
+   - Default Db: sales
+   - Sql Statement: select * from orders o join `customer` c on o.c_id = c.id
+where c.name = 'Bob''s' and o.total > 12.5
+
+  and o.note <> 'multi
+line'
+   - Is Cached: No
+Execution Summary:
+   - Instances Num Per BE: 172.20.56.83:8060:129,172.20.56.85:8060:32
+   - Splits Assignment Weight: {"172.20.56.83:9050":19704}
+MergedProfile:
+     Fragments:
+       Fragment 0:
+         Pipeline 0(instance_num=1):
+           FILE_SCAN_OPERATOR (id=20. nereids_id=1791. table name = orders):
+           OLAP_SCAN_OPERATOR(nereids_id=967. table_name=customer(c))(id=0):
+             PlanInfo
+                 - table: iceberg.sales.orders
+                 - predicates: (o_date >= '2024-01-01'), (total > 99.5), o_state IN ('CA', 7)
+                 - cardinality=300000, numNodes=3
+                 - partitions=2/3 (p2024,customer)
+             CommonCounters:
+               - ExecTime: avg 1.000ms, max 1.000ms, min 1.000ms
+DetailProfile(test-123):
+      Pipeline 0(host=TNetworkAddress(hostname:be-1.example.com, port:9050)):
+Appendix:
+PhysicalFileScan[orders]@0 ( stats=1,000 actualRows=10, qualified=iceberg.sales.orders )
+PhysicalFilter[12]@1 ( stats=750, predicates=AND[(o_date#3 <= '2024-12-31'),(total#4 < 150)] )
+"#;
+
+    #[test]
+    fn test_redact_text() {
+        let mut redactor = Redactor::with_salt("test");
+        let redacted = redactor.redact_text(PROFILE);
+
+        for original in [
+            "alice", "iceberg", "sales", "orders", "customer", "Bob", "12.5", "multi", "line'", "172.20", "be-1.example.com", "9050",
+            "2024-", "99.5", "'CA'", "150",
+        ] {
+            assert!(!redacted.contains(original), "{} is not redacted:\n{}", original, redacted);
+        }
+
+        let alias = |kind, original: &str| redactor.aliases[&(kind, original.to_string())].clone();
+        let orders = alias(RedactionKind::Table, "orders");
+        let customer = alias(RedactionKind::Table, "customer");
+        let qualified = format!("{}.{}.{}", alias(RedactionKind::Catalog, "iceberg"), alias(RedactionKind::Database, "sales"), orders);
+
+        // "c" is the table alias in the scan header, so it is replaced in the statement as well
+        let c = alias(RedactionKind::Table, "c");
+        assert!(redacted.contains(&format!("from {} o join `{}` {} on", orders, customer, c)));
+        assert!(redacted.contains(&format!("\nwhere {}.name = '{}' and o.total > ?\n", c, alias(RedactionKind::Literal, "Bob''s"))));
+        assert!(redacted.contains(&format!("o.note <> '{}\n{}'\n   - Is Cached: No", alias(RedactionKind::Literal, "multi"), alias(RedactionKind::Literal, "line"))));
+
+        // Plan constants get the literal aliases, attribute values are kept
+        let date = alias(RedactionKind::Literal, "2024-01-01");
+        assert!(redacted.contains(&format!("- predicates: (o_date >= '{}'), (total > ?), o_state IN ('{}', ?)", date, alias(RedactionKind::Literal, "CA"))));
+        assert!(redacted.contains("- cardinality=300000, numNodes=3"));
+        assert!(redacted.contains(&format!("- partitions=2/3 ({},{})", alias(RedactionKind::Table, "p2024"), customer)));
+        assert!(redacted.contains("( stats=750, predicates=AND[(o_date#3 <= 's_"));
+        assert!(redacted.contains(&format!("table name = {}):", orders)));
+        assert!(redacted.contains(&format!("table_name={}({}))(id=0):", customer, alias(RedactionKind::Table, "c"))));
+        assert!(redacted.contains(&format!("- table: {}", qualified)));
+        assert!(redacted.contains(&format!("PhysicalFileScan[{}]@0 ( stats=1,000 actualRows=10, qualified={} )", orders, qualified)));
+
+        // Addresses keep their shape, so the schedule counters still parse
+        let host = alias(RedactionKind::Host, "172.20.56.83");
+        assert!(redacted.contains(&format!("Instances Num Per BE: {}:{}:129,", host, alias(RedactionKind::Port, "8060"))));
+        assert!(redacted.contains(&format!("hostname:{}, port:{}", alias(RedactionKind::Host, "be-1.example.com"), alias(RedactionKind::Port, "9050"))));
+        assert!(host.starts_with("10."));
+        assert_eq!(redacted.lines().count(), PROFILE.lines().count());
+    }
+
+    #[test]
+    fn test_redact_summary_host_names() {
+        // No DetailProfile, so the host names are only known from the Execution Summary
+        let profile = "Summary:\n   - Profile ID: test-456\nExecution Summary:\n   \
+            - Instances Num Per BE: be-01.prod.corp:8060:4,be-02.prod.corp:8060:2\n   \
+            - Splits Assignment Weight: {\"be-03.prod.corp:9050\":19704}\n";
+        let mut redactor = Redactor::new();
+        let redacted = redactor.redact_text(profile);
+
+        for original in ["be-01", "be-02", "be-03", "prod.corp", "8060", "9050"] {
+            assert!(!redacted.contains(original), "{} is not redacted:\n{}", original, redacted);
+        }
+        let host = redactor.alias(RedactionKind::Host, "be-01.prod.corp");
+        assert!(redacted.contains(&format!("Instances Num Per BE: {}:{}:4,", host, redactor.alias(RedactionKind::Port, "8060"))));
+    }
+
+    #[test]
+    fn test_stable_aliases() {
+        let text = Redactor::new().redact_text(PROFILE);
+        assert_eq!(text, Redactor::new().redact_text(PROFILE));
+        assert_ne!(text, Redactor::with_salt("other").redact_text(PROFILE));
+
+        let mut redactor = Redactor::new();
+        redactor.redact_text(PROFILE);
+        let mapping = redactor.mapping();
+        let users: Vec<&RedactionEntry> = mapping.iter().filter(|e| e.kind == RedactionKind::User).collect();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].original, "alice");
+        assert_eq!(mapping[0].kind, RedactionKind::User);
+    }
+
+    #[test]
+    fn test_redact_sql() {
+        let mut redactor = Redactor::new();
+        let orders = redactor.redact_table_ref("tpch.orders");
+        let (db, table) = orders.split_once('.').unwrap();
+
+        let sql = redactor.redact_sql("SELECT count(*) FROM tpch.orders WHERE o_comment = \"x\" LIMIT 10, 'cut");
+        assert_eq!(sql, format!(
+            "SELECT count(*) FROM {}.{} WHERE o_comment = \"{}\" LIMIT ?, '{}'",
+            db, table, redactor.alias(RedactionKind::Literal, "x"), redactor.alias(RedactionKind::Literal, "cut")
+        ));
+    }
+
+    #[test]
+    fn test_redact_sql_table_references() {
+        // Neither the view nor the CTE appears in the plan, they are aliased from the statement
+        let mut redactor = Redactor::new();
+        let sql = redactor.redact_sql(
+            "with recent as (select * from v_orders where extract(year from o_date) = 2024) \
+             select * from recent r, tpch.`lineitem` l join s3(\"uri\" = 'x') f on f.id = l.id where l.id = r.id"
+        );
+        let table = |redactor: &mut Redactor, name: &str| redactor.alias(RedactionKind::Table, name);
+        assert_eq!(sql, format!(
+            "with recent as (select * from {} where extract(year from o_date) = ?) \
+             select * from {} r, {}.`{}` l join s3(\"{}\" = '{}') f on f.id = l.id where l.id = r.id",
+            table(&mut redactor, "v_orders"),
+            table(&mut redactor, "recent"),
+            redactor.alias(RedactionKind::Database, "tpch"),
+            table(&mut redactor, "lineitem"),
+            redactor.alias(RedactionKind::Literal, "uri"),
+            redactor.alias(RedactionKind::Literal, "x"),
+        ));
+
+        let insert = redactor.redact_sql("INSERT INTO sales.daily (d, n) SELECT d, count(*) FROM recent GROUP BY d");
+        assert!(insert.starts_with(&format!("INSERT INTO {}.{} (d, n) SELECT", redactor.alias(RedactionKind::Database, "sales"), table(&mut redactor, "daily"))));
+        assert!(insert.ends_with(&format!("FROM {} GROUP BY d", table(&mut redactor, "recent"))));
+    }
+
+    #[test]
+    fn test_redact_summary_value() {
+        let mut redactor = Redactor::new();
+        let db = redactor.redact_summary_value("Default Db", "default_cluster:tpch");
+        assert!(db.starts_with("default_cluster:db_"));
+        assert_eq!(redactor.redact_summary_value("Default Catalog", "N/A"), "N/A");
+    }
+}
@@ -0,0 +1,570 @@
+//! Plain-text reports for the command-line mode
+
+use crate::models::*;
+use crate::OptimizationAdvisor;
+use std::fmt::Write;
+
+/// Number of operator changes listed in a comparison report
+const MAX_NODE_DIFFS: usize = 10;
+
+/// Number of operators listed in the memory section of an analysis report
+const MAX_MEMORY_OPERATORS: usize = 5;
+
+/// Number of exchange edges listed in the shuffle section of an analysis report
+const MAX_SHUFFLE_EDGES: usize = 10;
+
+/// Number of file scans listed in the external scan section of an analysis report
+const MAX_EXTERNAL_SCANS: usize = 10;
+
+/// Number of file scans listed in the remote I/O section of an analysis report
+const MAX_REMOTE_IO_SCANS: usize = 10;
+
+/// TerminalReport renders analysis and comparison results as plain text
+pub struct TerminalReport;
+
+impl TerminalReport {
+    /// Render summary, conclusion, hotspots, critical path, task time, memory, shuffle, external scans, remote I/O, suggestions, score and parse warnings of an analysis
+    pub fn render_analysis(result: &ProfileAnalysisResponse) -> String {
+        let mut out = String::new();
+        
+        Self::heading(&mut out, "Doris Profile Analysis", '=');
+        if let Some(ref summary) = result.summary {
+            Self::field(&mut out, "Query ID", &summary.query_id);
+            if let Some(load_type) = summary.load_type {
+                Self::field(&mut out, "Load Type", &format!("{:?}", load_type));
+            }
+            Self::field(&mut out, "State", &summary.query_state);
+            Self::field(&mut out, "Total Time", &summary.total_time);
+            Self::field(&mut out, "Doris Version", &summary.doris_version);
+        }
+        Self::field(&mut out, "Score", &format!(
+            "{}/100 ({})",
+            result.performance_score,
+            OptimizationAdvisor::get_score_category(result.performance_score)
+        ));
+        
+        out.push('\n');
+        Self::heading(&mut out, "Conclusion", '-');
+        let _ = writeln!(out, "{}", result.conclusion);
+        
+        out.push('\n');
+        Self::heading(&mut out, &format!("Hotspots ({})", result.hotspots.len()), '-');
+        if result.hotspots.is_empty() {
+            let _ = writeln!(out, "No hotspots detected");
+        }
+        for (i, hotspot) in result.hotspots.iter().enumerate() {
+            let percentage = hotspot.time_percentage
+                .map(|p| format!(" {:.1}%", p))
+                .unwrap_or_default();
+            let _ = writeln!(
+                out, "{}. [{:?}] {}{}",
+                i + 1, hotspot.severity, hotspot.operator_name, percentage
+            );
+            Self::indented(&mut out, &hotspot.node_path, 3);
+            Self::indented(&mut out, &hotspot.description, 3);
+            if let Some(ref suggestion) = hotspot.suggestion {
+                Self::indented(&mut out, "Suggestion:", 3);
+                Self::indented(&mut out, suggestion, 5);
+            }
+        }
+        
+        if let Some(ref path) = result.critical_path {
+            out.push('\n');
+            Self::heading(&mut out, &format!("Critical Path ({:.3}ms)", path.latency_ns as f64 / 1_000_000.0), '-');
+            for (i, step) in path.steps.iter().enumerate() {
+                let _ = writeln!(
+                    out, "{}. {} {:.3}ms {:.1}% (exec {:.3}ms, wait {:.3}ms)",
+                    i + 1, step.operator_name,
+                    step.contribution_ns as f64 / 1_000_000.0, step.percentage,
+                    step.exec_time_ns as f64 / 1_000_000.0, step.wait_time_ns as f64 / 1_000_000.0
+                );
+            }
+        }
+        
+        if let Some(ref accounting) = result.time_accounting {
+            out.push('\n');
+            Self::heading(&mut out, "Task Time", '-');
+            for fragment in &accounting.fragments {
+                let time = &fragment.time;
+                let _ = writeln!(
+                    out, "{} ({} tasks): running {:.1}%, sink {:.1}%, waiting for worker {:.1}%, blocked {:.1}%",
+                    fragment.fragment_id, time.task_count,
+                    time.share(time.running_ns), time.share(time.sink_ns),
+                    time.share(time.wait_worker_ns), time.share(time.dependency_wait_ns)
+                );
+            }
+        }
+        
+        if let Some(memory) = result.memory.as_ref().filter(|m| !m.operators.is_empty()) {
+            let mb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+            out.push('\n');
+            Self::heading(&mut out, &format!("Memory (operator peaks {:.2} MB)", mb(memory.operator_peak_bytes)), '-');
+            if let Some(peak) = memory.query_peak_bytes {
+                Self::field(&mut out, "Query Peak", &format!("{:.2} MB", mb(peak)));
+            }
+            if let Some(failed) = memory.reserve_failed_times {
+                Self::field(&mut out, "Failed Reservations", &format!("{} of {}", failed, memory.reserve_times.unwrap_or(0)));
+            }
+            for (i, op) in memory.operators.iter().take(MAX_MEMORY_OPERATORS).enumerate() {
+                let components: Vec<String> = op.components.iter()
+                    .map(|c| format!("{} {:.2} MB", c.name, mb(c.bytes)))
+                    .collect();
+                let mut line = format!("{}. {} {:.2} MB", i + 1, op.operator_name, mb(op.peak_bytes));
+                if !components.is_empty() {
+                    let _ = write!(line, " ({})", components.join(", "));
+                }
+                if let Some(ref spill) = op.spill {
+                    let _ = write!(line, ", spilled {:.2} MB", mb(spill.write_bytes));
+                }
+                let _ = writeln!(out, "{}", line);
+            }
+        }
+        
+        if let Some(ref shuffle) = result.shuffle {
+            let mut edges: Vec<&ShuffleEdge> = shuffle.edges.iter().collect();
+            edges.sort_by_key(|e| std::cmp::Reverse(e.bytes_sent.unwrap_or(0)));
+            out.push('\n');
+            Self::heading(&mut out, &format!("Shuffle ({} edges)", edges.len()), '-');
+            for (i, edge) in edges.iter().take(MAX_SHUFFLE_EDGES).enumerate() {
+                let mut line = format!(
+                    "{}. {} -> {} (exchange {})",
+                    i + 1,
+                    edge.sender_fragment_id.as_deref().unwrap_or("?"),
+                    edge.receiver_fragment_id.as_deref().unwrap_or("?"),
+                    edge.exchange_id
+                );
+                if let Some(rows) = edge.rows {
+                    let _ = write!(line, ": {} rows", rows);
+                }
+                if let Some(bytes) = edge.bytes_sent {
+                    let _ = write!(line, ", {:.2} MB over RPC", bytes as f64 / (1024.0 * 1024.0));
+                }
+                if let Some(local) = edge.local_percentage {
+                    let _ = write!(line, ", {:.1}% local", local);
+                }
+                if let Some(ratio) = edge.compression_ratio {
+                    let _ = write!(line, ", compression {:.1}x", ratio);
+                }
+                if let (Some(avg), Some(max)) = (edge.rpc_avg_ns, edge.rpc_max_ns) {
+                    let _ = write!(line, ", RPC avg {:.3}ms max {:.3}ms", avg as f64 / 1_000_000.0, max as f64 / 1_000_000.0);
+                }
+                let _ = writeln!(out, "{}", line);
+            }
+        }
+        
+        if let Some(ref lakehouse) = result.lakehouse {
+            let mut scans: Vec<&LakehouseScan> = lakehouse.scans.iter().collect();
+            scans.sort_by_key(|s| std::cmp::Reverse(s.raw_rows_read.unwrap_or(0)));
+            out.push('\n');
+            Self::heading(&mut out, &format!("External Scans ({})", scans.len()), '-');
+            for (i, scan) in scans.iter().take(MAX_EXTERNAL_SCANS).enumerate() {
+                let mut line = format!("{}. {}", i + 1, scan.table_name.as_deref().unwrap_or(&scan.node_id));
+                if let Some(ref format) = scan.format {
+                    let _ = write!(line, " ({})", format);
+                }
+                if let Some(files) = scan.files {
+                    let _ = write!(line, ": {} files", files);
+                }
+                if let Some(rows) = scan.raw_rows_read {
+                    let _ = write!(line, ", {} rows read", rows);
+                }
+                if let Some(pruned) = scan.pruned_percentage {
+                    let _ = write!(line, ", {:.1}% pruned", pruned);
+                }
+                if let Some(hit) = scan.footer_hit_percentage {
+                    let _ = write!(line, ", footer cache {:.1}% hit", hit);
+                }
+                if let Some(deletes) = scan.delete_files.filter(|d| *d > 0) {
+                    let _ = write!(line, ", {} delete files", deletes);
+                }
+                let _ = writeln!(out, "{}", line);
+            }
+        }
+        
+        if let Some(ref remote_io) = result.remote_io {
+            let mut scans: Vec<&RemoteIoScan> = remote_io.scans.iter().collect();
+            scans.sort_by_key(|s| std::cmp::Reverse(s.get_requests));
+            out.push('\n');
+            Self::heading(&mut out, &format!("Remote I/O ({} scans)", scans.len()), '-');
+            for (i, scan) in scans.iter().take(MAX_REMOTE_IO_SCANS).enumerate() {
+                let mut line = format!(
+                    "{}. {}: {} GET requests",
+                    i + 1,
+                    scan.table_name.as_deref().unwrap_or(&scan.node_id),
+                    scan.get_requests
+                );
+                if let Some(rate) = scan.requests_per_second {
+                    let _ = write!(line, " ({:.1}/s)", rate);
+                }
+                if let Some(bytes) = scan.avg_request_bytes {
+                    let _ = write!(line, ", {:.2} MB avg", bytes as f64 / (1024.0 * 1024.0));
+                }
+                if let Some(ns) = scan.avg_request_time_ns {
+                    let _ = write!(line, ", {:.3}ms avg latency", ns as f64 / 1_000_000.0);
+                }
+                if let Some(throttled) = scan.throttled_requests.filter(|t| *t > 0) {
+                    let _ = write!(line, ", {} throttled", throttled);
+                    if let Some(pct) = scan.throttle_percentage {
+                        let _ = write!(line, " ({:.1}% of scanner time)", pct);
+                    }
+                }
+                let _ = writeln!(out, "{}", line);
+            }
+        }
+        
+        out.push('\n');
+        Self::heading(&mut out, &format!("Suggestions ({})", result.suggestions.len()), '-');
+        if result.suggestions.is_empty() {
+            let _ = writeln!(out, "No suggestions");
+        }
+        for (i, suggestion) in result.suggestions.iter().enumerate() {
+            let _ = writeln!(
+                out, "{}. [{:?}] {} ({:?})",
+                i + 1, suggestion.priority, suggestion.title, suggestion.category
+            );
+            Self::indented(&mut out, &suggestion.description, 3);
+        }
+        
+        if !result.parse_warnings.is_empty() {
+            out.push('\n');
+            Self::heading(&mut out, &format!("Parse Warnings ({})", result.parse_warnings.len()), '-');
+            Self::warnings(&mut out, &result.parse_warnings);
+        }
+        
+        out
+    }
+    
+    /// Render total time, operator changes and session variable changes of a comparison
+    pub fn render_comparison(comparison: &ProfileComparison) -> String {
+        let mut out = String::new();
+        
+        Self::heading(&mut out, "Doris Profile Comparison", '=');
+        Self::field(&mut out, "Before", &comparison.before_query_id);
+        Self::field(&mut out, "After", &comparison.after_query_id);
+        Self::field(&mut out, "Total Time", &Self::format_delta(&comparison.total_time, "ms"));
+        
+        out.push('\n');
+        Self::heading(&mut out, "Operator Changes", '-');
+        if comparison.node_diffs.is_empty() {
+            let _ = writeln!(out, "No matching operators");
+        }
+        for (i, diff) in comparison.node_diffs.iter().take(MAX_NODE_DIFFS).enumerate() {
+            let plan_id = diff.plan_node_id
+                .map(|id| format!(" (id={})", id))
+                .unwrap_or_default();
+            let _ = writeln!(out, "{}. {}{}", i + 1, diff.operator_name, plan_id);
+            Self::indented(&mut out, &format!("time: {}", Self::format_time_delta(&diff.time)), 3);
+            Self::indented(&mut out, &format!("rows: {}", Self::format_delta(&diff.rows, "")), 3);
+        }
+        if comparison.node_diffs.len() > MAX_NODE_DIFFS {
+            let _ = writeln!(out, "... {} more (use --json for all)", comparison.node_diffs.len() - MAX_NODE_DIFFS);
+        }
+        
+        for (title, operators) in [
+            ("Added Operators", &comparison.added_operators),
+            ("Removed Operators", &comparison.removed_operators),
+        ] {
+            if operators.is_empty() {
+                continue;
+            }
+            out.push('\n');
+            Self::heading(&mut out, title, '-');
+            for op in operators {
+                let plan_id = op.plan_node_id
+                    .map(|id| format!(" (id={})", id))
+                    .unwrap_or_default();
+                let _ = writeln!(out, "{}{}", op.operator_name, plan_id);
+            }
+        }
+        
+        if !comparison.session_variable_changes.is_empty() {
+            out.push('\n');
+            Self::heading(&mut out, "Session Variable Changes", '-');
+            for change in &comparison.session_variable_changes {
+                let _ = writeln!(
+                    out, "{}: {} -> {}",
+                    change.name,
+                    change.before.as_deref().unwrap_or("-"),
+                    change.after.as_deref().unwrap_or("-")
+                );
+            }
+        }
+        
+        for (title, warnings) in [
+            ("Before Parse Warnings", &comparison.before_parse_warnings),
+            ("After Parse Warnings", &comparison.after_parse_warnings),
+        ] {
+            if warnings.is_empty() {
+                continue;
+            }
+            out.push('\n');
+            Self::heading(&mut out, &format!("{} ({})", title, warnings.len()), '-');
+            Self::warnings(&mut out, warnings);
+        }
+        
+        out
+    }
+    
+    /// Render slowest queries, hotspot operators, score distribution and failures of a batch
+    pub fn render_batch(report: &BatchReport) -> String {
+        let mut out = String::new();
+        
+        Self::heading(&mut out, "Doris Profile Batch Analysis", '=');
+        Self::field(&mut out, "Files", &report.total_files.to_string());
+        Self::field(&mut out, "Analyzed", &report.analyzed_count.to_string());
+        Self::field(&mut out, "Failed", &report.failures.len().to_string());
+        
+        out.push('\n');
+        Self::heading(&mut out, "Slowest Queries", '-');
+        for (i, entry) in report.slowest_queries.iter().enumerate() {
+            let _ = writeln!(
+                out, "{}. {:.0}ms  score {}  {} ({})",
+                i + 1,
+                entry.total_time_ms.unwrap_or_default(),
+                entry.performance_score,
+                entry.query_id,
+                entry.file
+            );
+            if let Some(ref operator) = entry.top_hotspot {
+                Self::indented(&mut out, &format!("top hotspot: {}", operator), 3);
+            }
+        }
+        
+        out.push('\n');
+        Self::heading(&mut out, "Hotspot Operators", '-');
+        if report.hotspot_operators.is_empty() {
+            let _ = writeln!(out, "No hotspots detected");
+        }
+        for op in &report.hotspot_operators {
+            let _ = writeln!(
+                out, "{:<40} {} hotspots in {} queries",
+                op.operator_name, op.hotspot_count, op.query_count
+            );
+        }
+        
+        out.push('\n');
+        Self::heading(&mut out, "Score Distribution", '-');
+        for bucket in &report.score_distribution {
+            Self::field(&mut out, &bucket.category, &bucket.count.to_string());
+        }
+        
+        if !report.failures.is_empty() {
+            out.push('\n');
+            Self::heading(&mut out, "Failures", '-');
+            for failure in &report.failures {
+                let _ = writeln!(out, "{}: {}", failure.file, failure.error);
+            }
+        }
+        
+        out
+    }
+    
+    fn heading(out: &mut String, title: &str, underline: char) {
+        let _ = writeln!(out, "{}", title);
+        let _ = writeln!(out, "{}", underline.to_string().repeat(title.chars().count()));
+    }
+    
+    fn field(out: &mut String, name: &str, value: &str) {
+        let _ = writeln!(out, "{:<14} {}", format!("{}:", name), value);
+    }
+    
+    /// Write one "line:column [section] message" line per warning
+    fn warnings(out: &mut String, warnings: &[ParseWarning]) {
+        for warning in warnings {
+            let _ = writeln!(
+                out, "{}:{} [{}] {}",
+                warning.line, warning.column, warning.section, warning.message
+            );
+        }
+    }
+    
+    /// Write every line of `text` with the given indent
+    fn indented(out: &mut String, text: &str, indent: usize) {
+        for line in text.lines() {
+            let _ = writeln!(out, "{:indent$}{}", "", line, indent = indent);
+        }
+    }
+    
+    /// Format "before -> after (delta, percentage)"
+    fn format_delta(delta: &MetricDelta, unit: &str) -> String {
+        Self::format_change(delta, |v| format!("{:.0}{}", v, unit))
+    }
+    
+    /// Same as `format_delta` for nanosecond values, shown in ms
+    fn format_time_delta(delta: &MetricDelta) -> String {
+        Self::format_change(delta, |v| format!("{:.3}ms", v / 1_000_000.0))
+    }
+    
+    fn format_change(delta: &MetricDelta, value: impl Fn(f64) -> String) -> String {
+        let side = |v: Option<f64>| v.map(&value).unwrap_or_else(|| "-".to_string());
+        let mut change = format!("{} -> {}", side(delta.before), side(delta.after));
+        if let Some(d) = delta.delta {
+            let sign = if d > 0.0 { "+" } else { "" };
+            let _ = write!(change, " ({}{}", sign, value(d));
+            if let Some(p) = delta.delta_percentage {
+                let _ = write!(change, ", {}{:.1}%", sign, p);
+            }
+            change.push(')');
+        }
+        change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_render_analysis() {
+        let result = ProfileAnalysisResponse {
+            hotspots: vec![HotSpot {
+                node_id: "scan".to_string(),
+                node_path: "Fragment 0 > OLAP_SCAN_OPERATOR".to_string(),
+                operator_name: "OLAP_SCAN_OPERATOR".to_string(),
+                node_type: NodeType::OlapScan,
+                severity: HotspotSeverity::High,
+                description: "Scan takes 42.0% of query time".to_string(),
+                kind: HotspotKind::TimeConsuming,
+                time_percentage: Some(42.0),
+                suggestion: Some("Add a partition filter\nCheck the bucket key".to_string()),
+                suggestion_source: Some("default".to_string()),
+            }],
+            conclusion: "Query is slow".to_string(),
+            suggestions: vec![],
+            performance_score: 72,
+            execution_tree: None,
+            summary: Some(ProfileSummary {
+                query_id: "q1".to_string(),
+                total_time: "3sec".to_string(),
+                ..Default::default()
+            }),
+            physical_plan: None,
+            runtime_filters: Vec::new(),
+            parse_warnings: vec![ParseWarning {
+                line: 120,
+                column: 12,
+                section: "MergedProfile".to_string(),
+                message: "MergedProfile ends at \"Pipeline 0(instance_num=1):\" without counters; the profile looks truncated".to_string(),
+            }],
+            critical_path: Some(CriticalPath {
+                latency_ns: 2_500_000_000,
+                steps: vec![CriticalPathStep {
+                    node_id: "scan".to_string(),
+                    operator_name: "OLAP_SCAN_OPERATOR".to_string(),
+                    node_type: NodeType::OlapScan,
+                    fragment_id: None,
+                    pipeline_id: None,
+                    exec_time_ns: 500_000_000,
+                    wait_time_ns: 2_000_000_000,
+                    contribution_ns: 2_500_000_000,
+                    percentage: 83.3,
+                }],
+            }),
+            time_accounting: Some(TimeAccounting {
+                from_detail_profile: true,
+                pipelines: vec![],
+                fragments: vec![FragmentTime {
+                    fragment_id: "Fragment 1".to_string(),
+                    time: TimeBreakdown {
+                        task_count: 4,
+                        running_ns: 600,
+                        sink_ns: 100,
+                        wait_worker_ns: 200,
+                        dependency_wait_ns: 100,
+                        ..Default::default()
+                    },
+                }],
+            }),
+            memory: Some(MemoryBreakdown {
+                query_peak_bytes: None,
+                mem_limit_bytes: None,
+                operator_peak_bytes: 3 * 1024 * 1024,
+                reserve_times: Some(40),
+                reserve_failed_times: Some(2),
+                operators: vec![OperatorMemory {
+                    node_id: "1".to_string(),
+                    operator_name: "HASH_JOIN_SINK_OPERATOR".to_string(),
+                    node_type: NodeType::HashJoinBuild,
+                    fragment_id: None,
+                    pipeline_id: None,
+                    peak_bytes: 3 * 1024 * 1024,
+                    max_instance_peak_bytes: 1024 * 1024,
+                    components: vec![MemoryComponent { name: "HashTable".to_string(), bytes: 2 * 1024 * 1024 }],
+                    spill: Some(SpillStats { write_bytes: 512 * 1024, ..Default::default() }),
+                }],
+            }),
+            shuffle: Some(ShuffleModel {
+                edges: vec![ShuffleEdge {
+                    exchange_id: 25,
+                    sender_fragment_id: Some("Fragment 1".to_string()),
+                    receiver_fragment_id: Some("Fragment 0".to_string()),
+                    rows: Some(48),
+                    bytes_sent: Some(3 * 1024 * 1024),
+                    local_bytes_sent: Some(1024 * 1024),
+                    local_percentage: Some(25.0),
+                    compression_ratio: Some(2.0),
+                    rpc_avg_ns: Some(1_500_000),
+                    rpc_max_ns: Some(20_000_000),
+                    ..Default::default()
+                }],
+            }),
+            lakehouse: Some(LakehouseScanModel {
+                scans: vec![LakehouseScan {
+                    node_id: "Fragment 1-Pipeline 4-20".to_string(),
+                    table_name: Some("web_sales".to_string()),
+                    format: Some("parquet".to_string()),
+                    files: Some(648),
+                    raw_rows_read: Some(720_000_376),
+                    pruned_percentage: Some(12.5),
+                    footer_hit_percentage: Some(100.0),
+                    delete_files: Some(3),
+                    ..Default::default()
+                }],
+            }),
+            remote_io: Some(RemoteIoModel {
+                scans: vec![RemoteIoScan {
+                    node_id: "Fragment 1-Pipeline 4-20".to_string(),
+                    table_name: Some("web_sales".to_string()),
+                    get_requests: 1300,
+                    requests_per_second: Some(650.0),
+                    avg_request_bytes: Some(2 * 1024 * 1024),
+                    avg_request_time_ns: Some(40_000_000),
+                    throttled_requests: Some(25),
+                    throttle_percentage: Some(12.5),
+                    ..Default::default()
+                }],
+            }),
+        };
+        
+        let report = TerminalReport::render_analysis(&result);
+        assert!(report.contains("Query ID:      q1"));
+        assert!(report.contains("Score:         72/100"));
+        assert!(report.contains("1. [High] OLAP_SCAN_OPERATOR 42.0%"));
+        assert!(report.contains("     Add a partition filter\n     Check the bucket key\n"));
+        assert!(report.contains("Critical Path (2500.000ms)"));
+        assert!(report.contains("1. OLAP_SCAN_OPERATOR 2500.000ms 83.3% (exec 500.000ms, wait 2000.000ms)"));
+        assert!(report.contains("Fragment 1 (4 tasks): running 60.0%, sink 10.0%, waiting for worker 20.0%, blocked 10.0%"));
+        assert!(report.contains("Failed Reservations: 2 of 40"));
+        assert!(report.contains("1. HASH_JOIN_SINK_OPERATOR 3.00 MB (HashTable 2.00 MB), spilled 0.50 MB"));
+        assert!(report.contains("1. Fragment 1 -> Fragment 0 (exchange 25): 48 rows, 3.00 MB over RPC, 25.0% local, compression 2.0x, RPC avg 1.500ms max 20.000ms"));
+        assert!(report.contains("1. web_sales (parquet): 648 files, 720000376 rows read, 12.5% pruned, footer cache 100.0% hit, 3 delete files"));
+        assert!(report.contains("1. web_sales: 1300 GET requests (650.0/s), 2.00 MB avg, 40.000ms avg latency, 25 throttled (12.5% of scanner time)"));
+        assert!(report.contains("No suggestions"));
+        assert!(report.contains("Parse Warnings (1)"));
+        assert!(report.contains("120:12 [MergedProfile] MergedProfile ends at"));
+    }
+    
+    #[test]
+    fn test_format_time_delta() {
+        let delta = MetricDelta {
+            before: Some(2_000_000.0),
+            after: Some(1_000_000.0),
+            delta: Some(-1_000_000.0),
+            delta_percentage: Some(-50.0),
+        };
+        assert_eq!(
+            TerminalReport::format_time_delta(&delta),
+            "2.000ms -> 1.000ms (-1.000ms, -50.0%)"
+        );
+    }
+}
@@ -10,6 +10,10 @@ export default createStore({
     error: null,
     // Profile text
     profileText: "",
+    // Every profile found in an uploaded file: { source, success, error, data }
+    uploadedProfiles: [],
+    // Index of the uploaded profile shown
+    selectedProfile: 0,
   },
 
   getters: {
@@ -21,6 +25,8 @@ export default createStore({
     performanceScore: (state) => state.analysisResult?.performance_score || 0,
     conclusion: (state) => state.analysisResult?.conclusion || "",
     profileText: (state) => state.profileText,
+    uploadedProfiles: (state) => state.uploadedProfiles,
+    failedProfiles: (state) => state.uploadedProfiles.filter((item) => !item.success),
   },
 
   mutations: {
@@ -36,10 +42,19 @@ export default createStore({
     SET_PROFILE_TEXT(state, text) {
       state.profileText = text;
     },
+    SET_UPLOADED_PROFILES(state, profiles) {
+      state.uploadedProfiles = profiles;
+    },
+    SELECT_PROFILE(state, index) {
+      state.selectedProfile = index;
+      state.analysisResult = state.uploadedProfiles[index].data;
+    },
     CLEAR_RESULT(state) {
       state.analysisResult = null;
       state.error = null;
       state.profileText = "";
+      state.uploadedProfiles = [];
+      state.selectedProfile = 0;
     },
     UPDATE_HOTSPOT(state, updatedHotspot) {
       if (state.analysisResult && state.analysisResult.hotspots) {
@@ -58,6 +73,7 @@ export default createStore({
       commit("SET_LOADING", true);
       commit("SET_ERROR", null);
       commit("SET_PROFILE_TEXT", profileText);
+      commit("SET_UPLOADED_PROFILES", []);
 
       try {
         const response = await fetch("/api/analyze", {
@@ -87,10 +103,6 @@ export default createStore({
       commit("SET_ERROR", null);
 
       try {
-        // Read file content to store it
-        const fileContent = await file.text();
-        commit("SET_PROFILE_TEXT", fileContent);
-        
         const formData = new FormData();
         formData.append("file", file);
 
@@ -101,10 +113,20 @@ export default createStore({
 
         const data = await response.json();
 
-        if (data.success) {
-          commit("SET_ANALYSIS_RESULT", data.data);
+        // The file may hold several (compressed) profiles; keep them all and show
+        // the first analyzed one, the others can be picked from the result page
+        const first = data.success ? data.data.findIndex((item) => item.success) : -1;
+        if (first !== -1) {
+          // Profile texts are not sent back; node diagnosis needs the text, which is
+          // only available here for an uncompressed file holding a single profile
+          const text = data.data.length === 1 ? await file.text() : "";
+          const isProfile = /^\s*(Summary|Query):/.test(text);
+          commit("SET_PROFILE_TEXT", isProfile ? text : "");
+          commit("SET_UPLOADED_PROFILES", data.data);
+          commit("SELECT_PROFILE", first);
         } else {
-          commit("SET_ERROR", data.error || "Failed to analyze profile");
+          const failed = data.data && data.data.find((item) => item.error);
+          commit("SET_ERROR", (failed && failed.error) || data.error || "Failed to analyze profile");
         }
       } catch (error) {
         commit("SET_ERROR", error.message || "Network error");
@@ -113,6 +135,10 @@ export default createStore({
       }
     },
 
+    selectProfile({ commit }, index) {
+      commit("SELECT_PROFILE", index);
+    },
+
     clearResult({ commit }) {
       commit("CLEAR_RESULT");
     },